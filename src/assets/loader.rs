@@ -14,7 +14,13 @@ struct K8SAsset;
 pub enum K8SAssetType {
     Controller,
     Service,
-    Ingress
+    Ingress,
+    VolumeClaim,
+    Job,
+    CronJob,
+    Pod,
+    ConfigMap,
+    Secret
 }
 
 impl K8SAssetType {
@@ -22,7 +28,13 @@ impl K8SAssetType {
         match self {
             K8SAssetType::Controller => "controller_tmpl.yaml",
             K8SAssetType::Service    => "service_tmpl.yaml",
-            K8SAssetType::Ingress    => "ingress_tmpl.yaml"
+            K8SAssetType::Ingress    => "ingress_tmpl.yaml",
+            K8SAssetType::VolumeClaim => "pvc_tmpl.yaml",
+            K8SAssetType::Job        => "job_tmpl.yaml",
+            K8SAssetType::CronJob    => "cronjob_tmpl.yaml",
+            K8SAssetType::Pod        => "pod_tmpl.yaml",
+            K8SAssetType::ConfigMap  => "configmap_tmpl.yaml",
+            K8SAssetType::Secret     => "secret_tmpl.yaml"
         }
     }
 }
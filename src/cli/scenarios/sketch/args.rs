@@ -1,5 +1,14 @@
+use std::env;
 use crate::cli::scenarios::scenes::picker::EnumHelper;
 
+/// Constant
+pub const ENV_FILE_OPTION: &str = "--env-file";
+pub const FILE_OPTIONS: [&str; 2] = ["-f", "--file"];
+pub const PROFILE_OPTION: &str = "--profile";
+pub const PROFILES_ENV: &str = "COMPOSE_PROFILES";
+// options followed by a value
+const VALUED_OPTIONS: [&str; 4] = [ENV_FILE_OPTION, FILE_OPTIONS[0], FILE_OPTIONS[1], PROFILE_OPTION];
+
 /// Generate Options
 ///
 /// # Description
@@ -30,12 +39,118 @@ impl EnumHelper<GenerateOptions> for GenerateOptions {
 /// # Return
 /// Option<GenerateOptions>
 pub fn retrieve_cmd_options(options: &[String]) -> Option<GenerateOptions> {
-    let opt = match retrieve_options_by_idx(options, 0) {
-        Some(p) => p,
-        None => String::new()
-    };
+    options
+        .iter()
+        .find_map(|opt| GenerateOptions::from_string(opt))
+}
+
+/// Retrieve Option Value
+///
+/// # Description
+/// Retrieve the value of an option written as `--option value` or `--option=value`
+///
+/// # Arguments
+/// * `options` &[String]
+/// * `name` &str name of the option (e.g: --env-file)
+///
+/// # Return
+/// Option<String>
+pub fn retrieve_option_value(options: &[String], name: &str) -> Option<String> {
+    retrieve_option_values(options, &[name]).into_iter().next()
+}
+
+/// Retrieve Option Values
+///
+/// # Description
+/// Retrieve every values of a repeated option (e.g: -f a.yaml -f b.yaml) in the order of the command
+///
+/// # Arguments
+/// * `options` &[String]
+/// * `names` &[&str] names of the option (e.g: -f, --file)
+///
+/// # Return
+/// Vec<String>
+pub fn retrieve_option_values(options: &[String], names: &[&str]) -> Vec<String> {
+    let mut values = Vec::new();
+    for (idx, opt) in options.iter().enumerate() {
+        if names.contains(&opt.as_str()) {
+            if let Some(value) = retrieve_options_by_idx(options, idx + 1) {
+                values.push(value);
+            }
+
+            continue;
+        }
+
+        let value = names
+            .iter()
+            .find_map(|name| {
+                let prefix = format!("{}=", name);
+                match opt.starts_with(&prefix) {
+                    true => Some(&opt[prefix.len()..]),
+                    false => None
+                }
+            });
+
+        if let Some(v) = value {
+            values.push(String::from(v));
+        }
+    }
+
+    values
+}
+
+/// Retrieve Profiles
+///
+/// # Description
+/// Retrieve the active profiles from the `--profile` options. The comma separated
+/// `COMPOSE_PROFILES` environment variable is used when no option is given
+///
+/// # Arguments
+/// * `options` &[String]
+///
+/// # Return
+/// Vec<String>
+pub fn retrieve_profiles(options: &[String]) -> Vec<String> {
+    let profiles = retrieve_option_values(options, &[PROFILE_OPTION]);
+    if !profiles.is_empty() {
+        return profiles;
+    }
+
+    env::var(PROFILES_ENV)
+        .map(|v| v
+            .split(',')
+            .map(|p| p.trim().to_owned())
+            .filter(|p| !p.is_empty())
+            .collect()
+        )
+        .unwrap_or_default()
+}
+
+/// Retrieve Positional Values
+///
+/// # Description
+/// Retrieve the arguments which are neither an option nor the value of an option (e.g: names of services)
+///
+/// # Arguments
+/// * `options` &[String]
+///
+/// # Return
+/// Vec<String>
+pub fn retrieve_positional_values(options: &[String]) -> Vec<String> {
+    options
+        .iter()
+        .enumerate()
+        .filter(|(idx, opt)| {
+            let is_value = idx
+                .checked_sub(1)
+                .and_then(|prev| options.get(prev))
+                .map(|prev| VALUED_OPTIONS.contains(&prev.as_str()))
+                .unwrap_or(false);
 
-    GenerateOptions::from_string(&opt)
+            !opt.starts_with('-') && !is_value
+        })
+        .map(|(_, opt)| opt.to_owned())
+        .collect()
 }
 
 /// Retrieve options by idx
@@ -1,5 +1,14 @@
-use super::args::{GenerateOptions, retrieve_cmd_options};
-use crate::docker::{loader, parser};
+use super::args::{
+    GenerateOptions,
+    ENV_FILE_OPTION,
+    FILE_OPTIONS,
+    retrieve_cmd_options,
+    retrieve_option_value,
+    retrieve_option_values,
+    retrieve_positional_values,
+    retrieve_profiles
+};
+use crate::docker::{loader, parser, environment, secret, selection};
 use crate::core::logger::{log, LogType};
 use crate::kubernetes::builder;
 use crate::kubernetes::io::{
@@ -11,37 +20,74 @@ use crate::kubernetes::io::{
 use crate::confiture::config;
 use crate::confiture::config::{Confiture, ConfigIngress};
 use crate::docker::parser::DockerService;
+use crate::docker::volume::{DockerNamedVolume, resolve_binds};
+use crate::docker::secret::DockerFileObject;
+use crate::kubernetes::controllers::volume::KubeVolumeClaim;
+use crate::kubernetes::controllers::secret::{KubeFileObject, get_file_objects};
+use crate::docker::secret::FileKind;
 use crate::core::errors::cli_error::{CliErr, ErrHelper, ErrMessage};
 use crate::core::errors::message::cli::{
     GET_DOCKER_SERVICE_LIST,
     GET_CONFITURE,
     GENERATE_ERROR,
-    INGRESS_CONFIG
+    INGRESS_CONFIG,
+    PRIVILEGED_SERVICE
 };
 
-/// Constant referring to the compose file which need to be parse
-const COMPOSE_FILE_NAME: &str = "docker-compose.yaml";
+/// Content of the compose project (confiture, services, named volumes, secrets & configs)
+type Project = (config::Confiture, Vec<DockerService>, Vec<DockerNamedVolume>, Vec<DockerFileObject>);
 
 /// Launch
 ///
 /// # Description
 /// Launch the generate scenario with the command below
 /// capoomobi generate <path_to_docker-compose.yaml>
-/// e.g: capoomobi generate ./example
+/// e.g: capoomobi generate ./example --env-file ./prod.env
+/// e.g: capoomobi generate ./example -f docker-compose.yaml -f docker-compose.prod.yaml
+/// e.g: capoomobi generate ./example --profile debug
+/// e.g: capoomobi generate ./example web mysql
 ///
 /// # Arguments
 /// * `sub_action`: slice of string representing the path
 pub fn launch(sub_action: &str, options: &[String]) {
     // Retrieve the kubernetes array which describe every services
-    let config = prepare(sub_action);
+    let config = prepare(sub_action, options);
     if config.is_none() {
         CliErr::new(GET_CONFITURE, "", ErrMessage::NotFound).log_pretty();
         return;
     }
 
     let args = retrieve_cmd_options(options);
-    let (confiture, docker) = config.unwrap();
-    execute_with_options(docker, confiture, args);
+    let (confiture, docker, volumes, files) = config.unwrap();
+    if let Err(e) = check_privileged_services(&docker, &confiture) {
+        e.log_pretty();
+        return;
+    }
+
+    execute_with_options(docker, volumes, files, confiture, args);
+}
+
+/// Check Privileged Services
+///
+/// # Description
+/// Refuse the services which are privileged or which share a namespace with the host
+/// unless they are allowed by the `allow_privileged` field of the confiture
+///
+/// # Arguments
+/// * `dk` &[DockerService]
+/// * `conf` &Confiture
+///
+/// # Return
+/// Result<(), CliErr>
+fn check_privileged_services(dk: &[DockerService], conf: &Confiture) -> Result<(), CliErr> {
+    let refused = dk
+        .iter()
+        .find(|svc| svc.security.is_privileged() && !conf.is_privileged_allowed(&svc.name));
+
+    match refused {
+        Some(svc) => Err(CliErr::new(PRIVILEGED_SERVICE, &svc.name, ErrMessage::PolicyError)),
+        None => Ok(())
+    }
 }
 
 /// Execute With Options
@@ -51,18 +97,35 @@ pub fn launch(sub_action: &str, options: &[String]) {
 ///
 /// # Arguments
 /// * `dk` DockerService
+/// * `volumes` Vec<DockerNamedVolume>
+/// * `files` Vec<DockerFileObject>
 /// * `options` args::GenerateOptions
-fn execute_with_options(dk: Vec<DockerService>, conf: Confiture, options: Option<GenerateOptions>) {
+fn execute_with_options(dk: Vec<DockerService>, volumes: Vec<DockerNamedVolume>, files: Vec<DockerFileObject>, conf: Confiture, options: Option<GenerateOptions>) {
     let map = conf.get_config_confiture_map();
-    let kube_objects = builder::get_basic_objects(&dk, map);
+    let patterns = conf.get_sensitive_patterns();
+    let kube_objects = builder::get_basic_objects(&dk, map, &conf.volumes, &patterns);
+    if let Err(e) = builder::check_selectors(&kube_objects) {
+        e.log_pretty();
+        return;
+    }
+
+    let claims = builder::get_volume_claim_objects(&dk, &kube_objects, &volumes, &conf.volumes);
+    let project = dk.first().map(|s| s.project.as_str()).unwrap_or_default();
+    let mut file_objects = get_file_objects(&files, project);
+    // ConfigMaps of the bind mounts
+    file_objects.extend(kube_objects.iter().flat_map(|k| k.mounts.clone()));
 
     if options.is_none() {
-        create_kubes_files(kube_objects);
+        create_kubes_files(kube_objects, claims, file_objects);
         return;
     }
 
     match options.unwrap() {
-        GenerateOptions::Print => display::render_kubes_objects(kube_objects),
+        GenerateOptions::Print => {
+            display::render_kubes_objects(kube_objects);
+            display::render_volume_claims(claims);
+            display::render_file_objects(file_objects);
+        },
         GenerateOptions::Ingress => create_ingress_file(&dk, conf.ingress)
     }
 }
@@ -74,12 +137,23 @@ fn execute_with_options(dk: Vec<DockerService>, conf: Confiture, options: Option
 ///
 /// # Arguments
 /// * `path` &str
+/// * `options` &[String] options of the command (compose files, env file, profiles & services)
 ///
 /// # Return
-/// Option<Vec<Kube>>
-fn prepare(path: &str) -> Option<(config::Confiture, Vec<DockerService>)> {
-    // get the yaml builder
-    let yaml_content = match loader::load(path, COMPOSE_FILE_NAME) {
+/// Option<Project>
+fn prepare(path: &str, options: &[String]) -> Option<Project> {
+    let env_file = retrieve_option_value(options, ENV_FILE_OPTION);
+    let env_file = env_file.as_deref();
+    // the compose file of the folder is detected when no file is given
+    let files = retrieve_option_values(options, &FILE_OPTIONS);
+    let compose_files = if files.is_empty() {
+        loader::get_compose_files(path)
+    } else {
+        Ok(files.to_vec())
+    };
+
+    // get the yaml builder of the merged compose files
+    let yaml_content = match compose_files.and_then(|f| loader::load_files(path, &f, env_file)) {
         Ok(content) => content,
         Err(e) => {
             e.log_pretty();
@@ -87,8 +161,17 @@ fn prepare(path: &str) -> Option<(config::Confiture, Vec<DockerService>)> {
         }
     };
 
+    // get the top-level named volumes
+    let docker_volumes = parser::get_docker_volumes(&yaml_content);
+
+    // get the top-level secrets & configs
+    let docker_files = parser::get_docker_files(&yaml_content);
+
+    // get the name of the compose project
+    let project = parser::get_project_name(&yaml_content, path);
+
     // get a vector of docker services
-    let docker_svc = match parser::get_docker_services(yaml_content) {
+    let mut docker_svc = match parser::get_docker_services(yaml_content) {
         Some(vector) => vector,
         None => {
             CliErr::new(GET_DOCKER_SERVICE_LIST, "", ErrMessage::ParsingError).log_pretty();
@@ -96,11 +179,42 @@ fn prepare(path: &str) -> Option<(config::Confiture, Vec<DockerService>)> {
         }
     };
 
-    // load the configuration file
-    let conf_opts = config::load(path);
-    conf_opts.as_ref()?;
+    for svc in docker_svc.iter_mut() {
+        svc.project = project.clone();
+    }
+
+    // keep the services enabled by the profiles or listed in the command
+    let profiles = retrieve_profiles(options);
+    let names = retrieve_positional_values(options);
+    let mut docker_svc = match selection::select_services(docker_svc, &profiles, &names) {
+        Ok(services) => services,
+        Err(e) => {
+            e.log_pretty();
+            return None;
+        }
+    };
+
+    // load the configuration file & drop the services excluded by the confiture
+    let conf_opts = config::load(path, &docker_svc)?;
+    docker_svc.retain(|s| !conf_opts.is_skipped(&s.name));
+
+    // load the content of the secrets & configs used by the selected services
+    let mut docker_files = selection::select_files(docker_files, &docker_svc);
+    if let Err(e) = secret::load_file_objects(&mut docker_files, path) {
+        e.log_pretty();
+        return None;
+    }
 
-    Some((conf_opts.unwrap(), docker_svc))
+    // resolve the sources of the bind mounts & load the files which could be stored in a ConfigMap
+    resolve_binds(&mut docker_svc, path);
+
+    // merge the env files within the environment of the services
+    if let Err(e) = environment::load_env_files(&mut docker_svc, path) {
+        e.log_pretty();
+        return None;
+    }
+
+    Some((conf_opts, docker_svc, docker_volumes, docker_files))
 }
 
 /// Create Kubes Files
@@ -110,8 +224,28 @@ fn prepare(path: &str) -> Option<(config::Confiture, Vec<DockerService>)> {
 ///
 /// # Arguments
 /// * `kubes` Vec<Kube>
-fn create_kubes_files(kubes: Vec<builder::Kube>) {
-    let res = folder::create(&kubes).and_then(|_| runner::create_default_object(kubes));
+/// * `claims` Vec<KubeVolumeClaim>
+/// * `files` Vec<KubeFileObject>
+fn create_kubes_files(kubes: Vec<builder::Kube>, claims: Vec<KubeVolumeClaim>, files: Vec<KubeFileObject>) {
+    let res = folder::create(&kubes)
+        .and_then(|_| runner::create_default_object(kubes))
+        .and_then(|_| {
+            claims.into_iter().try_for_each(|c| {
+                let filename = c.get_filename();
+                objects::create(c, &filename, objects::Objects::VolumeClaim)
+            })
+        })
+        .and_then(|_| {
+            files.into_iter().try_for_each(|f| {
+                let filename = f.get_filename();
+                let kind = match f.kind {
+                    FileKind::Secret => objects::Objects::Secret,
+                    FileKind::Config => objects::Objects::ConfigMap
+                };
+
+                objects::create(f, &filename, kind)
+            })
+        });
     match res {
         Ok(()) => log(LogType::Success, "Successfully creating the Kubernetes files", None),
         Err(()) => CliErr::new(GENERATE_ERROR, "", ErrMessage::IOError).log_pretty()
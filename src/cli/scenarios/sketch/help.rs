@@ -48,12 +48,17 @@ fn describe_generate() {
     log_help(
         HelpLogType::Action,
         format!(
-            "{}\n{}\n",
+            "{}\n{}\n{}\n{}\n{}\n{}\n",
             "- --print: Print the manifests that will be write to the project",
-            "- --ingress: Generate an ingress file based on the docker-compose.yaml & confiture.json"
+            "- --ingress: Generate an ingress file based on the docker-compose.yaml & confiture.json",
+            "- --env-file <path>: Interpolate the docker-compose.yaml with the env file instead of the .env file",
+            "- -f, --file <file>: Compose file to use, repeat the option to merge several files",
+            "- --profile <name>: Enable the services of a profile, repeat the option to enable several profiles",
+            "- <service...>: Generate only the listed services and the services they depends on"
         )
     );
     log_help(HelpLogType::Example, "capoomobi generate /workspace/my-docker-project".to_owned());
+    log_help(HelpLogType::Example, "capoomobi generate /workspace/my-docker-project --profile debug web".to_owned());
 }
 
 /// Describe Project
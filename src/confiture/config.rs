@@ -5,36 +5,115 @@
 use std::path::PathBuf;
 use std::collections::HashMap;
 use serde::{Deserialize};
-use serde_json;
+use serde_json::{self, Map, Value};
 use crate::core::fs::toolbox::{get_absolute_path, open_file};
 use crate::core::logger::{log, LogType};
-use crate::kubernetes::controllers::controller::ControllerKind;
+use crate::kubernetes::controllers::controller::{ControllerKind, PodManagementPolicy, UpdateStrategy, Toleration};
 use crate::kubernetes::controllers::service::ServiceType;
+use crate::kubernetes::controllers::volume::AccessMode;
+use crate::kubernetes::controllers::mount::ConfigMount;
+use crate::kubernetes::controllers::rollout::DeploymentStrategy;
+use crate::kubernetes::controllers::placement::{PodAntiAffinity, TopologySpreadConstraint};
+use crate::kubernetes::controllers::job::ConcurrencyPolicy;
+use crate::kubernetes::controllers::probe::ProbeKind;
+use crate::kubernetes::controllers::resources::KubeResources;
+use crate::docker::parser::DockerService;
+use crate::docker::port::Protocol;
 
 /// Constant
 const CONFITURE_FILE_NAME: &str = "./confiture.json";
+const DEFAULTS_FIELD: &str = "defaults";
+const SENSITIVE_PATTERNS: [&str; 5] = ["*_PASSWORD", "*_PASS", "*_TOKEN", "*_SECRET", "*_KEY"];
 
 /// Config Deployment structure
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, Default)]
 pub struct ConfigDeployment {
-    pub replicas: u8,
-    pub controller: ControllerKind
+    // override the deploy.replicas of the service
+    pub replicas: Option<u32>,
+    pub controller: Option<ControllerKind>,
+    pub pod_management_policy: Option<PodManagementPolicy>,
+    pub update_strategy: Option<UpdateStrategy>,
+    // only used by a Deployment
+    pub strategy: Option<DeploymentStrategy>,
+    #[serde(default)]
+    pub tolerations: Vec<Toleration>,
+    #[serde(default)]
+    pub topology_spread_constraints: Vec<TopologySpreadConstraint>,
+    pub anti_affinity: Option<PodAntiAffinity>,
+    pub job: Option<ConfigJob>,
+    pub probes: Option<ConfigProbes>,
+    pub resources: Option<KubeResources>,
+    pub wait_for_dependencies: Option<bool>,
+    // replace the cluster DNS by the dns of the service (dnsPolicy: None)
+    pub replace_cluster_dns: Option<bool>,
+    #[serde(default)]
+    pub mounts: Vec<ConfigMount>,
+    // key of the external secrets & configs by the name of their compose file
+    #[serde(default)]
+    pub file_keys: HashMap<String, String>
 }
 
-/// Config Service structure
+/// Config Job structure
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ConfigJob {
+    pub schedule: Option<String>,
+    pub backoff_limit: Option<u32>,
+    pub completions: Option<u32>,
+    pub parallelism: Option<u32>,
+    pub concurrency_policy: Option<ConcurrencyPolicy>
+}
+
+/// Config Probes structure
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ConfigProbes {
+    pub kinds: Option<Vec<ProbeKind>>,
+    pub initial_delay_seconds: Option<u32>,
+    pub period_seconds: Option<u32>,
+    pub timeout_seconds: Option<u32>,
+    pub failure_threshold: Option<u32>,
+    pub success_threshold: Option<u32>
+}
+
+/// Config Service Port structure
 #[derive(Deserialize, Debug, Clone)]
+pub struct ConfigServicePort {
+    pub port: u16,
+    pub nodeport: u16,
+    #[serde(default)]
+    pub protocol: Protocol
+}
+
+/// Config Service structure
+#[derive(Deserialize, Debug, Clone, Default)]
 pub struct ConfigService {
+    #[serde(default)]
     pub kind: ServiceType,
     #[serde(default)]
-    pub nodeport: u16
+    pub nodeport: u16,
+    #[serde(default)]
+    pub ports: Vec<ConfigServicePort>
 }
 
 /// Config structure
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, Default)]
 pub struct ConfigConfiture {
+    #[serde(default)]
     pub deployment: ConfigDeployment,
+    #[serde(default)]
     pub service: ConfigService,
-    pub name: String
+    pub name: String,
+    // exclude the service from the generation
+    #[serde(default)]
+    pub skip: bool
+}
+
+/// Config Volume structure
+#[derive(Deserialize, Debug, Clone)]
+pub struct ConfigVolume {
+    pub name: String,
+    pub size: Option<String>,
+    pub storage_class: Option<String>,
+    pub access_mode: Option<AccessMode>
 }
 
 /// Ingress Service
@@ -54,8 +133,15 @@ pub struct ConfigIngress {
 /// Confiture
 #[derive(Deserialize, Debug)]
 pub struct Confiture {
+    #[serde(default)]
     confitures: Vec<ConfigConfiture>,
-    pub ingress: Option<ConfigIngress>
+    pub ingress: Option<ConfigIngress>,
+    #[serde(default)]
+    pub volumes: Vec<ConfigVolume>,
+    sensitive_patterns: Option<Vec<String>>,
+    // services allowed to be privileged or to share a namespace with the host
+    #[serde(default)]
+    allow_privileged: Vec<String>
 }
 
 impl Confiture {
@@ -77,6 +163,49 @@ impl Confiture {
 
         map
     }
+
+    /// Get Sensitive Patterns
+    ///
+    /// # Description
+    /// Retrieve the patterns of the environment variables stored in a Secret. Default patterns
+    /// are used when the confiture doesn't define any
+    ///
+    /// # Return
+    /// Vec<String>
+    pub fn get_sensitive_patterns(&self) -> Vec<String> {
+        match &self.sensitive_patterns {
+            Some(patterns) => patterns.clone(),
+            None => SENSITIVE_PATTERNS.iter().map(|p| String::from(*p)).collect()
+        }
+    }
+
+    /// Is Privileged Allowed
+    ///
+    /// # Description
+    /// Check whether a service is allowed to be privileged or to share a namespace with the host
+    ///
+    /// # Arguments
+    /// * `name` &str name of the service
+    ///
+    /// # Return
+    /// bool
+    pub fn is_privileged_allowed(&self, name: &str) -> bool {
+        self.allow_privileged.iter().any(|n| n == name)
+    }
+
+    /// Is Skipped
+    ///
+    /// # Description
+    /// Check whether a service is excluded from the generation
+    ///
+    /// # Arguments
+    /// * `name` &str name of the service
+    ///
+    /// # Return
+    /// bool
+    pub fn is_skipped(&self, name: &str) -> bool {
+        self.confitures.iter().any(|c| c.name == name && c.skip)
+    }
 }
 
 /// Retrieve File Path
@@ -108,16 +237,30 @@ fn retrieve_file_path(path: String, folder: &str) -> PathBuf {
 /// Load
 ///
 /// # Description
-/// Load the configuration file and retrieve it's contents
+/// Load the configuration file and retrieve it's contents. The confiture of a service is built from
+/// the `defaults` block, then the `x-capoomobi` field of the service and finally the entry of the
+/// confiture.json which takes precedence. A service without any entry uses the defaults, as does every
+/// service of a project without confiture.json
 ///
 /// # Param
 /// * `target_folder` &str
+/// * `services` &[DockerService]
 ///
 /// # Return
 /// Option<Confiture>
-pub fn load(target_folder: &str) -> Option<Confiture> {
+pub fn load(target_folder: &str, services: &[DockerService]) -> Option<Confiture> {
     let p = retrieve_file_path(String::new(), target_folder);
-    let content = match open_file(&p) {
+    let mut raw = match p.is_file() {
+        true => read_file(&p)?,
+        false => {
+            log(LogType::Info, "No confiture.json found, the services are generated with the default settings", None);
+            Value::Object(Map::new())
+        }
+    };
+
+    merge_extensions(&mut raw, services);
+    apply_defaults(&mut raw, services);
+    let confiture: Confiture = match serde_json::from_value(raw) {
         Ok(c) => c,
         Err(err) => {
             log(LogType::Warning, &err.to_string(), None);
@@ -125,7 +268,21 @@ pub fn load(target_folder: &str) -> Option<Confiture> {
         }
     };
 
-    let confiture: Confiture = match serde_json::from_str(&content) {
+    Some(confiture)
+}
+
+/// Read File
+///
+/// # Description
+/// Read the content of the confiture.json
+///
+/// # Arguments
+/// * `p` &PathBuf
+///
+/// # Return
+/// Option<Value>
+fn read_file(p: &PathBuf) -> Option<Value> {
+    let content = match open_file(p) {
         Ok(c) => c,
         Err(err) => {
             log(LogType::Warning, &err.to_string(), None);
@@ -133,5 +290,124 @@ pub fn load(target_folder: &str) -> Option<Confiture> {
         }
     };
 
-    Some(confiture)
+    match serde_json::from_str(&content) {
+        Ok(c) => Some(c),
+        Err(err) => {
+            log(LogType::Warning, &err.to_string(), None);
+            None
+        }
+    }
+}
+
+/// Merge Extensions
+///
+/// # Description
+/// Merge the `x-capoomobi` field of the services within the confitures of the confiture.json
+///
+/// # Arguments
+/// * `raw` &mut Value content of the confiture.json
+/// * `services` &[DockerService]
+fn merge_extensions(raw: &mut Value, services: &[DockerService]) {
+    let root = match raw.as_object_mut() {
+        Some(r) => r,
+        None => return
+    };
+
+    let confitures = root
+        .entry("confitures")
+        .or_insert_with(|| Value::Array(Vec::new()));
+
+    let list = match confitures.as_array_mut() {
+        Some(l) => l,
+        None => return
+    };
+
+    for svc in services {
+        let mut extension = match &svc.extension {
+            Some(Value::Object(e)) => Value::Object(e.clone()),
+            _ => continue
+        };
+
+        let idx = list
+            .iter()
+            .position(|c| c["name"].as_str() == Some(svc.name.as_str()));
+
+        match idx {
+            Some(i) => {
+                merge_value(&mut extension, list[i].take());
+                list[i] = extension;
+            },
+            None => {
+                extension["name"] = Value::String(svc.name.clone());
+                list.push(extension);
+            }
+        }
+    }
+}
+
+/// Apply Defaults
+///
+/// # Description
+/// Use the `defaults` block of the confiture.json as the base of the confiture of every service.
+/// An entry is created for the services which don't have any
+///
+/// # Arguments
+/// * `raw` &mut Value content of the confiture.json
+/// * `services` &[DockerService]
+fn apply_defaults(raw: &mut Value, services: &[DockerService]) {
+    let root = match raw.as_object_mut() {
+        Some(r) => r,
+        None => return
+    };
+
+    let defaults = root
+        .remove(DEFAULTS_FIELD)
+        .unwrap_or_else(|| Value::Object(Map::new()));
+
+    let list = match root.get_mut("confitures").and_then(|c| c.as_array_mut()) {
+        Some(l) => l,
+        None => return
+    };
+
+    for entry in list.iter_mut() {
+        let mut base = defaults.clone();
+        merge_value(&mut base, entry.take());
+        *entry = base;
+    }
+
+    for svc in services {
+        if list.iter().any(|c| c["name"].as_str() == Some(svc.name.as_str())) {
+            continue;
+        }
+
+        let mut entry = defaults.clone();
+        if let Some(e) = entry.as_object_mut() {
+            e.insert(String::from("name"), Value::String(svc.name.clone()));
+            list.push(entry);
+        }
+    }
+}
+
+/// Merge Value
+///
+/// # Description
+/// Merge recursively the objects of a JSON value, the other values are replaced
+///
+/// # Arguments
+/// * `base` &mut Value
+/// * `over` Value
+fn merge_value(base: &mut Value, over: Value) {
+    match (base, over) {
+        (Value::Object(b), Value::Object(o)) => {
+            for (k, v) in o {
+                match b.get_mut(&k) {
+                    Some(existing) => merge_value(existing, v),
+                    None => {
+                        b.insert(k, v);
+                    }
+                }
+            }
+        },
+        (b, o) => *b = o
+    }
 }
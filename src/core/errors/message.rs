@@ -8,6 +8,8 @@ pub mod cli {
     pub const DELETE_PROJECT: &str = "Unable to delete project";
     pub const GENERATE_ERROR: &str = "An error happened while writing the content to the targeted yaml files";
     pub const INGRESS_CONFIG: &str = "Ingress configuration not provided in confiture.json";
+    pub const PRIVILEGED_SERVICE: &str = "A privileged or host namespace service must be listed in the allow_privileged field of the confiture.json";
+    pub const SELECTOR_COLLISION: &str = "Services select the pods of each other, their names must stay unique once converted to labels";
 }
 
 pub mod template {
@@ -49,4 +51,18 @@ pub mod docker {
     pub const UNABLE_READ: &str  = "Unable to open the docker-compose.yaml file";
     pub const UNABLE_PARSE: &str = "Unable to parse the docker-compose.yaml for reason: ";
     pub const ABS_PATH: &str   = "Unable to find the docker-compose.yaml file";
+    pub const NO_COMPOSE_FILE: &str = "Unable to find a compose.yaml, compose.yml, docker-compose.yaml or docker-compose.yml file in the folder";
+    // extends
+    pub const EXTENDS_NOT_FOUND: &str = "Unable to find the service extended by";
+    pub const EXTENDS_CYCLE: &str = "A cycle has been detected in the extends of the service";
+    // selection
+    pub const UNKNOWN_SERVICE: &str = "Unable to find the selected service in the docker-compose.yaml";
+    // interpolation
+    pub const MISSING_VARIABLE: &str = "A required variable of the docker-compose.yaml is not set";
+    pub const INVALID_INTERPOLATION: &str = "Invalid interpolation format in the docker-compose.yaml";
+    pub const UNABLE_READ_DOTENV: &str = "Unable to read the env file use by the interpolation";
+    // environment
+    pub const UNABLE_READ_ENV_FILE: &str = "Unable to read the env_file of a service";
+    // secrets & configs
+    pub const UNABLE_READ_FILE_OBJECT: &str = "Unable to read the file of a secret or a config";
 }
@@ -14,6 +14,7 @@ pub mod cli_error {
     const PARSING_ERROR: &str = "Error while parsing a content";
     const SERIALIZE_ERROR: &str = "Error while serializing a resources";
     const NOT_FOUND_ERROR: &str = "Resources not found";
+    const POLICY_ERROR: &str = "The generation is refused by the confiture";
     const UNEXPECTED_ERROR: &str = "An unexpected error happened";
 
     /// ErrMessage
@@ -25,7 +26,8 @@ pub mod cli_error {
         MissingFieldError,
         IOError,
         NotFound,
-        RendererError
+        RendererError,
+        PolicyError
     }
 
     /// Err Helper
@@ -70,6 +72,7 @@ pub mod cli_error {
                 ErrMessage::ParsingError => PARSING_ERROR,
                 ErrMessage::SerializeError => SERIALIZE_ERROR,
                 ErrMessage::NotFound => NOT_FOUND_ERROR,
+                ErrMessage::PolicyError => POLICY_ERROR,
                 _ => UNEXPECTED_ERROR
             };
 
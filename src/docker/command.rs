@@ -0,0 +1,97 @@
+/// Command
+///
+/// # Description
+/// Module use to parse the `entrypoint` & the `command` fields of a docker-compose service
+use yaml_rust::{yaml};
+use super::parser::get_yaml_scalar;
+
+/// Parse Command
+///
+/// # Description
+/// Retrieve the arguments of an `entrypoint` or a `command` field. The list form is kept as is
+/// whereas the string form is split like a shell would (e.g: sh -c "echo hello" -> [sh, -c, echo hello])
+///
+/// # Arguments
+/// * `content` &yaml::Yaml
+///
+/// # Return
+/// Vec<String>
+pub fn parse_command(content: &yaml::Yaml) -> Vec<String> {
+    match content.as_vec() {
+        Some(list) => list.iter().map(get_yaml_scalar).collect(),
+        None => split_shell_words(&get_yaml_scalar(content))
+    }
+}
+
+/// Split Shell Words
+///
+/// # Description
+/// Split a string into words with the quoting rules of a POSIX shell. A single quoted string is taken
+/// literally, a double quoted string and the unquoted characters support the backslash escapes
+///
+/// # Arguments
+/// * `value` &str
+///
+/// # Return
+/// Vec<String>
+pub fn split_shell_words(value: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    // an empty quoted string (e.g: "") is still a word
+    let mut in_word = false;
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                for q in chars.by_ref() {
+                    if q == '\'' {
+                        break;
+                    }
+
+                    word.push(q);
+                }
+            },
+            '"' => {
+                in_word = true;
+                while let Some(q) = chars.next() {
+                    match q {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some(e) if ['"', '\\', '$', '`'].contains(&e) => word.push(e),
+                            Some(e) => {
+                                word.push('\\');
+                                word.push(e);
+                            },
+                            None => word.push('\\')
+                        },
+                        _ => word.push(q)
+                    }
+                }
+            },
+            '\\' => {
+                in_word = true;
+                if let Some(e) = chars.next() {
+                    word.push(e);
+                }
+            },
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            },
+            _ => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+
+    if in_word {
+        words.push(word);
+    }
+
+    words
+}
@@ -0,0 +1,90 @@
+/// Dependency
+///
+/// # Description
+/// Module use to parse the `depends_on` field of a docker-compose service
+/// It supports the short syntax (list of services) and the long syntax (map of services with a condition)
+use serde::{Serialize};
+use yaml_rust::{yaml};
+use super::parser::get_yaml_scalar;
+
+/// Dependency Condition
+///
+/// List of the conditions a dependency should satisfy before the service is started
+#[derive(Serialize, Clone, Debug, Copy, PartialEq)]
+pub enum DependencyCondition {
+    Started,
+    Healthy,
+    CompletedSuccessfully
+}
+
+impl Default for DependencyCondition {
+    fn default() -> Self {
+        DependencyCondition::Started
+    }
+}
+
+impl DependencyCondition {
+    /// From Value
+    ///
+    /// # Description
+    /// Retrieve a condition from a compose condition value. Default to Started
+    ///
+    /// # Arguments
+    /// * `value` &str
+    ///
+    /// # Return
+    /// DependencyCondition
+    pub fn from_value(value: &str) -> DependencyCondition {
+        match value {
+            "service_healthy" => DependencyCondition::Healthy,
+            "service_completed_successfully" => DependencyCondition::CompletedSuccessfully,
+            _ => DependencyCondition::Started
+        }
+    }
+}
+
+/// Docker Dependency
+///
+/// # Description
+/// Structure representing a service which need to be started before a docker-compose service
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DockerDependency {
+    pub name: String,
+    pub condition: DependencyCondition
+}
+
+/// Parse Dependencies
+///
+/// # Description
+/// Retrieve the dependencies of a docker-compose service
+///
+/// # Arguments
+/// * `content` &yaml::Yaml the content of the `depends_on` field
+///
+/// # Return
+/// Vec<DockerDependency>
+pub fn parse_dependencies(content: &yaml::Yaml) -> Vec<DockerDependency> {
+    if let Some(list) = content.as_vec() {
+        return list
+            .iter()
+            .map(get_yaml_scalar)
+            .filter(|name| !name.is_empty())
+            .map(|name| DockerDependency {
+                name,
+                condition: DependencyCondition::Started
+            })
+            .collect();
+    }
+
+    match content.as_hash() {
+        Some(hash) => hash
+            .iter()
+            .map(|(key, value)| DockerDependency {
+                name: get_yaml_scalar(key),
+                condition: DependencyCondition::from_value(&get_yaml_scalar(&value["condition"]))
+            })
+            .filter(|dep| !dep.name.is_empty())
+            .collect(),
+        None => Vec::new()
+    }
+}
@@ -0,0 +1,323 @@
+/// Deploy
+///
+/// # Description
+/// Module use to parse the `deploy` field of a docker-compose service
+use std::collections::BTreeMap;
+use serde::{Serialize};
+use yaml_rust::{yaml};
+use super::parser::{get_yaml_scalar, get_map_value, parse_duration};
+
+/// Deploy Mode
+///
+/// List of the deployment mode supported by docker-compose
+#[derive(Serialize, Clone, Debug, Copy, PartialEq)]
+pub enum DeployMode {
+    Replicated,
+    Global
+}
+
+impl Default for DeployMode {
+    fn default() -> Self {
+        DeployMode::Replicated
+    }
+}
+
+/// Restart Condition
+///
+/// List of the restart policies supported by docker-compose
+#[derive(Serialize, Clone, Debug, Copy, PartialEq)]
+pub enum RestartCondition {
+    Always,
+    No,
+    OnFailure,
+    UnlessStopped
+}
+
+impl Default for RestartCondition {
+    fn default() -> Self {
+        RestartCondition::Always
+    }
+}
+
+/// Update Order
+///
+/// List of the orders in which the tasks of a service are replaced during an update
+#[derive(Serialize, Clone, Debug, Copy, PartialEq)]
+pub enum UpdateOrder {
+    StopFirst,
+    StartFirst
+}
+
+impl Default for UpdateOrder {
+    fn default() -> Self {
+        UpdateOrder::StopFirst
+    }
+}
+
+/// Docker Update Config
+///
+/// # Description
+/// Structure representing the `update_config` or the `rollback_config` of a docker-compose service
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct DockerUpdateConfig {
+    pub parallelism: Option<u32>,
+    // delay between the update of each group of tasks in seconds
+    pub delay: Option<u32>,
+    pub order: UpdateOrder,
+    pub failure_action: Option<String>
+}
+
+/// Constraint Operator
+///
+/// List of the operators supported by a placement constraint
+#[derive(Serialize, Clone, Debug, Copy, PartialEq)]
+pub enum ConstraintOperator {
+    Equal,
+    NotEqual
+}
+
+/// Docker Constraint
+///
+/// # Description
+/// Structure representing a placement constraint (e.g: node.labels.zone == eu-1)
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DockerConstraint {
+    pub field: String,
+    pub operator: ConstraintOperator,
+    pub value: String
+}
+
+/// Docker Placement
+///
+/// # Description
+/// Structure representing the placement of the tasks of a docker-compose service
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct DockerPlacement {
+    pub constraints: Vec<DockerConstraint>,
+    // node fields the tasks are spread over (e.g: node.labels.zone)
+    pub preferences: Vec<String>,
+    pub max_replicas_per_node: Option<u32>
+}
+
+/// Docker Resource
+///
+/// # Description
+/// Structure representing the raw cpus & memory of a docker-compose resource (e.g: 0.5, 512M)
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct DockerResource {
+    pub cpus: Option<String>,
+    pub memory: Option<String>
+}
+
+/// Docker Resources
+///
+/// # Description
+/// Structure representing the limits & the reservations of a docker-compose service
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct DockerResources {
+    pub limits: DockerResource,
+    pub reservations: DockerResource
+}
+
+/// Docker Deploy
+///
+/// # Description
+/// Structure representing the deployment configuration of a docker-compose service
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct DockerDeploy {
+    pub mode: DeployMode,
+    pub replicas: Option<u32>,
+    // labels of the service itself
+    pub labels: BTreeMap<String, String>,
+    pub restart: RestartCondition,
+    pub resources: DockerResources,
+    pub placement: DockerPlacement,
+    pub update_config: Option<DockerUpdateConfig>,
+    pub rollback_config: Option<DockerUpdateConfig>,
+    // stop_grace_period in seconds
+    pub stop_grace_period: Option<u32>,
+    pub stop_signal: Option<String>
+}
+
+/// Parse Deploy
+///
+/// # Description
+/// Retrieve the deployment configuration of a docker-compose service
+///
+/// # Arguments
+/// * `service` &yaml::Yaml the content of the docker-compose service
+///
+/// # Return
+/// DockerDeploy
+pub fn parse_deploy(service: &yaml::Yaml) -> DockerDeploy {
+    let content = &service["deploy"];
+    let mode = match get_yaml_scalar(&content["mode"]).as_str() {
+        "global" => DeployMode::Global,
+        _ => DeployMode::Replicated
+    };
+
+    // the legacy `scale` field is used when the replicas aren't set
+    let replicas = get_yaml_scalar(&content["replicas"])
+        .parse::<u32>()
+        .or_else(|_| get_yaml_scalar(&service["scale"]).parse::<u32>())
+        .ok();
+
+    DockerDeploy {
+        mode,
+        replicas,
+        labels: get_map_value(&content["labels"]),
+        restart: parse_restart(service),
+        resources: parse_resources(service),
+        placement: parse_placement(&content["placement"]),
+        update_config: parse_update_config(&content["update_config"]),
+        rollback_config: parse_update_config(&content["rollback_config"]),
+        stop_grace_period: parse_duration(&get_yaml_scalar(&service["stop_grace_period"])),
+        stop_signal: Some(get_yaml_scalar(&service["stop_signal"]))
+            .map(|s| s.trim().to_uppercase())
+            .filter(|s| !s.is_empty())
+    }
+}
+
+/// Parse Placement
+///
+/// # Description
+/// Retrieve the placement constraints & preferences of a service. A constraint which doesn't use
+/// the `==` or the `!=` operator is ignored
+///
+/// # Arguments
+/// * `content` &yaml::Yaml the content of the `deploy.placement` field
+///
+/// # Return
+/// DockerPlacement
+fn parse_placement(content: &yaml::Yaml) -> DockerPlacement {
+    let constraints = content["constraints"]
+        .as_vec()
+        .map(|list| list.iter().filter_map(|c| parse_constraint(&get_yaml_scalar(c))).collect())
+        .unwrap_or_default();
+
+    let preferences = content["preferences"]
+        .as_vec()
+        .map(|list| {
+            list.iter()
+                .map(|p| get_yaml_scalar(&p["spread"]).trim().to_owned())
+                .filter(|p| !p.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    DockerPlacement {
+        constraints,
+        preferences,
+        max_replicas_per_node: get_yaml_scalar(&content["max_replicas_per_node"]).parse::<u32>().ok()
+    }
+}
+
+/// Parse Constraint
+///
+/// # Description
+/// Parse a placement constraint (e.g: node.role != manager)
+///
+/// # Arguments
+/// * `value` &str
+///
+/// # Return
+/// Option<DockerConstraint>
+fn parse_constraint(value: &str) -> Option<DockerConstraint> {
+    let (operator, symbol) = match value.contains("==") {
+        true => (ConstraintOperator::Equal, "=="),
+        false => (ConstraintOperator::NotEqual, "!=")
+    };
+
+    let mut parts = value.splitn(2, symbol);
+    let field = parts.next()?;
+    let value = parts.next()?;
+
+    Some(DockerConstraint {
+        field: field.trim().to_owned(),
+        operator,
+        value: value.trim().to_owned()
+    })
+}
+
+/// Parse Update Config
+///
+/// # Description
+/// Retrieve the `update_config` or the `rollback_config` of a service
+///
+/// # Arguments
+/// * `content` &yaml::Yaml
+///
+/// # Return
+/// Option<DockerUpdateConfig>
+fn parse_update_config(content: &yaml::Yaml) -> Option<DockerUpdateConfig> {
+    content.as_hash()?;
+
+    let order = match get_yaml_scalar(&content["order"]).as_str() {
+        "start-first" => UpdateOrder::StartFirst,
+        _ => UpdateOrder::StopFirst
+    };
+
+    Some(DockerUpdateConfig {
+        parallelism: get_yaml_scalar(&content["parallelism"]).parse::<u32>().ok(),
+        delay: parse_duration(&get_yaml_scalar(&content["delay"])),
+        order,
+        failure_action: Some(get_yaml_scalar(&content["failure_action"])).filter(|f| !f.is_empty())
+    })
+}
+
+/// Parse Resources
+///
+/// # Description
+/// Retrieve the resources of a service from the `deploy.resources` field. The legacy fields
+/// `cpus`, `mem_limit` & `mem_reservation` are used as a fallback
+///
+/// # Arguments
+/// * `service` &yaml::Yaml
+///
+/// # Return
+/// DockerResources
+fn parse_resources(service: &yaml::Yaml) -> DockerResources {
+    let content = &service["deploy"]["resources"];
+    let get_value = |value: &yaml::Yaml, fallback: &yaml::Yaml| {
+        Some(get_yaml_scalar(value))
+            .filter(|v| !v.is_empty())
+            .or_else(|| Some(get_yaml_scalar(fallback)).filter(|v| !v.is_empty()))
+    };
+
+    DockerResources {
+        limits: DockerResource {
+            cpus: get_value(&content["limits"]["cpus"], &service["cpus"]),
+            memory: get_value(&content["limits"]["memory"], &service["mem_limit"])
+        },
+        reservations: DockerResource {
+            cpus: get_value(&content["reservations"]["cpus"], &yaml::Yaml::BadValue),
+            memory: get_value(&content["reservations"]["memory"], &service["mem_reservation"])
+        }
+    }
+}
+
+/// Parse Restart
+///
+/// # Description
+/// Retrieve the restart policy of a service from the `restart` field (e.g: no, on-failure:3)
+/// or from the `deploy.restart_policy.condition` field (none, on-failure, any)
+///
+/// # Arguments
+/// * `service` &yaml::Yaml
+///
+/// # Return
+/// RestartCondition
+fn parse_restart(service: &yaml::Yaml) -> RestartCondition {
+    let mut value = get_yaml_scalar(&service["restart"]);
+    if value.is_empty() {
+        value = get_yaml_scalar(&service["deploy"]["restart_policy"]["condition"]);
+    }
+
+    // the max retries of on-failure is not supported
+    match value.split(':').next().unwrap_or("") {
+        "no" | "false" | "none" => RestartCondition::No,
+        "on-failure" => RestartCondition::OnFailure,
+        "unless-stopped" => RestartCondition::UnlessStopped,
+        _ => RestartCondition::Always
+    }
+}
@@ -0,0 +1,186 @@
+/// Environment
+///
+/// # Description
+/// Module use to parse the `env_file` field of a docker-compose service and to load the
+/// content of the env files. The env files are resolved relative to the docker-compose file
+use std::path::PathBuf;
+use serde::{Serialize};
+use yaml_rust::{yaml};
+use super::parser::{DockerService, get_yaml_scalar};
+use crate::core::fs::toolbox;
+use crate::core::errors::cli_error::{CliErr, ErrMessage, ErrHelper};
+use crate::core::errors::message::docker::UNABLE_READ_ENV_FILE;
+
+/// Constant
+const COMMENT: char = '#';
+const EXPORT_PREFIX: &str = "export ";
+
+/// Docker Env File
+///
+/// # Description
+/// Structure representing a file listed by the `env_file` field of a docker-compose service
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DockerEnvFile {
+    pub path: String,
+    pub required: bool
+}
+
+/// Parse Env Files
+///
+/// # Description
+/// Retrieve the env files of a docker-compose service. The field could either be a path, a list of
+/// paths or a list of `path` / `required` mappings
+///
+/// # Arguments
+/// * `content` &yaml::Yaml the content of the `env_file` field
+///
+/// # Return
+/// Vec<DockerEnvFile>
+pub fn parse_env_files(content: &yaml::Yaml) -> Vec<DockerEnvFile> {
+    let items = match content.as_vec() {
+        Some(list) => list.to_vec(),
+        None => vec![content.clone()]
+    };
+
+    items
+        .iter()
+        .filter_map(|item| {
+            let (path, required) = match item.as_hash() {
+                Some(_) => (get_yaml_scalar(&item["path"]), item["required"].as_bool().unwrap_or(true)),
+                None => (get_yaml_scalar(item), true)
+            };
+
+            if path.is_empty() {
+                return None;
+            }
+
+            Some(DockerEnvFile {
+                path,
+                required
+            })
+        })
+        .collect()
+}
+
+/// Parse Env Content
+///
+/// # Description
+/// Parse the content of an env file (e.g: `KEY=VALUE`). Blank lines and comments are ignored,
+/// the `export` keyword and the quotes surrounding a value are removed. The variables keep the order of the file
+///
+/// # Arguments
+/// * `content` &str
+///
+/// # Return
+/// Vec<(String, String)>
+pub fn parse_env_content(content: &str) -> Vec<(String, String)> {
+    let mut variables = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(COMMENT) {
+            continue;
+        }
+
+        let line = match line.starts_with(EXPORT_PREFIX) {
+            true => &line[EXPORT_PREFIX.len()..],
+            false => line
+        };
+        let mut pair = line.splitn(2, '=');
+        let key = pair.next().unwrap_or("").trim();
+        if key.is_empty() {
+            continue;
+        }
+
+        let value = pair.next().unwrap_or("").trim();
+        set_variable(&mut variables, key, unquote(value));
+    }
+
+    variables
+}
+
+/// Load Env Files
+///
+/// # Description
+/// Load the env files of each docker-compose service and merge their variables within the environment
+/// of the service. A later file override the previous ones and the `environment` field override the files
+///
+/// # Arguments
+/// * `services` &mut [DockerService]
+/// * `base` &str folder of the docker-compose file
+///
+/// # Return
+/// Result<(), CliErr>
+pub fn load_env_files(services: &mut [DockerService], base: &str) -> Result<(), CliErr> {
+    for svc in services.iter_mut() {
+        let mut environment = Vec::new();
+        for file in &svc.env_files {
+            let mut path = PathBuf::from(base);
+            path.push(&file.path);
+
+            match toolbox::open_file(&path) {
+                Ok(content) => merge_variables(&mut environment, parse_env_content(&content)),
+                Err(_) if !file.required => continue,
+                Err(err) => {
+                    let reason = format!("{}: {}", file.path, err);
+                    return Err(CliErr::new(UNABLE_READ_ENV_FILE, &reason, ErrMessage::IOError));
+                }
+            }
+        }
+
+        merge_variables(&mut environment, std::mem::take(&mut svc.environment));
+        svc.environment = environment;
+    }
+
+    Ok(())
+}
+
+/// Merge Variables
+///
+/// # Description
+/// Merge a list of variables within an other one. A variable which already exists is overridden in place
+/// so that the variables keep the order in which they're declared
+///
+/// # Arguments
+/// * `target` &mut Vec<(String, String)>
+/// * `variables` Vec<(String, String)>
+pub fn merge_variables(target: &mut Vec<(String, String)>, variables: Vec<(String, String)>) {
+    for (key, value) in variables {
+        set_variable(target, &key, &value);
+    }
+}
+
+/// Set Variable
+///
+/// # Description
+/// Set the value of a variable of a list
+///
+/// # Arguments
+/// * `target` &mut Vec<(String, String)>
+/// * `key` &str
+/// * `value` &str
+fn set_variable(target: &mut Vec<(String, String)>, key: &str, value: &str) {
+    match target.iter_mut().find(|(k, _)| k == key) {
+        Some((_, v)) => *v = String::from(value),
+        None => target.push((String::from(key), String::from(value)))
+    }
+}
+
+/// Unquote
+///
+/// # Description
+/// Remove the single or double quotes surrounding a value
+///
+/// # Arguments
+/// * `value` &str
+///
+/// # Return
+/// &str
+fn unquote(value: &str) -> &str {
+    for q in &['"', '\''] {
+        if value.len() > 1 && value.starts_with(*q) && value.ends_with(*q) {
+            return &value[1..value.len() - 1];
+        }
+    }
+
+    value
+}
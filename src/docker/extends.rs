@@ -0,0 +1,320 @@
+/// Extends
+///
+/// # Description
+/// Module use to resolve the `extends` field of the docker-compose services. A service could extend
+/// a service of the same file (`extends: base` or `extends: {service: base}`) or a service of
+/// another file (`extends: {file: common.yaml, service: base}`)
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use yaml_rust::{yaml, Yaml};
+use super::{loader, merge};
+use super::parser::get_yaml_scalar;
+use crate::core::errors::cli_error::{CliErr, ErrMessage, ErrHelper};
+use crate::core::errors::message::docker::{
+    EXTENDS_NOT_FOUND,
+    EXTENDS_CYCLE
+};
+
+/// Constant
+const EXTENDS_FIELD: &str = "extends";
+const CURRENT_FOLDER: &str = ".";
+const CURRENT_FOLDER_PREFIX: &str = "./";
+
+/// Resolve Extends
+///
+/// # Description
+/// Replace the `extends` field of every services of a compose file by the content of the extended service
+///
+/// # Arguments
+/// * `content` Yaml content of the compose file
+/// * `path` &str folder of the project
+/// * `file_name` &str path of the compose file relative to the folder of the project
+/// * `variables` &BTreeMap<String, String> variables use to interpolate the extended files
+///
+/// # Return
+/// Result<Yaml, CliErr>
+pub fn resolve_extends(content: Yaml, path: &str, file_name: &str, variables: &BTreeMap<String, String>) -> Result<Yaml, CliErr> {
+    let mut document = match content {
+        Yaml::Hash(h) => h,
+        c => return Ok(c)
+    };
+
+    let key = Yaml::String(String::from("services"));
+    let services = match document.get(&key) {
+        Some(s) => s.clone(),
+        None => return Ok(Yaml::Hash(document))
+    };
+
+    let names: Vec<String> = match services.as_hash() {
+        Some(h) => h.keys().map(get_yaml_scalar).collect(),
+        None => return Ok(Yaml::Hash(document))
+    };
+
+    let mut resolved = yaml::Hash::new();
+    for name in names {
+        let mut chain = Vec::new();
+        let service = resolve_service(&services, &name, path, file_name, variables, &mut chain)?;
+        resolved.insert(Yaml::String(name), service);
+    }
+
+    document.insert(key, Yaml::Hash(resolved));
+    Ok(Yaml::Hash(document))
+}
+
+/// Resolve Service
+///
+/// # Description
+/// Resolve recursively the extended services of a service. The fields of the service are merged on top
+/// of the extended service with the merge rules of the compose files
+///
+/// # Arguments
+/// * `services` &Yaml services of the compose file
+/// * `name` &str name of the service
+/// * `path` &str folder of the project
+/// * `file_name` &str path of the compose file relative to the folder of the project
+/// * `variables` &BTreeMap<String, String>
+/// * `chain` &mut Vec<String> services being resolved use to detect the cycles
+///
+/// # Return
+/// Result<Yaml, CliErr>
+fn resolve_service(
+    services: &Yaml,
+    name: &str,
+    path: &str,
+    file_name: &str,
+    variables: &BTreeMap<String, String>,
+    chain: &mut Vec<String>
+) -> Result<Yaml, CliErr> {
+    let id = format!("{}:{}", file_name, name);
+    if chain.contains(&id) {
+        return Err(CliErr::new(EXTENDS_CYCLE, &id, ErrMessage::ParsingError));
+    }
+
+    let mut service = match &services[name] {
+        Yaml::Hash(h) => h.clone(),
+        Yaml::Null => yaml::Hash::new(),
+        _ => return Err(CliErr::new(EXTENDS_NOT_FOUND, &id, ErrMessage::NotFound))
+    };
+
+    let extends = match service.remove(&Yaml::String(String::from(EXTENDS_FIELD))) {
+        Some(e) => e,
+        None => return Ok(Yaml::Hash(service))
+    };
+
+    let (file, base_name) = match &extends {
+        Yaml::Hash(_) => (
+            Some(get_yaml_scalar(&extends["file"])).filter(|f| !f.is_empty()),
+            get_yaml_scalar(&extends["service"])
+        ),
+        e => (None, get_yaml_scalar(e))
+    };
+
+    chain.push(id);
+    let base = match file {
+        Some(f) => {
+            // the extended file is relative to the file which declare the extends
+            let target = Path::new(file_name)
+                .parent()
+                .unwrap_or_else(|| Path::new(""))
+                .join(&f);
+
+            let target_name = target.to_string_lossy().to_string();
+            let content = loader::read(path, &target_name, variables)?;
+            let other = content.into_iter().next().unwrap_or(Yaml::Null);
+            let base = resolve_service(&other["services"], &base_name, path, &target_name, variables, chain)?;
+            // the relative paths of the extended service are relative to its own file
+            match Path::new(&f).parent() {
+                Some(folder) => rebase_service(base, folder),
+                None => base
+            }
+        },
+        None => resolve_service(services, &base_name, path, file_name, variables, chain)?
+    };
+    chain.pop();
+
+    Ok(merge::merge_yaml(base, Yaml::Hash(service)))
+}
+
+/// Rebase Service
+///
+/// # Description
+/// Resolve the relative paths of a service (`env_file`, sources of the bind mounts & `build` context)
+/// from the folder of the file which declares the service
+///
+/// # Arguments
+/// * `service` Yaml
+/// * `folder` &Path folder of the file of the service relative to the file which extends it
+///
+/// # Return
+/// Yaml
+fn rebase_service(service: Yaml, folder: &Path) -> Yaml {
+    let mut service = match service {
+        Yaml::Hash(h) => h,
+        s => return s
+    };
+
+    if folder.as_os_str().is_empty() {
+        return Yaml::Hash(service);
+    }
+
+    for (key, value) in service.iter_mut() {
+        let rebased = match key.as_str() {
+            Some("env_file") => rebase_list(value, folder, rebase_env_file),
+            Some("volumes") => rebase_list(value, folder, rebase_volume),
+            Some("build") => match value.clone() {
+                Yaml::String(context) if !is_remote(&context) => Yaml::String(rebase_path(&context, folder)),
+                Yaml::Hash(mut h) => {
+                    if !is_remote(&get_yaml_scalar(&h[&Yaml::String(String::from("context"))])) {
+                        rebase_field(&mut h, "context", folder);
+                    }
+                    Yaml::Hash(h)
+                },
+                b => b
+            },
+            _ => continue
+        };
+
+        *value = rebased;
+    }
+
+    Yaml::Hash(service)
+}
+
+/// Rebase List
+///
+/// # Description
+/// Rebase every items of a field which could either be a single value or a list
+///
+/// # Arguments
+/// * `value` &Yaml
+/// * `folder` &Path
+/// * `rebase` Fn(Yaml, &Path) -> Yaml
+///
+/// # Return
+/// Yaml
+fn rebase_list<F: Fn(Yaml, &Path) -> Yaml>(value: &Yaml, folder: &Path, rebase: F) -> Yaml {
+    match value.clone() {
+        Yaml::Array(items) => Yaml::Array(items.into_iter().map(|i| rebase(i, folder)).collect()),
+        item => rebase(item, folder)
+    }
+}
+
+/// Rebase Env File
+///
+/// # Description
+/// Rebase a file listed by the `env_file` field (path or `path` / `required` mapping)
+///
+/// # Arguments
+/// * `file` Yaml
+/// * `folder` &Path
+///
+/// # Return
+/// Yaml
+fn rebase_env_file(file: Yaml, folder: &Path) -> Yaml {
+    match file {
+        Yaml::String(p) => Yaml::String(rebase_path(&p, folder)),
+        Yaml::Hash(mut h) => {
+            rebase_field(&mut h, "path", folder);
+            Yaml::Hash(h)
+        },
+        f => f
+    }
+}
+
+/// Rebase Volume
+///
+/// # Description
+/// Rebase the relative source of a bind mount written with the short (`./data:/data:ro`) or the long syntax.
+/// Sources which doesn't start with a `.` are named volumes with the short syntax
+///
+/// # Arguments
+/// * `volume` Yaml
+/// * `folder` &Path
+///
+/// # Return
+/// Yaml
+fn rebase_volume(volume: Yaml, folder: &Path) -> Yaml {
+    match volume {
+        Yaml::String(v) if v.starts_with('.') => {
+            let mut parts = v.splitn(2, ':');
+            let source = parts.next().unwrap_or("");
+            let rebased = match parts.next() {
+                Some(rest) => format!("{}:{}", rebase_path(source, folder), rest),
+                None => v.clone()
+            };
+            Yaml::String(rebased)
+        },
+        Yaml::Hash(mut h) => {
+            if get_yaml_scalar(&h[&Yaml::String(String::from("type"))]) == "bind" {
+                rebase_field(&mut h, "source", folder);
+            }
+            Yaml::Hash(h)
+        },
+        v => v
+    }
+}
+
+/// Rebase Field
+///
+/// # Description
+/// Rebase the path stored in a field of a mapping
+///
+/// # Arguments
+/// * `hash` &mut yaml::Hash
+/// * `field` &str
+/// * `folder` &Path
+fn rebase_field(hash: &mut yaml::Hash, field: &str, folder: &Path) {
+    let key = Yaml::String(String::from(field));
+    if let Some(Yaml::String(p)) = hash.get(&key) {
+        let rebased = rebase_path(p, folder);
+        hash.insert(key, Yaml::String(rebased));
+    }
+}
+
+/// Rebase Path
+///
+/// # Description
+/// Prefix a relative path by a folder. The rebased path keeps starting with a `.` so that it's still
+/// read as a relative path (e.g: common/web.env -> ./common/web.env)
+///
+/// # Arguments
+/// * `p` &str
+/// * `folder` &Path
+///
+/// # Return
+/// String
+fn rebase_path(p: &str, folder: &Path) -> String {
+    if p.is_empty() || p.starts_with('/') || p.starts_with('~') {
+        return String::from(p);
+    }
+
+    let mut rebased = PathBuf::new();
+    if !folder.is_absolute() && !folder.starts_with(CURRENT_FOLDER) && !folder.starts_with("..") {
+        rebased.push(CURRENT_FOLDER);
+    }
+
+    rebased.push(folder);
+    let relative = match p.starts_with(CURRENT_FOLDER_PREFIX) {
+        true => &p[CURRENT_FOLDER_PREFIX.len()..],
+        false => p
+    };
+    if relative != CURRENT_FOLDER {
+        rebased.push(relative);
+    }
+
+    rebased.to_string_lossy().to_string()
+}
+
+/// Is Remote
+///
+/// # Description
+/// Check whether a build context is a remote repository (e.g: https://github.com/docker/app.git)
+///
+/// # Arguments
+/// * `context` &str
+///
+/// # Return
+/// bool
+fn is_remote(context: &str) -> bool {
+    context.contains("://") || context.starts_with("git@")
+}
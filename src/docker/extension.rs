@@ -0,0 +1,62 @@
+/// Extension
+///
+/// # Description
+/// Module use to retrieve the `x-capoomobi` extension field of a docker-compose service. The field
+/// accepts the same settings as an entry of the confiture.json (e.g: deployment, service)
+use serde_json::{Map, Number, Value};
+use yaml_rust::Yaml;
+use super::parser::get_yaml_scalar;
+
+/// Constant
+const EXTENSION_FIELD: &str = "x-capoomobi";
+
+/// Parse Extension
+///
+/// # Description
+/// Retrieve the `x-capoomobi` field of a service in the JSON representation of the confiture
+///
+/// # Arguments
+/// * `service` &Yaml the content of the docker-compose service
+///
+/// # Return
+/// Option<Value>
+pub fn parse_extension(service: &Yaml) -> Option<Value> {
+    match &service[EXTENSION_FIELD] {
+        Yaml::Hash(_) => Some(to_json(&service[EXTENSION_FIELD])),
+        _ => None
+    }
+}
+
+/// To Json
+///
+/// # Description
+/// Convert a yaml value into a JSON value
+///
+/// # Arguments
+/// * `value` &Yaml
+///
+/// # Return
+/// Value
+fn to_json(value: &Yaml) -> Value {
+    match value {
+        Yaml::Hash(hash) => {
+            let map: Map<String, Value> = hash
+                .iter()
+                .map(|(k, v)| (get_yaml_scalar(k), to_json(v)))
+                .collect();
+
+            Value::Object(map)
+        },
+        Yaml::Array(list) => Value::Array(list.iter().map(to_json).collect()),
+        Yaml::Integer(i) => Value::Number(Number::from(*i)),
+        Yaml::Real(r) => r
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .unwrap_or_else(|| Value::String(r.clone())),
+        Yaml::Boolean(b) => Value::Bool(*b),
+        Yaml::String(s) => Value::String(s.clone()),
+        _ => Value::Null
+    }
+}
@@ -0,0 +1,98 @@
+/// Healthcheck
+///
+/// # Description
+/// Module use to parse the `healthcheck` field of a docker-compose service
+use serde::{Serialize};
+use yaml_rust::{yaml};
+use super::parser::{get_yaml_scalar, parse_duration};
+
+/// Constant
+const TEST_CMD: &str = "CMD";
+const TEST_CMD_SHELL: &str = "CMD-SHELL";
+
+/// Healthcheck Test
+///
+/// # Description
+/// Command run by docker in order to check the health of a container
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub enum HealthcheckTest {
+    // command executed without shell e.g: ["CMD", "curl", "-f", "http://localhost"]
+    Cmd(Vec<String>),
+    // command executed by the default shell e.g: "curl -f http://localhost || exit 1"
+    Shell(String)
+}
+
+/// Docker Healthcheck
+///
+/// # Description
+/// Structure representing the healthcheck of a docker-compose service. Durations are in seconds
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DockerHealthcheck {
+    pub test: HealthcheckTest,
+    pub interval: Option<u32>,
+    pub timeout: Option<u32>,
+    pub retries: Option<u32>,
+    pub start_period: Option<u32>
+}
+
+/// Parse Healthcheck
+///
+/// # Description
+/// Retrieve the healthcheck of a docker-compose service. A disabled healthcheck or
+/// a healthcheck without any test isn't returned
+///
+/// # Arguments
+/// * `content` &yaml::Yaml the content of the `healthcheck` field
+///
+/// # Return
+/// Option<DockerHealthcheck>
+pub fn parse_healthcheck(content: &yaml::Yaml) -> Option<DockerHealthcheck> {
+    if content.as_hash().is_none() || content["disable"].as_bool().unwrap_or(false) {
+        return None;
+    }
+
+    let test = parse_test(&content["test"])?;
+    let retries = get_yaml_scalar(&content["retries"]).parse::<u32>().ok();
+
+    Some(DockerHealthcheck {
+        test,
+        interval: parse_duration(&get_yaml_scalar(&content["interval"])),
+        timeout: parse_duration(&get_yaml_scalar(&content["timeout"])),
+        retries,
+        start_period: parse_duration(&get_yaml_scalar(&content["start_period"]))
+    })
+}
+
+/// Parse Test
+///
+/// # Description
+/// Parse the test of a healthcheck which could either be a string or a list starting
+/// with NONE, CMD or CMD-SHELL
+///
+/// # Arguments
+/// * `content` &yaml::Yaml
+///
+/// # Return
+/// Option<HealthcheckTest>
+fn parse_test(content: &yaml::Yaml) -> Option<HealthcheckTest> {
+    let list = match content.as_vec() {
+        Some(l) => l,
+        None => {
+            let cmd = get_yaml_scalar(content);
+            if cmd.trim().is_empty() {
+                return None;
+            }
+
+            return Some(HealthcheckTest::Shell(cmd));
+        }
+    };
+
+    let values: Vec<String> = list.iter().map(get_yaml_scalar).collect();
+    let (kind, args) = values.split_first()?;
+    match kind.as_str() {
+        TEST_CMD if !args.is_empty() => Some(HealthcheckTest::Cmd(args.to_vec())),
+        TEST_CMD_SHELL if !args.is_empty() => Some(HealthcheckTest::Shell(args.join(" "))),
+        // NONE disables the healthcheck
+        _ => None
+    }
+}
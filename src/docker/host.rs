@@ -0,0 +1,120 @@
+/// Host
+///
+/// # Description
+/// Module use to parse the name resolution fields of a docker-compose service
+/// (hostname, domainname, extra_hosts, dns, dns_search & dns_opt)
+use serde::{Serialize};
+use yaml_rust::{yaml};
+use super::parser::get_yaml_scalar;
+
+/// Constant
+const HOST_SEPARATORS: [char; 2] = ['=', ':'];
+
+/// Docker Host
+///
+/// # Description
+/// Structure representing the identity & the name resolution of a docker-compose service
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct DockerHost {
+    pub hostname: Option<String>,
+    pub domainname: Option<String>,
+    pub extra_hosts: Vec<DockerExtraHost>,
+    pub dns: Vec<String>,
+    pub dns_search: Vec<String>,
+    pub dns_opt: Vec<String>
+}
+
+/// Docker Extra Host
+///
+/// # Description
+/// Structure representing an entry added to the /etc/hosts of the container
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DockerExtraHost {
+    pub hostname: String,
+    pub ip: String
+}
+
+/// Parse Host
+///
+/// # Description
+/// Retrieve the name resolution fields of a docker-compose service
+///
+/// # Arguments
+/// * `service` &yaml::Yaml the content of the docker-compose service
+///
+/// # Return
+/// DockerHost
+pub fn parse_host(service: &yaml::Yaml) -> DockerHost {
+    let get_value = |field: &str| Some(get_yaml_scalar(&service[field]))
+        .map(|v| v.trim().to_owned())
+        .filter(|v| !v.is_empty());
+
+    DockerHost {
+        hostname: get_value("hostname"),
+        domainname: get_value("domainname"),
+        extra_hosts: parse_extra_hosts(&service["extra_hosts"]),
+        dns: get_values(&service["dns"]),
+        dns_search: get_values(&service["dns_search"]),
+        dns_opt: get_values(&service["dns_opt"])
+    }
+}
+
+/// Parse Extra Hosts
+///
+/// # Description
+/// Retrieve the extra hosts of a service. The list syntax (e.g: somehost:162.242.195.82 or somehost=162.242.195.82)
+/// and the map syntax (e.g: somehost: 162.242.195.82) are supported
+///
+/// # Arguments
+/// * `content` &yaml::Yaml
+///
+/// # Return
+/// Vec<DockerExtraHost>
+fn parse_extra_hosts(content: &yaml::Yaml) -> Vec<DockerExtraHost> {
+    if let Some(map) = content.as_hash() {
+        return map
+            .iter()
+            .map(|(k, v)| DockerExtraHost {
+                hostname: get_yaml_scalar(k),
+                ip: get_yaml_scalar(v)
+            })
+            .collect();
+    }
+
+    get_values(content)
+        .into_iter()
+        .filter_map(|entry| {
+            // the hostname can't contain a separator while an IPv6 contains colons
+            let mut parts = entry.splitn(2, &HOST_SEPARATORS[..]);
+            let hostname = parts.next()?;
+            let ip = parts.next()?;
+            Some(DockerExtraHost {
+                hostname: hostname.trim().to_owned(),
+                ip: ip.trim().trim_start_matches('[').trim_end_matches(']').to_owned()
+            })
+        })
+        .collect()
+}
+
+/// Get Values
+///
+/// # Description
+/// Retrieve the non empty values of a field which is either a string or a list
+///
+/// # Arguments
+/// * `content` &yaml::Yaml
+///
+/// # Return
+/// Vec<String>
+fn get_values(content: &yaml::Yaml) -> Vec<String> {
+    let values = match content.as_vec() {
+        Some(list) => list.iter().map(get_yaml_scalar).collect(),
+        None => vec![get_yaml_scalar(content)]
+    };
+
+    values
+        .into_iter()
+        .map(|v| v.trim().to_owned())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
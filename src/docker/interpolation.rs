@@ -0,0 +1,351 @@
+/// Interpolation
+///
+/// # Description
+/// Module use to substitute the variables of a docker-compose file (e.g: ${IMAGE_TAG}, ${PORT:-8080}).
+/// The variables are read from the environment of the process and from an env file (`.env` by default)
+use std::collections::BTreeMap;
+use std::env;
+use std::path::PathBuf;
+use yaml_rust::Yaml;
+use yaml_rust::yaml::Hash;
+use super::environment::parse_env_content;
+use super::parser::get_yaml_scalar;
+use crate::core::fs::toolbox;
+use crate::core::logger::{log, LogType};
+use crate::core::errors::cli_error::{CliErr, ErrMessage, ErrHelper};
+use crate::core::errors::message::docker::{
+    MISSING_VARIABLE,
+    INVALID_INTERPOLATION,
+    UNABLE_READ_DOTENV
+};
+
+/// Constant
+const DEFAULT_ENV_FILE: &str = ".env";
+const SERVICES_FIELD: &str = "services";
+const ENVIRONMENT_FIELD: &str = "environment";
+
+/// Get Variables
+///
+/// # Description
+/// Retrieve the variables available for the interpolation. The `.env` file next to the docker-compose file
+/// is optional whereas an explicit env file has to exist. The environment of the process takes precedence
+///
+/// # Arguments
+/// * `folder` &str folder of the docker-compose file
+/// * `env_file` Option<&str> env file provided by the user
+///
+/// # Return
+/// Result<BTreeMap<String, String>, CliErr>
+pub fn get_variables(folder: &str, env_file: Option<&str>) -> Result<BTreeMap<String, String>, CliErr> {
+    let path = match env_file {
+        Some(f) => PathBuf::from(f),
+        None => {
+            let mut p = PathBuf::from(folder);
+            p.push(DEFAULT_ENV_FILE);
+            p
+        }
+    };
+
+    let mut variables = match toolbox::open_file(&path) {
+        Ok(content) => parse_env_content(&content).into_iter().collect(),
+        Err(_) if env_file.is_none() => BTreeMap::new(),
+        Err(err) => {
+            let reason = format!("{}: {}", path.display(), err);
+            return Err(CliErr::new(UNABLE_READ_DOTENV, &reason, ErrMessage::IOError));
+        }
+    };
+
+    variables.extend(env::vars());
+    Ok(variables)
+}
+
+/// Interpolate
+///
+/// # Description
+/// Substitute the variables of the string scalars of a parsed docker-compose document. As the document
+/// is parsed beforehand, a value can't change its structure and the comments are never interpolated.
+/// A substituted number or boolean (e.g: replicas: ${REPLICAS}) keeps its type
+///
+/// # Arguments
+/// * `content` Yaml
+/// * `variables` &BTreeMap<String, String>
+///
+/// # Return
+/// Result<Yaml, CliErr>
+pub fn interpolate(content: Yaml, variables: &BTreeMap<String, String>) -> Result<Yaml, CliErr> {
+    match content {
+        Yaml::String(raw) => {
+            let value = interpolate_value(&raw, variables)?;
+            if value == raw {
+                return Ok(Yaml::String(raw));
+            }
+
+            Ok(get_typed_scalar(value))
+        },
+        Yaml::Array(list) => list
+            .into_iter()
+            .map(|item| interpolate(item, variables))
+            .collect::<Result<Vec<Yaml>, CliErr>>()
+            .map(Yaml::Array),
+        Yaml::Hash(hash) => {
+            let mut interpolated = Hash::new();
+            for (key, value) in hash {
+                interpolated.insert(key, interpolate(value, variables)?);
+            }
+
+            Ok(Yaml::Hash(interpolated))
+        },
+        other => Ok(other)
+    }
+}
+
+/// Resolve Environment
+///
+/// # Description
+/// Resolve the variables of the `environment` of the services which don't have any value (e.g: `- API_TOKEN`
+/// or `API_TOKEN:`). As with docker-compose the value is read from the variables of the interpolation and
+/// a variable which isn't set is removed from the environment
+///
+/// # Arguments
+/// * `content` Yaml
+/// * `variables` &BTreeMap<String, String>
+///
+/// # Return
+/// Yaml
+pub fn resolve_environment(content: Yaml, variables: &BTreeMap<String, String>) -> Yaml {
+    let mut root = match content {
+        Yaml::Hash(root) => root,
+        other => return other
+    };
+
+    let key = Yaml::String(String::from(ENVIRONMENT_FIELD));
+    if let Some(Yaml::Hash(services)) = root.get_mut(&Yaml::String(String::from(SERVICES_FIELD))) {
+        for (_, service) in services.iter_mut() {
+            let environment = match service {
+                Yaml::Hash(svc) => svc.get_mut(&key),
+                _ => None
+            };
+
+            if let Some(env) = environment {
+                *env = resolve_variables(std::mem::replace(env, Yaml::Null), variables);
+            }
+        }
+    }
+
+    Yaml::Hash(root)
+}
+
+/// Interpolate Value
+///
+/// # Description
+/// Substitute the variables of a string value. Supports $VAR, ${VAR}, ${VAR:-default}, ${VAR-default},
+/// ${VAR:?error}, ${VAR?error}, ${VAR:+replacement}, ${VAR+replacement} and the $$ escape
+///
+/// # Arguments
+/// * `value` &str
+/// * `variables` &BTreeMap<String, String>
+///
+/// # Return
+/// Result<String, CliErr>
+pub fn interpolate_value(value: &str, variables: &BTreeMap<String, String>) -> Result<String, CliErr> {
+    let mut output = String::new();
+    let mut rest = value;
+
+    while let Some(idx) = rest.find('$') {
+        output.push_str(&rest[..idx]);
+        let next = &rest[idx + 1..];
+
+        if next.starts_with('$') {
+            output.push('$');
+            rest = &next[1..];
+        } else if next.starts_with('{') {
+            let braced = &next[1..];
+            let end = find_closing_brace(braced).ok_or_else(|| {
+                CliErr::new(INVALID_INTERPOLATION, &rest[idx..], ErrMessage::ParsingError)
+            })?;
+
+            output.push_str(&resolve_expression(&braced[..end], variables)?);
+            rest = &braced[end + 1..];
+        } else {
+            let len = get_name_length(next);
+            if len == 0 {
+                output.push('$');
+            } else {
+                output.push_str(&get_variable(&next[..len], variables));
+            }
+
+            rest = &next[len..];
+        }
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Resolve Expression
+///
+/// # Description
+/// Resolve the expression written between the braces of a variable (e.g: PORT:-8080)
+///
+/// # Arguments
+/// * `expr` &str
+/// * `variables` &BTreeMap<String, String>
+///
+/// # Return
+/// Result<String, CliErr>
+fn resolve_expression(expr: &str, variables: &BTreeMap<String, String>) -> Result<String, CliErr> {
+    let len = get_name_length(expr);
+    let (name, modifier) = expr.split_at(len);
+    if name.is_empty() {
+        return Err(CliErr::new(INVALID_INTERPOLATION, &format!("${{{}}}", expr), ErrMessage::ParsingError));
+    }
+
+    if modifier.is_empty() {
+        return Ok(get_variable(name, variables));
+    }
+
+    // with a colon an empty variable is considered as unset
+    let (strict, operator) = match modifier.starts_with(':') {
+        true => (true, &modifier[1..]),
+        false => (false, modifier)
+    };
+
+    let value = variables.get(name).filter(|v| !strict || !v.is_empty());
+    let mut chars = operator.chars();
+    let op = chars.next();
+    let argument = chars.as_str();
+
+    match (op, value) {
+        (Some('-'), Some(v)) => Ok(v.to_owned()),
+        (Some('-'), None) => interpolate_value(argument, variables),
+        (Some('?'), Some(v)) => Ok(v.to_owned()),
+        (Some('?'), None) => {
+            let reason = format!("{}: {}", name, interpolate_value(argument, variables)?);
+            Err(CliErr::new(MISSING_VARIABLE, &reason, ErrMessage::MissingFieldError))
+        },
+        (Some('+'), Some(_)) => interpolate_value(argument, variables),
+        (Some('+'), None) => Ok(String::new()),
+        _ => Err(CliErr::new(INVALID_INTERPOLATION, &format!("${{{}}}", expr), ErrMessage::ParsingError))
+    }
+}
+
+/// Resolve Variables
+///
+/// # Description
+/// Resolve the variables without value of an `environment` field written as a list or as a mapping
+///
+/// # Arguments
+/// * `environment` Yaml
+/// * `variables` &BTreeMap<String, String>
+///
+/// # Return
+/// Yaml
+fn resolve_variables(environment: Yaml, variables: &BTreeMap<String, String>) -> Yaml {
+    match environment {
+        Yaml::Array(list) => Yaml::Array(list
+            .into_iter()
+            .filter_map(|item| {
+                let raw = get_yaml_scalar(&item);
+                if raw.contains('=') {
+                    return Some(item);
+                }
+
+                let name = raw.trim();
+                variables.get(name).map(|v| Yaml::String(format!("{}={}", name, v)))
+            })
+            .collect()),
+        Yaml::Hash(hash) => Yaml::Hash(hash
+            .into_iter()
+            .filter_map(|(k, v)| match v {
+                Yaml::Null => variables
+                    .get(&get_yaml_scalar(&k))
+                    .map(|value| (k, Yaml::String(value.to_owned()))),
+                v => Some((k, v))
+            })
+            .collect()),
+        other => other
+    }
+}
+
+/// Get Typed Scalar
+///
+/// # Description
+/// Retrieve the yaml scalar of an interpolated value. A number or a boolean is only typed when it's
+/// written back identically, thus a value such as 010 or 0o440 is kept as a string
+///
+/// # Arguments
+/// * `value` String
+///
+/// # Return
+/// Yaml
+fn get_typed_scalar(value: String) -> Yaml {
+    match Yaml::from_str(&value) {
+        typed @ (Yaml::Integer(_) | Yaml::Real(_) | Yaml::Boolean(_)) if get_yaml_scalar(&typed) == value => typed,
+        _ => Yaml::String(value)
+    }
+}
+
+/// Get Variable
+///
+/// # Description
+/// Retrieve the value of a variable. An unset variable is replaced by an empty string
+///
+/// # Arguments
+/// * `name` &str
+/// * `variables` &BTreeMap<String, String>
+///
+/// # Return
+/// String
+fn get_variable(name: &str, variables: &BTreeMap<String, String>) -> String {
+    match variables.get(name) {
+        Some(v) => v.to_owned(),
+        None => {
+            log(LogType::Warning, &format!("The {} variable is not set. Defaulting to a blank string", name), None);
+            String::new()
+        }
+    }
+}
+
+/// Get Name Length
+///
+/// # Description
+/// Retrieve the length of the variable name at the start of a value
+///
+/// # Arguments
+/// * `value` &str
+///
+/// # Return
+/// usize
+fn get_name_length(value: &str) -> usize {
+    if value.starts_with(|c: char| c.is_ascii_digit()) {
+        return 0;
+    }
+
+    value
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(value.len())
+}
+
+/// Find Closing Brace
+///
+/// # Description
+/// Retrieve the index of the brace closing a variable, nested variables are skipped
+///
+/// # Arguments
+/// * `value` &str content following the opening brace
+///
+/// # Return
+/// Option<usize>
+fn find_closing_brace(value: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (idx, c) in value.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(idx),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    None
+}
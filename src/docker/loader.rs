@@ -3,28 +3,86 @@
 /// # Description
 ///
 /// Yaml parser module is use to extract the content of a yaml file
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use yaml_rust::{YamlLoader, yaml};
+use yaml_rust::parser::{Parser, Event, MarkedEventReceiver};
+use yaml_rust::scanner::{Marker, TScalarStyle};
 use crate::core::fs::toolbox;
+use super::{interpolation, merge, extends};
 use crate::core::errors::cli_error::{CliErr, ErrMessage, ErrHelper};
 use crate::core::errors::message::docker::{
     UNABLE_READ,
     UNABLE_PARSE,
-    ABS_PATH
+    ABS_PATH,
+    NO_COMPOSE_FILE
 };
 
-/// Parse
+/// Constant
+const COMPOSE_FILE_NAMES: [&str; 4] = [
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml"
+];
+const OVERRIDE_EXTENSIONS: [&str; 2] = ["yaml", "yml"];
+const MODE_FIELD: &str = "mode";
+
+/// Node
+///
+/// # Description
+/// Collection being read by the ModeReceiver
+enum Node {
+    Sequence,
+    // the key of the mapping which is being read, none when the next scalar is a key
+    Mapping(Option<String>)
+}
+
+/// Mode Receiver
+///
+/// # Description
+/// Receive the events of the yaml parser to find the `mode` fields written in the octal form of YAML 1.1
+/// (e.g: 0440) which yaml-rust reads as a decimal number
+struct ModeReceiver {
+    stack: Vec<Node>,
+    // position & length of the octal modes
+    modes: Vec<(usize, usize)>
+}
+
+/// Load
+///
+/// # Description
+/// Retrieve the yaml content once the variables are interpolated and the extended services are resolved.
+/// The `.env` file of the folder is used unless an env file is provided
+///
+/// # Arguments
+/// * `path` slice of string
+/// * `file_name` slice of string
+/// * `env_file` Option<&str> env file replacing the `.env` file of the folder
+///
+/// # Return
+/// Result<Vec<yaml::Yaml>, CliErr>
+pub fn load(path: &str, file_name: &str, env_file: Option<&str>) -> Result<Vec<yaml::Yaml>, CliErr> {
+    let variables = interpolation::get_variables(path, env_file)?;
+    read(path, file_name, &variables)?
+        .into_iter()
+        .map(|doc| extends::resolve_extends(doc, path, file_name, &variables))
+        .collect()
+}
+
+/// Read
 ///
 /// # Description
-/// Retrieve the yaml content
+/// Retrieve the yaml content of a file with the variables interpolated and the merge keys (`<<`) expanded
 ///
 /// # Arguments
 /// * `path` slice of string
 /// * `file_name` slice of string
+/// * `variables` &BTreeMap<String, String>
 ///
 /// # Return
 /// Result<Vec<yaml::Yaml>, CliErr>
-pub fn load(path: &str, file_name: &str) -> Result<Vec<yaml::Yaml>, CliErr> {
+pub fn read(path: &str, file_name: &str, variables: &BTreeMap<String, String>) -> Result<Vec<yaml::Yaml>, CliErr> {
     let mut paths = PathBuf::from(path);
     paths.push(file_name);
 
@@ -38,23 +96,97 @@ pub fn load(path: &str, file_name: &str) -> Result<Vec<yaml::Yaml>, CliErr> {
         return Err(CliErr::new(UNABLE_READ, &err.to_string(), ErrMessage::IOError));
     }
 
-    match parse_yaml_builder(content.unwrap()) {
-        Ok(yaml_content) => Ok(yaml_content),
-        Err(err) => Err(CliErr::new(UNABLE_PARSE, &err.to_string(), ErrMessage::IOError))
+    // the variables are substituted once the file is parsed so their values can't alter the document
+    let yaml_content = match parse_yaml_builder(content.unwrap()) {
+        Ok(yaml_content) => yaml_content,
+        Err(err) => return Err(CliErr::new(UNABLE_PARSE, &err.to_string(), ErrMessage::IOError))
+    };
+
+    yaml_content
+        .into_iter()
+        .map(|doc| interpolation::interpolate(doc, variables)
+            .map(merge::expand_merge_keys)
+            .map(|d| interpolation::resolve_environment(d, variables)))
+        .collect()
+}
+
+/// Load Files
+///
+/// # Description
+/// Load several compose files and merge them in the given order into a single document.
+/// The files are resolved from the folder of the project
+///
+/// # Arguments
+/// * `path` slice of string
+/// * `files` &[String] name of the compose files
+/// * `env_file` Option<&str> env file replacing the `.env` file of the folder
+///
+/// # Return
+/// Result<Vec<yaml::Yaml>, CliErr>
+pub fn load_files(path: &str, files: &[String], env_file: Option<&str>) -> Result<Vec<yaml::Yaml>, CliErr> {
+    let mut merged = yaml::Yaml::Null;
+    for file in files {
+        let content = load(path, file, env_file)?;
+        if let Some(doc) = content.into_iter().next() {
+            merged = match merged {
+                yaml::Yaml::Null => doc,
+                base => merge::merge_yaml(base, doc)
+            };
+        }
     }
+
+    Ok(vec![merged])
+}
+
+/// Get Compose Files
+///
+/// # Description
+/// Detect the compose file of a folder (compose.yaml, compose.yml, docker-compose.yaml, docker-compose.yml)
+/// and the override file matching its name (e.g: docker-compose.override.yaml)
+///
+/// # Arguments
+/// * `path` slice of string
+///
+/// # Return
+/// Result<Vec<String>, CliErr>
+pub fn get_compose_files(path: &str) -> Result<Vec<String>, CliErr> {
+    let folder = PathBuf::from(path);
+    let compose = COMPOSE_FILE_NAMES
+        .iter()
+        .find(|name| folder.join(name).is_file());
+
+    let name = match compose {
+        Some(n) => n,
+        None => return Err(CliErr::new(NO_COMPOSE_FILE, path, ErrMessage::NotFound))
+    };
+
+    let mut files = vec![String::from(*name)];
+    let stem = name.rsplitn(2, '.').nth(1).unwrap_or(name);
+    let over = OVERRIDE_EXTENSIONS
+        .iter()
+        .map(|ext| format!("{}.override.{}", stem, ext))
+        .find(|file| folder.join(file).is_file());
+
+    if let Some(file) = over {
+        files.push(file);
+    }
+
+    Ok(files)
 }
 
 /// Parse Yaml builder
 ///
 /// # Description
-/// Retrieve the `yaml-rust` library yaml representation
+/// Retrieve the `yaml-rust` library yaml representation. The octal modes (e.g: `mode: 0440`) are kept
+/// as strings so they're not read as decimal numbers
 ///
 /// # Argument
 /// * `content` String
 ///
 /// # Return
 /// Result<Vec<yaml::Yaml>, yaml_rust::ScanError>
-fn parse_yaml_builder(content: String) -> Result<Vec<yaml::Yaml> , yaml_rust::ScanError> {
+pub fn parse_yaml_builder(content: String) -> Result<Vec<yaml::Yaml> , yaml_rust::ScanError> {
+    let content = quote_octal_modes(content)?;
     let yaml_file = YamlLoader::load_from_str(content.as_str());
 
     match yaml_file {
@@ -62,3 +194,71 @@ fn parse_yaml_builder(content: String) -> Result<Vec<yaml::Yaml> , yaml_rust::Sc
         Err(e) => Err(e)
     }
 }
+
+/// Quote Octal Modes
+///
+/// # Description
+/// Quote the `mode` fields written with a leading zero (e.g: 0440)
+///
+/// # Argument
+/// * `content` String
+///
+/// # Return
+/// Result<String, yaml_rust::ScanError>
+fn quote_octal_modes(content: String) -> Result<String, yaml_rust::ScanError> {
+    let mut receiver = ModeReceiver { stack: Vec::new(), modes: Vec::new() };
+    Parser::new(content.chars()).load(&mut receiver, true)?;
+    if receiver.modes.is_empty() {
+        return Ok(content);
+    }
+
+    // the markers of the parser are counted in characters
+    let mut chars: Vec<char> = content.chars().collect();
+    for (index, len) in receiver.modes.into_iter().rev() {
+        chars.insert(index + len, '"');
+        chars.insert(index, '"');
+    }
+
+    Ok(chars.into_iter().collect())
+}
+
+impl ModeReceiver {
+    /// Read Value
+    ///
+    /// # Description
+    /// Move to the next key once the value of a mapping is read
+    fn read_value(&mut self) {
+        if let Some(Node::Mapping(key)) = self.stack.last_mut() {
+            *key = None;
+        }
+    }
+}
+
+impl MarkedEventReceiver for ModeReceiver {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::SequenceStart(_) => self.stack.push(Node::Sequence),
+            Event::MappingStart(_) => self.stack.push(Node::Mapping(None)),
+            Event::SequenceEnd | Event::MappingEnd => {
+                self.stack.pop();
+                self.read_value();
+            },
+            Event::Scalar(value, style, _, _) => match self.stack.last_mut() {
+                Some(Node::Mapping(key @ None)) => *key = Some(value),
+                Some(Node::Mapping(Some(key))) => {
+                    let octal = value.len() > 1
+                        && value.starts_with('0')
+                        && value.chars().all(|c| c.is_digit(8));
+
+                    if key == MODE_FIELD && style == TScalarStyle::Plain && octal {
+                        self.modes.push((mark.index(), value.len()));
+                    }
+                    self.read_value();
+                },
+                _ => {}
+            },
+            Event::Alias(_) => self.read_value(),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,253 @@
+/// Merge
+///
+/// # Description
+/// Module use to merge several docker-compose files with the compose merge rules
+/// - mappings are merged recursively
+/// - scalars are replaced
+/// - `command`, `entrypoint` & the `test` of a healthcheck are replaced
+/// - `environment` & `labels` are merged by variable name
+/// - `volumes` & `devices` are merged by mount path, `secrets` & `configs` by source
+/// - other sequences are appended
+///
+/// The YAML merge keys (`<<: *anchor`) are also expanded by this module
+use std::mem;
+use yaml_rust::{yaml, Yaml};
+use super::parser::get_yaml_scalar;
+
+/// Constant
+const REPLACED_SEQUENCES: [&str; 3] = ["command", "entrypoint", "test"];
+const MAPPED_SEQUENCES: [&str; 2] = ["environment", "labels"];
+const MOUNTED_SEQUENCES: [&str; 2] = ["volumes", "devices"];
+const SOURCED_SEQUENCES: [&str; 2] = ["secrets", "configs"];
+const MERGE_KEY: &str = "<<";
+
+/// Merge Yaml
+///
+/// # Description
+/// Merge the content of an override file within the content of a base file
+///
+/// # Arguments
+/// * `base` Yaml
+/// * `over` Yaml
+///
+/// # Return
+/// Yaml
+pub fn merge_yaml(base: Yaml, over: Yaml) -> Yaml {
+    merge_value("", base, over)
+}
+
+/// Expand Merge Keys
+///
+/// # Description
+/// Replace the YAML merge keys (e.g: `<<: *common` or `<<: [*common, *other]`) by the content
+/// of the referenced mappings. The keys of the mapping take precedence over the merged ones and the
+/// first mapping of a list take precedence over the next ones
+///
+/// # Arguments
+/// * `value` Yaml
+///
+/// # Return
+/// Yaml
+pub fn expand_merge_keys(value: Yaml) -> Yaml {
+    match value {
+        Yaml::Hash(hash) => {
+            let key = Yaml::String(String::from(MERGE_KEY));
+            let mut expanded = yaml::Hash::new();
+            if let Some(sources) = hash.get(&key) {
+                let list = match sources {
+                    Yaml::Array(l) => l.clone(),
+                    s => vec![s.clone()]
+                };
+
+                for source in list {
+                    if let Yaml::Hash(h) = expand_merge_keys(source) {
+                        for (k, v) in h {
+                            expanded.entry(k).or_insert(v);
+                        }
+                    }
+                }
+            }
+
+            for (k, v) in hash {
+                if k == key {
+                    continue;
+                }
+
+                match expanded.get_mut(&k) {
+                    Some(existing) => *existing = expand_merge_keys(v),
+                    None => {
+                        expanded.insert(k, expand_merge_keys(v));
+                    }
+                }
+            }
+
+            Yaml::Hash(expanded)
+        },
+        Yaml::Array(list) => Yaml::Array(list.into_iter().map(expand_merge_keys).collect()),
+        v => v
+    }
+}
+
+/// Merge Value
+///
+/// # Description
+/// Merge two values of a field with the merge rule of the field
+///
+/// # Arguments
+/// * `key` &str name of the field
+/// * `base` Yaml
+/// * `over` Yaml
+///
+/// # Return
+/// Yaml
+fn merge_value(key: &str, base: Yaml, over: Yaml) -> Yaml {
+    if over.is_null() || over.is_badvalue() {
+        return base;
+    }
+
+    if MAPPED_SEQUENCES.contains(&key) {
+        return merge_value("", to_hash(base), to_hash(over));
+    }
+
+    match (base, over) {
+        (Yaml::Hash(mut b), Yaml::Hash(o)) => {
+            for (k, v) in o {
+                let field = get_yaml_scalar(&k);
+                // the existing value is replaced in place in order to keep the order of the base file
+                match b.get_mut(&k) {
+                    Some(existing) => {
+                        let current = mem::replace(existing, Yaml::Null);
+                        *existing = merge_value(&field, current, v);
+                    },
+                    None => {
+                        b.insert(k, v);
+                    }
+                }
+            }
+
+            Yaml::Hash(b)
+        },
+        (Yaml::Array(b), Yaml::Array(o)) => {
+            if REPLACED_SEQUENCES.contains(&key) {
+                return Yaml::Array(o);
+            }
+
+            if MOUNTED_SEQUENCES.contains(&key) {
+                return Yaml::Array(merge_by(b, o, get_mount_path));
+            }
+
+            if SOURCED_SEQUENCES.contains(&key) {
+                return Yaml::Array(merge_by(b, o, get_source));
+            }
+
+            let mut merged = b;
+            for item in o {
+                if !merged.contains(&item) {
+                    merged.push(item);
+                }
+            }
+
+            Yaml::Array(merged)
+        },
+        (_, o) => o
+    }
+}
+
+/// Merge By
+///
+/// # Description
+/// Merge two sequences, an item of the override replace the base item which has the same key
+///
+/// # Arguments
+/// * `base` Vec<Yaml>
+/// * `over` Vec<Yaml>
+/// * `get_key` fn(&Yaml) -> String
+///
+/// # Return
+/// Vec<Yaml>
+fn merge_by(base: Vec<Yaml>, over: Vec<Yaml>, get_key: fn(&Yaml) -> String) -> Vec<Yaml> {
+    let mut merged = base;
+    for item in over {
+        let key = get_key(&item);
+        match merged.iter().position(|m| get_key(m) == key) {
+            Some(idx) => merged[idx] = item,
+            None => merged.push(item)
+        }
+    }
+
+    merged
+}
+
+/// To Hash
+///
+/// # Description
+/// Convert a list of `KEY=VALUE` into a mapping. A key without value is kept with a null value
+///
+/// # Arguments
+/// * `value` Yaml
+///
+/// # Return
+/// Yaml
+fn to_hash(value: Yaml) -> Yaml {
+    let list = match value {
+        Yaml::Array(l) => l,
+        v => return v
+    };
+
+    let mut hash = yaml::Hash::new();
+    for item in list {
+        let raw = get_yaml_scalar(&item);
+        let mut pair = raw.splitn(2, '=');
+        let key = String::from(pair.next().unwrap_or("").trim());
+        let value = match pair.next() {
+            Some(v) => Yaml::String(String::from(v)),
+            None => Yaml::Null
+        };
+
+        hash.insert(Yaml::String(key), value);
+    }
+
+    Yaml::Hash(hash)
+}
+
+/// Get Mount Path
+///
+/// # Description
+/// Retrieve the path where a volume or a device is mounted in the container
+///
+/// # Arguments
+/// * `value` &Yaml
+///
+/// # Return
+/// String
+fn get_mount_path(value: &Yaml) -> String {
+    if value.as_hash().is_some() {
+        return get_yaml_scalar(&value["target"]);
+    }
+
+    let raw = get_yaml_scalar(value);
+    let parts: Vec<&str> = raw.split(':').collect();
+    match parts.as_slice() {
+        [target] => String::from(*target),
+        [_, target, ..] => String::from(*target),
+        _ => raw.clone()
+    }
+}
+
+/// Get Source
+///
+/// # Description
+/// Retrieve the source of a secret or a config reference
+///
+/// # Arguments
+/// * `value` &Yaml
+///
+/// # Return
+/// String
+fn get_source(value: &Yaml) -> String {
+    if value.as_hash().is_some() {
+        return get_yaml_scalar(&value["source"]);
+    }
+
+    get_yaml_scalar(value)
+}
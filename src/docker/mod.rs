@@ -1,2 +1,17 @@
+pub mod command;
+pub mod dependency;
+pub mod deploy;
+pub mod environment;
+pub mod extends;
+pub mod extension;
+pub mod healthcheck;
+pub mod host;
+pub mod interpolation;
 pub mod loader;
-pub mod parser;
\ No newline at end of file
+pub mod merge;
+pub mod parser;
+pub mod port;
+pub mod secret;
+pub mod security;
+pub mod selection;
+pub mod volume;
@@ -1,30 +1,64 @@
 /// Lexer
 ///
 /// Retrieve a representation of the docker-compose.yaml file
-use std::collections::HashMap;
+use std::collections::{HashMap, BTreeMap};
+use std::path::Path;
 use yaml_rust::{yaml};
+use super::command::parse_command;
+use super::deploy::{DockerDeploy, parse_deploy};
+use super::dependency::{DockerDependency, parse_dependencies};
+use super::environment::{DockerEnvFile, parse_env_files, merge_variables};
+use super::extension::parse_extension;
+use super::selection::parse_profiles;
+use super::healthcheck::{DockerHealthcheck, parse_healthcheck};
+use super::host::{DockerHost, parse_host};
+use super::port::{DockerPort, parse_ports};
+use super::security::{DockerSecurity, parse_security};
+use super::secret::{DockerFileObject, DockerFileReference, FileKind, parse_file_objects, parse_file_references, resolve_external_files};
+use super::volume::{DockerVolume, DockerNamedVolume, parse_volumes, parse_named_volumes, parse_tmpfs, resolve_external_volumes};
 
 // Error constant
 const UNKNOWN_SERVICE_NAME: &str = "unknown";
 
 /// Service represent a service in the compose file
 /// e.g services.portainer
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct DockerService {
     pub name: String,
     pub image: String,
-    pub commands: Vec<String>,
-    pub ports: Vec<String>,
-    pub labels: Vec<String>,
-    pub environment: Vec<String>,
-    pub volumes: Vec<String>
+    // entrypoint & command are the K8S command & args of the container
+    pub entrypoint: Vec<String>,
+    pub command: Vec<String>,
+    pub working_dir: Option<String>,
+    pub user: Option<String>,
+    pub tty: bool,
+    pub stdin_open: bool,
+    pub security: DockerSecurity,
+    // hostname & name resolution
+    pub host: DockerHost,
+    pub ports: Vec<DockerPort>,
+    pub labels: BTreeMap<String, String>,
+    // variables in the order of the compose file
+    pub environment: Vec<(String, String)>,
+    pub env_files: Vec<DockerEnvFile>,
+    pub volumes: Vec<DockerVolume>,
+    pub secrets: Vec<DockerFileReference>,
+    pub configs: Vec<DockerFileReference>,
+    pub deploy: DockerDeploy,
+    pub healthcheck: Option<DockerHealthcheck>,
+    pub depends_on: Vec<DockerDependency>,
+    pub profiles: Vec<String>,
+    // name of the compose project the service belongs to
+    pub project: String,
+    // content of the `x-capoomobi` field
+    pub extension: Option<serde_json::Value>
 }
 
 /// Enumeration Field Type
 /// Use to choice which type of field to filter
 enum FieldType {
     Single,
-    Collection
+    Map
 }
 
 /// Get Supported Attributes
@@ -39,8 +73,8 @@ enum FieldType {
 /// Vec of static str
 fn get_supported_attributes(field: FieldType) -> Vec<&'static str> {
     match field {
-        FieldType::Single => vec!["image", "command", "labels"],
-        FieldType::Collection => vec!["command", "ports", "labels", "environment", "volumes"]
+        FieldType::Single => vec!["image", "working_dir", "user"],
+        FieldType::Map => vec!["labels"]
     }
 }
 
@@ -65,11 +99,20 @@ pub fn get_docker_services(content: Vec<yaml::Yaml>) -> Option<Vec<DockerService
         return None;
     }
 
+    let named = parse_named_volumes(&raw_docker_content["volumes"]);
+    let secrets = parse_file_objects(&raw_docker_content["secrets"], FileKind::Secret);
+    let configs = parse_file_objects(&raw_docker_content["configs"], FileKind::Config);
     let raw_hash = raw_services.into_hash();
     if let Some(hashes) = raw_hash {
         let services: Vec<DockerService> = hashes
             .into_iter()
             .map(|yaml| parse_each_yaml_content(yaml.0, yaml.1))
+            .map(|mut svc| {
+                resolve_external_volumes(&mut svc.volumes, &named);
+                resolve_external_files(&mut svc.secrets, &secrets);
+                resolve_external_files(&mut svc.configs, &configs);
+                svc
+            })
             .collect();
 
         return Some(services);
@@ -78,27 +121,220 @@ pub fn get_docker_services(content: Vec<yaml::Yaml>) -> Option<Vec<DockerService
     None
 }
 
-/// Retrieve Array Or Fallback
+/// Get Project Name
 ///
 /// # Description
-/// Retrieve an array of the fallback array
-///
-/// # Argument
-/// * `content` Option of refrence to a string
-/// * `fallback` Vec<String>
-fn get_value(content: Option<&Vec<String>>, fallback: Vec<String>) -> Vec<String> {
-    match content {
-        Some(value) => {
-            if value.is_empty() {
-                return fallback;
-            }
+/// Retrieve the name of the compose project. The top-level `name` takes precedence, otherwise the name
+/// of the folder of the compose files is used. As with docker-compose the name is lowercased and only keeps
+/// alphanumeric characters, '-' & '_'
+///
+/// # Arguments
+/// * `content` &[yaml::Yaml]
+/// * `path` &str folder of the compose files
+///
+/// # Return
+/// String
+pub fn get_project_name(content: &[yaml::Yaml], path: &str) -> String {
+    let name = content
+        .first()
+        .map(|c| get_yaml_scalar(&c["name"]))
+        .filter(|n| !n.is_empty())
+        .or_else(|| {
+            Path::new(path)
+                .canonicalize()
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+        })
+        .unwrap_or_default();
+
+    name
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect()
+}
+
+/// Get Docker Volumes
+///
+/// # Description
+/// Retrieve the list of named volumes declared in the top-level `volumes` key
+///
+/// # Arguments
+/// * `content` &[yaml::Yaml]
+///
+/// # Return
+/// Vec<DockerNamedVolume>
+pub fn get_docker_volumes(content: &[yaml::Yaml]) -> Vec<DockerNamedVolume> {
+    match content.first() {
+        Some(raw_docker_content) => parse_named_volumes(&raw_docker_content["volumes"]),
+        None => Vec::new()
+    }
+}
 
-            value.to_vec()
+/// Get Docker Files
+///
+/// # Description
+/// Retrieve the secrets & the configs declared in the top-level `secrets` and `configs` keys
+///
+/// # Arguments
+/// * `content` &[yaml::Yaml]
+///
+/// # Return
+/// Vec<DockerFileObject>
+pub fn get_docker_files(content: &[yaml::Yaml]) -> Vec<DockerFileObject> {
+    match content.first() {
+        Some(raw_docker_content) => {
+            let mut files = parse_file_objects(&raw_docker_content["secrets"], FileKind::Secret);
+            files.append(&mut parse_file_objects(&raw_docker_content["configs"], FileKind::Config));
+            files
         },
-        None => fallback
+        None => Vec::new()
     }
 }
 
+/// Get Yaml Scalar
+///
+/// # Description
+/// Retrieve the string representation of a yaml scalar (string, number, boolean)
+///
+/// # Arguments
+/// * `value` &yaml::Yaml
+///
+/// # Return
+/// String
+pub fn get_yaml_scalar(value: &yaml::Yaml) -> String {
+    match value {
+        yaml::Yaml::String(s) => s.to_owned(),
+        yaml::Yaml::Integer(i) => i.to_string(),
+        yaml::Yaml::Real(r) => r.to_owned(),
+        yaml::Yaml::Boolean(b) => b.to_string(),
+        _ => String::new()
+    }
+}
+
+/// Parse Duration
+///
+/// # Description
+/// Parse a docker-compose duration (e.g: 1m30s, 10s, 500ms) into a number of seconds.
+/// The value is rounded up to the next second. A plain number is considered as seconds
+///
+/// # Arguments
+/// * `value` &str
+///
+/// # Return
+/// Option<u32>
+pub fn parse_duration(value: &str) -> Option<u32> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    if let Ok(secs) = value.parse::<u32>() {
+        return Some(secs);
+    }
+
+    let mut total = 0_f64;
+    let mut number = String::new();
+    let mut unit = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+
+        unit.push(c);
+        // a unit is complete once the next char is a digit or when the value is consumed
+        let complete = match chars.peek() {
+            Some(n) => n.is_ascii_digit() || *n == '.',
+            None => true
+        };
+        if complete {
+            total += number.parse::<f64>().ok()? * get_duration_unit(&unit)?;
+            number.clear();
+            unit.clear();
+        }
+    }
+
+    if !number.is_empty() {
+        return None;
+    }
+
+    Some(total.ceil() as u32)
+}
+
+/// Get Duration Unit
+///
+/// # Description
+/// Retrieve the number of seconds of a duration unit
+///
+/// # Arguments
+/// * `unit` &str
+///
+/// # Return
+/// Option<f64>
+fn get_duration_unit(unit: &str) -> Option<f64> {
+    match unit {
+        "h" => Some(3600.0),
+        "m" => Some(60.0),
+        "s" => Some(1.0),
+        "ms" => Some(0.001),
+        "us" => Some(0.000_001),
+        "ns" => Some(0.000_000_001),
+        _ => None
+    }
+}
+
+/// Get Map Value
+///
+/// # Description
+/// Retrieve a normalized key/value map from a field which could either be written
+/// as a list of `KEY=VALUE` or as a yaml mapping (e.g: labels)
+///
+/// # Arguments
+/// * `content` &yaml::Yaml
+///
+/// # Return
+/// BTreeMap<String, String>
+pub fn get_map_value(content: &yaml::Yaml) -> BTreeMap<String, String> {
+    get_pairs(content).into_iter().collect()
+}
+
+/// Get Pairs
+///
+/// # Description
+/// Retrieve the key/value pairs of a field which could either be written as a list of `KEY=VALUE`
+/// or as a yaml mapping (e.g: environment). The pairs keep the order of the compose file
+///
+/// # Arguments
+/// * `content` &yaml::Yaml
+///
+/// # Return
+/// Vec<(String, String)>
+pub fn get_pairs(content: &yaml::Yaml) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    if let Some(list) = content.as_vec() {
+        for item in list {
+            let raw = get_yaml_scalar(item);
+            let mut pair = raw.splitn(2, '=');
+            let key = pair.next().unwrap_or("").trim();
+            // a variable without value is never set to an empty string
+            if let (false, Some(value)) = (key.is_empty(), pair.next()) {
+                merge_variables(&mut pairs, vec![(String::from(key), String::from(value))]);
+            }
+        }
+    } else if let Some(hash) = content.as_hash() {
+        for (key, value) in hash {
+            let k = get_yaml_scalar(key);
+            if !k.is_empty() && !value.is_null() {
+                pairs.push((k, get_yaml_scalar(value)));
+            }
+        }
+    }
+
+    pairs
+}
+
 /// Parse Each Yaml Content
 ///
 /// # Description
@@ -111,35 +347,43 @@ fn get_value(content: Option<&Vec<String>>, fallback: Vec<String>) -> Vec<String
 /// # Return
 /// Service struct
 fn parse_each_yaml_content(service_name: yaml::Yaml, service_content: yaml::Yaml) -> DockerService {
-    let mut collection_attrs = HashMap::new();
     let single_type_vec: Vec<String> = get_supported_attributes(FieldType::Single)
         .into_iter()
-        .map(|key| String::from(service_content[key].as_str().unwrap_or("")))
+        .map(|key| get_yaml_scalar(&service_content[key]))
         .collect();
 
-    for attr in get_supported_attributes(FieldType::Collection) {
-        if let Some(service) = service_content[attr].as_vec() {
-            let str_vec_fields: Vec<String> = service
-                .iter()
-                .map(|value| String::from(value.as_str().unwrap_or("")))
-                .collect();
-
-            collection_attrs.insert(attr, str_vec_fields);
-        } else {
-            collection_attrs.insert(attr, Vec::new());
-        }
+    let mut map_attrs = HashMap::new();
+    for attr in get_supported_attributes(FieldType::Map) {
+        map_attrs.insert(attr, get_map_value(&service_content[attr]));
     }
 
-    let fallback_cmd = vec![String::from(&single_type_vec[1])];
-    let fallback_label = vec![String::from(&single_type_vec[2])];
-
     DockerService {
         name:  String::from(service_name.as_str().unwrap_or(UNKNOWN_SERVICE_NAME)),
         image: String::from(&single_type_vec[0]),
-        commands: get_value(collection_attrs.get("command"), fallback_cmd),
-        labels: get_value(collection_attrs.get("labels"), fallback_label),
-        ports: get_value(collection_attrs.get("ports"), Vec::new()),
-        environment: get_value(collection_attrs.get("environment"), Vec::new()),
-        volumes: get_value(collection_attrs.get("volumes"), Vec::new())
+        entrypoint: parse_command(&service_content["entrypoint"]),
+        command: parse_command(&service_content["command"]),
+        working_dir: Some(single_type_vec[1].clone()).filter(|w| !w.is_empty()),
+        user: Some(single_type_vec[2].clone()).filter(|u| !u.is_empty()),
+        tty: service_content["tty"].as_bool().unwrap_or(false),
+        stdin_open: service_content["stdin_open"].as_bool().unwrap_or(false),
+        security: parse_security(&service_content),
+        host: parse_host(&service_content),
+        labels: map_attrs.remove("labels").unwrap_or_default(),
+        ports: parse_ports(&service_content["ports"]),
+        environment: get_pairs(&service_content["environment"]),
+        env_files: parse_env_files(&service_content["env_file"]),
+        volumes: parse_volumes(&service_content["volumes"])
+            .into_iter()
+            .chain(parse_tmpfs(&service_content["tmpfs"]))
+            .collect(),
+        secrets: parse_file_references(&service_content["secrets"], FileKind::Secret),
+        configs: parse_file_references(&service_content["configs"], FileKind::Config),
+        deploy: parse_deploy(&service_content),
+        healthcheck: parse_healthcheck(&service_content["healthcheck"]),
+        depends_on: parse_dependencies(&service_content["depends_on"]),
+        profiles: parse_profiles(&service_content["profiles"]),
+        // resolved with the top-level fields of the compose project
+        project: String::new(),
+        extension: parse_extension(&service_content)
     }
 }
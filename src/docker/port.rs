@@ -0,0 +1,303 @@
+/// Port
+///
+/// # Description
+/// Module use to parse the ports of a docker-compose service
+/// It supports the short syntax (e.g: `127.0.0.1:8000-8010:8000-8010/udp`) and the long syntax
+use serde::{Serialize, Deserialize};
+use yaml_rust::{yaml};
+use super::parser::get_yaml_scalar;
+use crate::core::logger::{log, LogType};
+
+/// Constant
+const PROTOCOL_SEPARATOR: char = '/';
+const PORT_SEPARATOR: char = ':';
+const RANGE_SEPARATOR: char = '-';
+const MAX_NAME_LENGTH: usize = 15;
+
+/// Protocol
+///
+/// List of protocol supported by K8S
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Protocol {
+    Tcp,
+    Udp,
+    Sctp
+}
+
+impl Default for Protocol {
+    fn default() -> Self {
+        Protocol::Tcp
+    }
+}
+
+impl Protocol {
+    /// From Value
+    ///
+    /// # Description
+    /// Retrieve a protocol from a compose protocol value. Default to TCP
+    ///
+    /// # Arguments
+    /// * `value` &str
+    ///
+    /// # Return
+    /// Protocol
+    pub fn from_value(value: &str) -> Protocol {
+        match value.to_lowercase().as_str() {
+            "udp" => Protocol::Udp,
+            "sctp" => Protocol::Sctp,
+            _ => Protocol::Tcp
+        }
+    }
+
+    /// Prefix
+    ///
+    /// # Description
+    /// Retrieve the lowercase prefix use to generate the port name
+    ///
+    /// # Return
+    /// &str
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+            Protocol::Sctp => "sctp"
+        }
+    }
+}
+
+/// Docker Port
+///
+/// # Description
+/// Structure representing a single port exposed by a docker-compose service
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DockerPort {
+    pub target: u16,
+    pub published: Option<u16>,
+    pub host_ip: Option<String>,
+    pub protocol: Protocol,
+    pub mode: Option<String>,
+    pub name: Option<String>
+}
+
+impl DockerPort {
+    /// Get Name
+    ///
+    /// # Description
+    /// Retrieve the name of the port or generate one based on the protocol & the target port (e.g: tcp-8080)
+    ///
+    /// # Return
+    /// String
+    pub fn get_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.to_lowercase(),
+            None => format!("{}-{}", self.protocol.prefix(), self.target)
+        }
+    }
+
+    /// Get Published
+    ///
+    /// # Description
+    /// Retrieve the published port. Fallback to the target port when the port isn't published
+    ///
+    /// # Return
+    /// u16
+    pub fn get_published(&self) -> u16 {
+        self.published.unwrap_or(self.target)
+    }
+}
+
+/// Parse Ports
+///
+/// # Description
+/// Retrieve the list of ports of a docker-compose service
+///
+/// # Arguments
+/// * `content` &yaml::Yaml the content of the `ports` field
+///
+/// # Return
+/// Vec<DockerPort>
+pub fn parse_ports(content: &yaml::Yaml) -> Vec<DockerPort> {
+    let list = match content.as_vec() {
+        Some(l) => l,
+        None => return Vec::new()
+    };
+
+    list.iter()
+        .flat_map(|port| {
+            if port.as_hash().is_some() {
+                return parse_long_syntax(port);
+            }
+
+            parse_short_syntax(&get_yaml_scalar(port))
+        })
+        .collect()
+}
+
+/// Parse Short Syntax
+///
+/// # Description
+/// Parse a port written with the short syntax [HOST_IP:][HOST:]CONTAINER[/PROTOCOL]
+///
+/// # Arguments
+/// * `raw` &str
+///
+/// # Return
+/// Vec<DockerPort>
+fn parse_short_syntax(raw: &str) -> Vec<DockerPort> {
+    let mut parts = raw.trim().splitn(2, PROTOCOL_SEPARATOR);
+    let mapping = parts.next().unwrap_or("");
+    let protocol = Protocol::from_value(parts.next().unwrap_or(""));
+
+    // reverse split in order to keep ipv6 host ip in one piece
+    let mut values = mapping.rsplitn(3, PORT_SEPARATOR);
+    let target = values.next().unwrap_or("");
+    let published = values.next().unwrap_or("");
+    let host_ip = values
+        .next()
+        .map(|ip| ip.trim_matches(|c| c == '[' || c == ']').to_owned())
+        .filter(|ip| !ip.is_empty());
+
+    expand_ports(target, published)
+        .into_iter()
+        .map(|(target, published)| DockerPort {
+            target,
+            published,
+            host_ip: host_ip.clone(),
+            protocol,
+            mode: None,
+            name: None
+        })
+        .collect()
+}
+
+/// Parse Long Syntax
+///
+/// # Description
+/// Parse a port written with the long syntax (target, published, protocol, mode)
+///
+/// # Arguments
+/// * `content` &yaml::Yaml
+///
+/// # Return
+/// Vec<DockerPort>
+fn parse_long_syntax(content: &yaml::Yaml) -> Vec<DockerPort> {
+    let protocol = Protocol::from_value(&get_yaml_scalar(&content["protocol"]));
+    let mode = Some(get_yaml_scalar(&content["mode"])).filter(|m| !m.is_empty());
+    let name = get_port_name(&get_yaml_scalar(&content["name"]));
+    let host_ip = Some(get_yaml_scalar(&content["host_ip"])).filter(|ip| !ip.is_empty());
+
+    expand_ports(&get_yaml_scalar(&content["target"]), &get_yaml_scalar(&content["published"]))
+        .into_iter()
+        .map(|(target, published)| DockerPort {
+            target,
+            published,
+            host_ip: host_ip.clone(),
+            protocol,
+            mode: mode.clone(),
+            name: name.clone()
+        })
+        .collect()
+}
+
+/// Get Port Name
+///
+/// # Description
+/// Retrieve the name of a port written with the long syntax. The name is used by the Service & the container
+/// thus it must be a valid IANA service name (at most 15 lowercase alphanumeric characters or '-', with at least
+/// one letter and without any leading, trailing or consecutive '-'). An invalid name is replaced by the generated
+/// one with a warning
+///
+/// # Arguments
+/// * `raw` &str
+///
+/// # Return
+/// Option<String>
+fn get_port_name(raw: &str) -> Option<String> {
+    let name = raw.trim().to_lowercase();
+    if name.is_empty() {
+        return None;
+    }
+
+    let valid = name.len() <= MAX_NAME_LENGTH
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && name.chars().any(|c| c.is_ascii_lowercase())
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && !name.contains("--");
+
+    if !valid {
+        log(
+            LogType::Warning,
+            &format!("The port name {} is not a valid IANA service name and is replaced by a generated name", raw),
+            None
+        );
+        return None;
+    }
+
+    Some(name)
+}
+
+/// Expand Ports
+///
+/// # Description
+/// Expand the target & the published ports which could be a range into a list of couple
+///
+/// # Arguments
+/// * `target` &str
+/// * `published` &str
+///
+/// # Return
+/// Vec<(u16, Option<u16>)>
+fn expand_ports(target: &str, published: &str) -> Vec<(u16, Option<u16>)> {
+    let targets = match parse_range(target) {
+        Some(t) => t,
+        None => return Vec::new()
+    };
+
+    let published = parse_range(published);
+    (targets.0..=targets.1)
+        .enumerate()
+        .map(|(idx, port)| {
+            let host = published.and_then(|(start, end)| {
+                let value = u32::from(start) + idx as u32;
+                if start == end {
+                    return Some(start);
+                }
+
+                if value > u32::from(end) {
+                    return None;
+                }
+
+                Some(value as u16)
+            });
+
+            (port, host)
+        })
+        .collect()
+}
+
+/// Parse Range
+///
+/// # Description
+/// Parse a port or a range of port (e.g: 8000 or 8000-8010)
+///
+/// # Arguments
+/// * `value` &str
+///
+/// # Return
+/// Option<(u16, u16)>
+fn parse_range(value: &str) -> Option<(u16, u16)> {
+    let mut bounds = value.trim().splitn(2, RANGE_SEPARATOR);
+    let start = bounds.next()?.trim().parse::<u16>().ok()?;
+    let end = match bounds.next() {
+        Some(e) => e.trim().parse::<u16>().ok()?,
+        None => start
+    };
+
+    if start == 0 || end < start {
+        return None;
+    }
+
+    Some((start, end))
+}
@@ -0,0 +1,241 @@
+/// Secret
+///
+/// # Description
+/// Module use to parse the top-level `secrets` & `configs` of a docker-compose file and the references
+/// made by the services. Both are files mounted into the containers of a service
+use std::convert::TryFrom;
+use std::path::PathBuf;
+use serde::{Serialize};
+use yaml_rust::{yaml};
+use super::parser::get_yaml_scalar;
+use crate::core::errors::cli_error::{CliErr, ErrMessage, ErrHelper};
+use crate::core::errors::message::docker::UNABLE_READ_FILE_OBJECT;
+
+/// Constant
+const SECRETS_FOLDER: &str = "/run/secrets/";
+const CONFIGS_FOLDER: &str = "/";
+const OCTAL_PREFIX: &str = "0o";
+
+/// File Kind
+///
+/// List of the kind of files supported by docker-compose
+#[derive(Serialize, Clone, Debug, Copy, PartialEq)]
+pub enum FileKind {
+    Secret,
+    Config
+}
+
+impl FileKind {
+    /// Get Target
+    ///
+    /// # Description
+    /// Retrieve the absolute path where a file is mounted. A relative target is resolved from
+    /// the default folder of the kind
+    ///
+    /// # Arguments
+    /// * `target` &str
+    ///
+    /// # Return
+    /// String
+    fn get_target(self, target: &str) -> String {
+        if target.starts_with('/') {
+            return String::from(target);
+        }
+
+        match self {
+            FileKind::Secret => format!("{}{}", SECRETS_FOLDER, target),
+            FileKind::Config => format!("{}{}", CONFIGS_FOLDER, target)
+        }
+    }
+}
+
+/// Docker File Object
+///
+/// # Description
+/// Structure representing a secret or a config declared in the top-level `secrets` or `configs` key
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DockerFileObject {
+    pub name: String,
+    pub kind: FileKind,
+    pub file: Option<String>,
+    pub data: Vec<u8>,
+    pub external: bool,
+    pub external_name: Option<String>
+}
+
+/// Docker File Reference
+///
+/// # Description
+/// Structure representing a secret or a config mounted by a docker-compose service
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DockerFileReference {
+    pub source: String,
+    pub target: String,
+    pub uid: Option<String>,
+    pub gid: Option<String>,
+    pub mode: Option<u32>,
+    // name of the existing object when the file is external
+    pub external_name: Option<String>
+}
+
+/// Parse File Objects
+///
+/// # Description
+/// Retrieve the secrets or the configs declared in a top-level key. A file could either be read from a
+/// `file`, written inline with `content` or be `external` (the object already exist in the cluster)
+///
+/// # Arguments
+/// * `content` &yaml::Yaml the content of the top-level `secrets` or `configs` field
+/// * `kind` FileKind
+///
+/// # Return
+/// Vec<DockerFileObject>
+pub fn parse_file_objects(content: &yaml::Yaml, kind: FileKind) -> Vec<DockerFileObject> {
+    let hash = match content.as_hash() {
+        Some(h) => h,
+        None => return Vec::new()
+    };
+
+    hash.iter()
+        .map(|(name, value)| {
+            let name = get_yaml_scalar(name);
+            let external = value["external"].as_bool().unwrap_or(false) || value["external"].as_hash().is_some();
+            let external_name = Some(get_yaml_scalar(&value["name"]))
+                .filter(|n| !n.is_empty())
+                .or_else(|| Some(get_yaml_scalar(&value["external"]["name"])).filter(|n| !n.is_empty()))
+                .or_else(|| Some(name.clone()))
+                .filter(|_| external);
+
+            DockerFileObject {
+                kind,
+                file: Some(get_yaml_scalar(&value["file"])).filter(|f| !f.is_empty()),
+                data: get_yaml_scalar(&value["content"]).into_bytes(),
+                external,
+                external_name,
+                name
+            }
+        })
+        .filter(|f| !f.name.is_empty())
+        .collect()
+}
+
+/// Parse File References
+///
+/// # Description
+/// Retrieve the secrets or the configs mounted by a service. The short syntax only reference the name
+/// of the file which is mounted at /run/secrets/<name> for a secret and at /<name> for a config
+///
+/// # Arguments
+/// * `content` &yaml::Yaml the content of the `secrets` or `configs` field of a service
+/// * `kind` FileKind
+///
+/// # Return
+/// Vec<DockerFileReference>
+pub fn parse_file_references(content: &yaml::Yaml, kind: FileKind) -> Vec<DockerFileReference> {
+    let list = match content.as_vec() {
+        Some(l) => l,
+        None => return Vec::new()
+    };
+
+    list.iter()
+        .filter_map(|item| {
+            let source = match item.as_hash() {
+                Some(_) => get_yaml_scalar(&item["source"]),
+                None => get_yaml_scalar(item)
+            };
+
+            if source.is_empty() {
+                return None;
+            }
+
+            let target = Some(get_yaml_scalar(&item["target"]))
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| source.clone());
+
+            Some(DockerFileReference {
+                target: kind.get_target(&target),
+                uid: Some(get_yaml_scalar(&item["uid"])).filter(|u| !u.is_empty()),
+                gid: Some(get_yaml_scalar(&item["gid"])).filter(|g| !g.is_empty()),
+                mode: parse_mode(&item["mode"]),
+                external_name: None,
+                source
+            })
+        })
+        .collect()
+}
+
+/// Resolve External Files
+///
+/// # Description
+/// Set the name of the existing object of the references which refer to an external file
+///
+/// # Arguments
+/// * `references` &mut [DockerFileReference]
+/// * `objects` &[DockerFileObject] top-level files of the same kind
+pub fn resolve_external_files(references: &mut [DockerFileReference], objects: &[DockerFileObject]) {
+    for r in references.iter_mut() {
+        r.external_name = objects
+            .iter()
+            .find(|o| o.name == r.source)
+            .and_then(|o| o.external_name.clone());
+    }
+}
+
+/// Load File Objects
+///
+/// # Description
+/// Read the content of the files of the secrets & the configs. The files are resolved relative
+/// to the docker-compose file
+///
+/// # Arguments
+/// * `objects` &mut [DockerFileObject]
+/// * `base` &str folder of the docker-compose file
+///
+/// # Return
+/// Result<(), CliErr>
+pub fn load_file_objects(objects: &mut [DockerFileObject], base: &str) -> Result<(), CliErr> {
+    for obj in objects.iter_mut().filter(|o| !o.external) {
+        let file = match &obj.file {
+            Some(f) => f,
+            None => continue
+        };
+
+        let mut path = PathBuf::from(base);
+        path.push(file);
+
+        match std::fs::read(&path) {
+            Ok(data) => obj.data = data,
+            Err(err) => {
+                let reason = format!("{}: {}", file, err);
+                return Err(CliErr::new(UNABLE_READ_FILE_OBJECT, &reason, ErrMessage::IOError));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse Mode
+///
+/// # Description
+/// Parse the permissions of a mounted file. A number is used as it is (e.g: 0o440 or 288) while a string
+/// is read in octal (e.g: '0440'). The loader keeps the modes written with a leading zero as strings
+///
+/// # Arguments
+/// * `value` &yaml::Yaml
+///
+/// # Return
+/// Option<u32>
+fn parse_mode(value: &yaml::Yaml) -> Option<u32> {
+    match value {
+        yaml::Yaml::Integer(mode) => u32::try_from(*mode).ok(),
+        yaml::Yaml::String(mode) => {
+            let octal = match mode.starts_with(OCTAL_PREFIX) {
+                true => &mode[OCTAL_PREFIX.len()..],
+                false => mode.as_str()
+            };
+            u32::from_str_radix(octal, 8).ok()
+        },
+        _ => None
+    }
+}
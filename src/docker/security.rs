@@ -0,0 +1,114 @@
+/// Security
+///
+/// # Description
+/// Module use to parse the security related fields of a docker-compose service
+/// (privileged, cap_add, cap_drop, read_only, security_opt & the host namespaces)
+use serde::{Serialize};
+use yaml_rust::{yaml};
+use super::parser::get_yaml_scalar;
+
+/// Constant
+const HOST_MODE: &str = "host";
+const CAP_PREFIX: &str = "CAP_";
+
+/// Docker Security
+///
+/// # Description
+/// Structure representing the security options of a docker-compose service
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct DockerSecurity {
+    pub privileged: bool,
+    pub read_only: bool,
+    pub cap_add: Vec<String>,
+    pub cap_drop: Vec<String>,
+    pub security_opt: Vec<String>,
+    // namespaces shared with the host (network_mode, pid, ipc & userns_mode set to `host`)
+    pub host_network: bool,
+    pub host_pid: bool,
+    pub host_ipc: bool,
+    pub host_users: bool
+}
+
+impl DockerSecurity {
+    /// Is Privileged
+    ///
+    /// # Description
+    /// Check whether the service is privileged or share a namespace with the host
+    ///
+    /// # Return
+    /// bool
+    pub fn is_privileged(&self) -> bool {
+        self.privileged || self.host_network || self.host_pid || self.host_ipc || self.host_users
+    }
+}
+
+/// Parse Security
+///
+/// # Description
+/// Retrieve the security options of a docker-compose service. The capabilities are returned
+/// without the `CAP_` prefix (e.g: CAP_NET_ADMIN -> NET_ADMIN)
+///
+/// # Arguments
+/// * `service` &yaml::Yaml the content of the docker-compose service
+///
+/// # Return
+/// DockerSecurity
+pub fn parse_security(service: &yaml::Yaml) -> DockerSecurity {
+    let is_host = |field: &str| get_yaml_scalar(&service[field]) == HOST_MODE;
+
+    DockerSecurity {
+        privileged: service["privileged"].as_bool().unwrap_or(false),
+        read_only: service["read_only"].as_bool().unwrap_or(false),
+        cap_add: parse_capabilities(&service["cap_add"]),
+        cap_drop: parse_capabilities(&service["cap_drop"]),
+        security_opt: get_list(&service["security_opt"]),
+        host_network: is_host("network_mode"),
+        host_pid: is_host("pid"),
+        host_ipc: is_host("ipc"),
+        host_users: is_host("userns_mode")
+    }
+}
+
+/// Parse Capabilities
+///
+/// # Description
+/// Retrieve a list of capabilities
+///
+/// # Arguments
+/// * `content` &yaml::Yaml
+///
+/// # Return
+/// Vec<String>
+fn parse_capabilities(content: &yaml::Yaml) -> Vec<String> {
+    get_list(content)
+        .into_iter()
+        .map(|c| {
+            let upper = c.to_uppercase();
+            match upper.starts_with(CAP_PREFIX) {
+                true => String::from(&upper[CAP_PREFIX.len()..]),
+                false => upper
+            }
+        })
+        .collect()
+}
+
+/// Get List
+///
+/// # Description
+/// Retrieve the non empty values of a list
+///
+/// # Arguments
+/// * `content` &yaml::Yaml
+///
+/// # Return
+/// Vec<String>
+fn get_list(content: &yaml::Yaml) -> Vec<String> {
+    match content.as_vec() {
+        Some(list) => list
+            .iter()
+            .map(|v| get_yaml_scalar(v).trim().to_owned())
+            .filter(|v| !v.is_empty())
+            .collect(),
+        None => Vec::new()
+    }
+}
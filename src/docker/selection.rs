@@ -0,0 +1,125 @@
+/// Selection
+///
+/// # Description
+/// Module use to select the services of the docker-compose which need to be generated. A service is
+/// selected when it doesn't have any profile, when one of its profiles is active or when it is listed
+/// explicitly. The services the selected services depends on are selected as well
+use std::collections::BTreeSet;
+use yaml_rust::{yaml};
+use super::parser::{DockerService, get_yaml_scalar};
+use super::secret::{DockerFileObject, FileKind};
+use crate::core::errors::cli_error::{CliErr, ErrMessage, ErrHelper};
+use crate::core::errors::message::docker::UNKNOWN_SERVICE;
+
+/// Constant
+const ALL_PROFILES: &str = "*";
+
+/// Parse Profiles
+///
+/// # Description
+/// Retrieve the profiles of a docker-compose service
+///
+/// # Arguments
+/// * `content` &yaml::Yaml the content of the `profiles` field
+///
+/// # Return
+/// Vec<String>
+pub fn parse_profiles(content: &yaml::Yaml) -> Vec<String> {
+    match content.as_vec() {
+        Some(list) => list
+            .iter()
+            .map(get_yaml_scalar)
+            .filter(|p| !p.is_empty())
+            .collect(),
+        None => Vec::new()
+    }
+}
+
+/// Select Services
+///
+/// # Description
+/// Retrieve the services enabled by the active profiles or the services listed by their names,
+/// along with the services they depends on. The order of the docker-compose is kept
+///
+/// # Arguments
+/// * `services` Vec<DockerService>
+/// * `profiles` &[String] active profiles
+/// * `names` &[String] names of the services to select, the profiles are ignored when a service is listed
+///
+/// # Return
+/// Result<Vec<DockerService>, CliErr>
+pub fn select_services(services: Vec<DockerService>, profiles: &[String], names: &[String]) -> Result<Vec<DockerService>, CliErr> {
+    if let Some(unknown) = names.iter().find(|n| !services.iter().any(|s| &s.name == *n)) {
+        return Err(CliErr::new(UNKNOWN_SERVICE, unknown, ErrMessage::NotFound));
+    }
+
+    let mut pending: Vec<String> = services
+        .iter()
+        .filter(|s| if names.is_empty() {
+            is_enabled(s, profiles)
+        } else {
+            names.contains(&s.name)
+        })
+        .map(|s| s.name.clone())
+        .collect();
+
+    let mut selected = BTreeSet::new();
+    while let Some(name) = pending.pop() {
+        if !selected.insert(name.clone()) {
+            continue;
+        }
+
+        if let Some(svc) = services.iter().find(|s| s.name == name) {
+            pending.extend(svc.depends_on.iter().map(|d| d.name.clone()));
+        }
+    }
+
+    Ok(services
+        .into_iter()
+        .filter(|s| selected.contains(&s.name))
+        .collect())
+}
+
+/// Select Files
+///
+/// # Description
+/// Retrieve the secrets & the configs used by the selected services
+///
+/// # Arguments
+/// * `files` Vec<DockerFileObject>
+/// * `services` &[DockerService]
+///
+/// # Return
+/// Vec<DockerFileObject>
+pub fn select_files(files: Vec<DockerFileObject>, services: &[DockerService]) -> Vec<DockerFileObject> {
+    files
+        .into_iter()
+        .filter(|f| services
+            .iter()
+            .flat_map(|s| match f.kind {
+                FileKind::Secret => s.secrets.iter(),
+                FileKind::Config => s.configs.iter()
+            })
+            .any(|r| r.source == f.name)
+        )
+        .collect()
+}
+
+/// Is Enabled
+///
+/// # Description
+/// Check whether a service is enabled by the active profiles
+///
+/// # Arguments
+/// * `service` &DockerService
+/// * `profiles` &[String]
+///
+/// # Return
+/// bool
+fn is_enabled(service: &DockerService, profiles: &[String]) -> bool {
+    if service.profiles.is_empty() || profiles.iter().any(|p| p == ALL_PROFILES) {
+        return true;
+    }
+
+    service.profiles.iter().any(|p| profiles.contains(p))
+}
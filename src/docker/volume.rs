@@ -0,0 +1,381 @@
+/// Volume
+///
+/// # Description
+/// Module use to parse the volumes of a docker-compose service and the top-level volumes
+/// It supports the short syntax (e.g: `db-data:/var/lib/mysql:ro`), the long syntax and the `tmpfs` field
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Serialize};
+use yaml_rust::{yaml};
+use super::parser::{DockerService, get_yaml_scalar};
+
+/// Constant
+const VOLUME_SEPARATOR: char = ':';
+const READ_ONLY_MODE: &str = "ro";
+const TMPFS_SIZE_OPTION: &str = "size=";
+// a ConfigMap can't store more than 1MiB
+const MAX_BIND_SIZE: u64 = 1024 * 1024;
+
+/// Volume Kind
+///
+/// List of the kind of volume supported by docker-compose
+#[derive(Serialize, Clone, Debug, Copy, PartialEq)]
+pub enum VolumeKind {
+    Volume,
+    Bind,
+    Tmpfs
+}
+
+impl Default for VolumeKind {
+    fn default() -> Self {
+        VolumeKind::Volume
+    }
+}
+
+impl VolumeKind {
+    /// From Source
+    ///
+    /// # Description
+    /// Guess the kind of volume based on the source written with the short syntax
+    ///
+    /// # Arguments
+    /// * `source` &str
+    ///
+    /// # Return
+    /// VolumeKind
+    fn from_source(source: &str) -> VolumeKind {
+        if source.starts_with('.') || source.starts_with('/') || source.starts_with('~') {
+            return VolumeKind::Bind;
+        }
+
+        VolumeKind::Volume
+    }
+}
+
+/// Docker Volume
+///
+/// # Description
+/// Structure representing a volume mounted by a docker-compose service
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct DockerVolume {
+    pub kind: VolumeKind,
+    pub source: String,
+    pub target: String,
+    pub read_only: bool,
+    pub external: bool,
+    // name of the existing claim of an external volume (e.g: name: db-claim)
+    pub external_name: Option<String>,
+    // size of a tmpfs (e.g: 64m)
+    pub size: Option<String>,
+    // files of a bind mount small enough to be stored in a ConfigMap
+    pub content: Option<DockerBindContent>
+}
+
+/// Docker Bind Content
+///
+/// # Description
+/// Structure representing the files of a bind mount. The source is either a single file
+/// or a folder containing only files
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DockerBindContent {
+    pub is_file: bool,
+    pub files: Vec<DockerBindFile>
+}
+
+/// Docker Bind File
+///
+/// # Description
+/// Structure representing a file of a bind mount
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DockerBindFile {
+    pub name: String,
+    pub data: Vec<u8>
+}
+
+impl DockerVolume {
+    /// Is Named
+    ///
+    /// # Description
+    /// Check whenever the volume refer to a named volume
+    ///
+    /// # Return
+    /// bool
+    pub fn is_named(&self) -> bool {
+        self.kind == VolumeKind::Volume && !self.source.is_empty()
+    }
+}
+
+/// Docker Named Volume
+///
+/// # Description
+/// Structure representing a volume declared in the top-level `volumes` key
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DockerNamedVolume {
+    pub name: String,
+    pub external: bool,
+    pub external_name: Option<String>
+}
+
+/// Parse Volumes
+///
+/// # Description
+/// Retrieve the list of volumes of a docker-compose service
+///
+/// # Arguments
+/// * `content` &yaml::Yaml the content of the `volumes` field
+///
+/// # Return
+/// Vec<DockerVolume>
+pub fn parse_volumes(content: &yaml::Yaml) -> Vec<DockerVolume> {
+    let list = match content.as_vec() {
+        Some(l) => l,
+        None => return Vec::new()
+    };
+
+    list.iter()
+        .filter_map(|volume| {
+            if volume.as_hash().is_some() {
+                return parse_long_syntax(volume);
+            }
+
+            parse_short_syntax(&get_yaml_scalar(volume))
+        })
+        .collect()
+}
+
+/// Parse Named Volumes
+///
+/// # Description
+/// Retrieve the list of volumes declared in the top-level `volumes` key
+///
+/// # Arguments
+/// * `content` &yaml::Yaml the content of the top-level `volumes` field
+///
+/// # Return
+/// Vec<DockerNamedVolume>
+pub fn parse_named_volumes(content: &yaml::Yaml) -> Vec<DockerNamedVolume> {
+    let hash = match content.as_hash() {
+        Some(h) => h,
+        None => return Vec::new()
+    };
+
+    hash.iter()
+        .map(|(name, value)| {
+            let external = value["external"].as_bool().unwrap_or(false) || value["external"].as_hash().is_some();
+            let external_name = Some(get_yaml_scalar(&value["name"]))
+                .filter(|n| !n.is_empty())
+                .or_else(|| Some(get_yaml_scalar(&value["external"]["name"])).filter(|n| !n.is_empty()))
+                .filter(|_| external);
+
+            DockerNamedVolume {
+                name: get_yaml_scalar(name),
+                external,
+                external_name
+            }
+        })
+        .filter(|v| !v.name.is_empty())
+        .collect()
+}
+
+/// Resolve External Volumes
+///
+/// # Description
+/// Flag the volumes of a service which refer to an external top-level volume and retrieve
+/// the name of the existing volume
+///
+/// # Arguments
+/// * `volumes` &mut [DockerVolume]
+/// * `named` &[DockerNamedVolume]
+pub fn resolve_external_volumes(volumes: &mut [DockerVolume], named: &[DockerNamedVolume]) {
+    for v in volumes.iter_mut().filter(|v| v.is_named()) {
+        let external = named.iter().find(|n| n.external && n.name == v.source);
+        v.external = external.is_some();
+        v.external_name = external.and_then(|n| n.external_name.clone());
+    }
+}
+
+/// Parse Tmpfs
+///
+/// # Description
+/// Retrieve the tmpfs mounts of the `tmpfs` field of a service (e.g: /run or [/tmp:size=64m,mode=1777])
+///
+/// # Arguments
+/// * `content` &yaml::Yaml the content of the `tmpfs` field
+///
+/// # Return
+/// Vec<DockerVolume>
+pub fn parse_tmpfs(content: &yaml::Yaml) -> Vec<DockerVolume> {
+    let values = match content.as_vec() {
+        Some(list) => list.iter().map(get_yaml_scalar).collect(),
+        None => vec![get_yaml_scalar(content)]
+    };
+
+    values
+        .into_iter()
+        .filter_map(|value| {
+            let mut parts = value.splitn(2, VOLUME_SEPARATOR);
+            let target = parts.next().unwrap_or("").trim();
+            let options = parts.next().unwrap_or("");
+
+            if target.is_empty() {
+                return None;
+            }
+
+            let size = options
+                .split(',')
+                .map(str::trim)
+                .find(|o| o.starts_with(TMPFS_SIZE_OPTION))
+                .map(|o| String::from(&o[TMPFS_SIZE_OPTION.len()..]));
+
+            Some(DockerVolume {
+                kind: VolumeKind::Tmpfs,
+                target: String::from(target),
+                size,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Resolve Binds
+///
+/// # Description
+/// Resolve the relative source of the bind mounts from the folder of the docker-compose file and load
+/// the files of the sources located in this folder. A source is loaded when it's a file or a folder
+/// containing only files and when its size fits in a ConfigMap
+///
+/// # Arguments
+/// * `services` &mut [DockerService]
+/// * `base` &str folder of the docker-compose file
+pub fn resolve_binds(services: &mut [DockerService], base: &str) {
+    let folder = fs::canonicalize(base).unwrap_or_else(|_| PathBuf::from(base));
+    let binds = services
+        .iter_mut()
+        .flat_map(|s| s.volumes.iter_mut())
+        .filter(|v| v.kind == VolumeKind::Bind && v.source.starts_with('.'));
+
+    for volume in binds {
+        let joined = folder.join(&volume.source);
+        let path = fs::canonicalize(&joined).unwrap_or(joined);
+        if path.starts_with(&folder) {
+            volume.content = load_bind_content(&path);
+        }
+
+        volume.source = path.to_string_lossy().to_string();
+    }
+}
+
+/// Load Bind Content
+///
+/// # Description
+/// Load the files of a bind mount
+///
+/// # Arguments
+/// * `path` &Path
+///
+/// # Return
+/// Option<DockerBindContent>
+fn load_bind_content(path: &Path) -> Option<DockerBindContent> {
+    let metadata = fs::metadata(path).ok()?;
+    let is_file = metadata.is_file();
+    let paths: Vec<PathBuf> = if is_file {
+        vec![path.to_path_buf()]
+    } else {
+        let mut entries = fs::read_dir(path)
+            .ok()?
+            .map(|e| e.map(|entry| entry.path()))
+            .collect::<Result<Vec<PathBuf>, _>>()
+            .ok()?;
+
+        entries.sort();
+        entries
+    };
+
+    let mut size = 0;
+    let mut files = Vec::new();
+    for p in paths {
+        let meta = fs::metadata(&p).ok()?;
+        size += meta.len();
+        // nested folders can't be stored in a ConfigMap
+        if !meta.is_file() || size > MAX_BIND_SIZE {
+            return None;
+        }
+
+        files.push(DockerBindFile {
+            name: p.file_name()?.to_string_lossy().to_string(),
+            data: fs::read(&p).ok()?
+        });
+    }
+
+    Some(DockerBindContent {
+        is_file,
+        files
+    })
+}
+
+/// Parse Short Syntax
+///
+/// # Description
+/// Parse a volume written with the short syntax [SOURCE:]TARGET[:MODE]
+///
+/// # Arguments
+/// * `raw` &str
+///
+/// # Return
+/// Option<DockerVolume>
+fn parse_short_syntax(raw: &str) -> Option<DockerVolume> {
+    let parts: Vec<&str> = raw.trim().split(VOLUME_SEPARATOR).collect();
+    let (source, target, mode) = match parts.as_slice() {
+        [target] => ("", *target, ""),
+        [source, target] => (*source, *target, ""),
+        [source, target, mode] => (*source, *target, *mode),
+        _ => return None
+    };
+
+    if target.is_empty() {
+        return None;
+    }
+
+    Some(DockerVolume {
+        kind: VolumeKind::from_source(source),
+        source: String::from(source),
+        target: String::from(target),
+        read_only: mode.split(',').any(|m| m == READ_ONLY_MODE),
+        ..Default::default()
+    })
+}
+
+/// Parse Long Syntax
+///
+/// # Description
+/// Parse a volume written with the long syntax (type, source, target, read_only)
+///
+/// # Arguments
+/// * `content` &yaml::Yaml
+///
+/// # Return
+/// Option<DockerVolume>
+fn parse_long_syntax(content: &yaml::Yaml) -> Option<DockerVolume> {
+    let source = get_yaml_scalar(&content["source"]);
+    let target = get_yaml_scalar(&content["target"]);
+    if target.is_empty() {
+        return None;
+    }
+
+    let kind = match get_yaml_scalar(&content["type"]).as_str() {
+        "bind" => VolumeKind::Bind,
+        "tmpfs" => VolumeKind::Tmpfs,
+        "volume" => VolumeKind::Volume,
+        _ => VolumeKind::from_source(&source)
+    };
+
+    let size = Some(get_yaml_scalar(&content["tmpfs"]["size"])).filter(|s| !s.is_empty());
+    Some(DockerVolume {
+        kind,
+        source,
+        target,
+        read_only: content["read_only"].as_bool().unwrap_or(false),
+        size,
+        ..Default::default()
+    })
+}
@@ -5,21 +5,37 @@
 /// Module helping to generate a K8S struct with the following syntax
 /// - Object (Controller)
 /// - Service
+/// - ConfigMap & Secret (environment)
 use std::collections::HashMap;
 use std::path::PathBuf;
 use crate::docker::parser::{DockerService};
-use crate::kubernetes::controllers::controller::{KubeController};
-use crate::kubernetes::controllers::service::{KubeService};
+use crate::docker::volume::{DockerVolume, DockerNamedVolume};
+use crate::kubernetes::controllers::controller::{KubeController, ControllerKind};
+use crate::kubernetes::controllers::service::{KubeService, SVC_SUFFIX, get_headless_name};
+use crate::kubernetes::controllers::host::get_hostname;
 use crate::kubernetes::controllers::ingress::{KubeIngress};
-use crate::confiture::config::{ConfigConfiture, ConfigIngress};
+use crate::kubernetes::controllers::dependency::{get_init_containers};
+use crate::kubernetes::controllers::environment::{KubeEnvObject, get_environment};
+use crate::kubernetes::controllers::volume::{KubeVolumeClaim, get_volume_claims};
+use crate::kubernetes::controllers::mount::{get_mount_volumes};
+use crate::kubernetes::controllers::secret::{KubeFileObject};
+use crate::confiture::config::{ConfigConfiture, ConfigIngress, ConfigVolume};
 use crate::core::configurator::config;
 use crate::core::fs::toolbox;
+use crate::core::errors::cli_error::{CliErr, ErrHelper, ErrMessage};
+use crate::core::errors::message::cli::SELECTOR_COLLISION;
+use crate::core::logger::{log, LogType};
 
 /// Structure wrapping the Controller & the Service
 #[derive(Debug)]
 pub struct Kube {
     pub ctrl: KubeController,
     pub svc: Option<KubeService>,
+    pub headless: Option<KubeService>,
+    pub config_map: Option<KubeEnvObject>,
+    pub secret: Option<KubeEnvObject>,
+    // ConfigMaps of the bind mounts
+    pub mounts: Vec<KubeFileObject>,
     pub project_path: PathBuf
 }
 
@@ -31,27 +47,59 @@ pub struct Kube {
 /// # Arguments
 /// * `dk_vec` &[DockerService]
 /// * `options` Confiture configuration struct
+/// * `volumes` &[ConfigVolume]
+/// * `patterns` &[String] patterns of the environment variables stored in a Secret
 ///
 /// # Return
 /// * - `Kube vector` List of Kube
-pub fn get_basic_objects(dk_vec: &[DockerService], options: HashMap<String, &ConfigConfiture>) -> Vec<Kube> {
+pub fn get_basic_objects(dk_vec: &[DockerService], options: HashMap<String, &ConfigConfiture>, volumes: &[ConfigVolume], patterns: &[String]) -> Vec<Kube> {
+    // service which doesn't have any confiture
+    let default = ConfigConfiture::default();
+    // the services skipped by the confiture can't be awaited
+    let generated: Vec<DockerService> = dk_vec
+        .iter()
+        .filter(|dk| !options.get(&dk.name).map_or(false, |o| o.skip))
+        .cloned()
+        .collect();
+
     let kube_containers: Vec<Kube> = dk_vec
         .iter()
         .filter_map(|dk| {
             let base_path = get_object_path(&dk.name).unwrap_or_default();
-            let option = options.get(&dk.name).unwrap();
+            let option = options.get(&dk.name).copied().unwrap_or(&default);
+            if option.skip {
+                return None;
+            }
 
             // Create the 2 basic element of a K8S cluster
             // services (networking)
             let svc = KubeService::new(dk.clone(), &option.service, &base_path);
             // controller (workload)
-            let ctrl = KubeController::new(dk.clone(), &option.deployment, &base_path);
+            let mut ctrl = KubeController::new(dk.clone(), &option.deployment, volumes, &base_path)?;
+            // init containers waiting for the services the controller depends on
+            ctrl.init_containers = get_init_containers(dk, &generated, &option.deployment);
+            // bind mounts, tmpfs & anonymous volumes
+            let (mut pod_volumes, mut volume_mounts, mounts) = get_mount_volumes(&dk.name, &dk.volumes, &option.deployment.mounts, &ctrl.pod_labels);
+            ctrl.volumes.append(&mut pod_volumes);
+            ctrl.volume_mounts.append(&mut volume_mounts);
+            // environment (configuration & sensitive values)
+            let (config_map, secret) = get_environment(dk, patterns, &base_path);
+            ctrl.config_map_ref = config_map.as_ref().map(|c| c.name.clone());
+            ctrl.secret_env = secret
+                .as_ref()
+                .map(|s| s.get_secret_env_vars(&dk.environment))
+                .unwrap_or_default();
+            // identity of the pods of a StatefulSet or resolution of the subdomain
+            let headless = get_headless_service(&mut ctrl, dk, dk_vec, &base_path);
 
-            ctrl.as_ref()?;
             Some(
                 Kube {
-                    ctrl: ctrl.unwrap(),
+                    ctrl,
                     svc,
+                    headless,
+                    config_map,
+                    secret,
+                    mounts,
                     project_path: base_path
                 }
             )
@@ -61,6 +109,82 @@ pub fn get_basic_objects(dk_vec: &[DockerService], options: HashMap<String, &Con
     kube_containers
 }
 
+/// Get Headless Service
+///
+/// # Description
+/// Retrieve the headless service of a controller. A StatefulSet need one to govern the identity of its pods
+/// and replace their subdomain by its name. The other controllers need one named after the subdomain of their
+/// pods to resolve their hostname. A subdomain shared with an other service or which is the name of an other
+/// Service is skipped with a warning
+///
+/// # Arguments
+/// * `ctrl` &mut KubeController
+/// * `dk` &DockerService
+/// * `dk_vec` &[DockerService]
+/// * `base_path` &PathBuf
+///
+/// # Return
+/// Option<KubeService>
+fn get_headless_service(ctrl: &mut KubeController, dk: &DockerService, dk_vec: &[DockerService], base_path: &PathBuf) -> Option<KubeService> {
+    if ctrl.ctrl == ControllerKind::StatefulSet {
+        if let Some(subdomain) = ctrl.subdomain.take() {
+            log(
+                LogType::Warning,
+                &format!("The subdomain {} of the service {} is replaced by the headless Service of the StatefulSet and is skipped", subdomain, dk.name),
+                None
+            );
+        }
+
+        return Some(KubeService::new_headless(dk.clone(), &get_headless_name(&dk.name), base_path));
+    }
+
+    let subdomain = ctrl.subdomain.clone()?;
+    let taken = dk_vec.iter().any(|d| {
+        let shared = d.name != dk.name && get_hostname(&d.host).1.as_ref() == Some(&subdomain);
+        shared || format!("{}{}", d.name, SVC_SUFFIX) == subdomain || get_headless_name(&d.name) == subdomain
+    });
+
+    if taken {
+        log(
+            LogType::Warning,
+            &format!("The subdomain {} of the service {} is used by an other service and is skipped", subdomain, dk.name),
+            None
+        );
+        ctrl.subdomain = None;
+        return None;
+    }
+
+    Some(KubeService::new_headless(dk.clone(), &subdomain, base_path))
+}
+
+/// Check Selectors
+///
+/// # Description
+/// Refuse the controllers which select the pods of another controller. The selector is built from the name
+/// of the service which could collide once converted to a label (e.g: truncated names)
+///
+/// # Arguments
+/// * `kubes` &[Kube]
+///
+/// # Return
+/// Result<(), CliErr>
+pub fn check_selectors(kubes: &[Kube]) -> Result<(), CliErr> {
+    for (idx, kube) in kubes.iter().enumerate() {
+        let collision = kubes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != idx)
+            .find(|(_, k)| kube.ctrl.selector.iter().all(|(key, v)| k.ctrl.pod_labels.get(key) == Some(v)));
+
+        if let Some((_, k)) = collision {
+            let reason = format!("{} & {}", kube.ctrl.name, k.ctrl.name);
+            return Err(CliErr::new(SELECTOR_COLLISION, &reason, ErrMessage::ParsingError));
+        }
+    }
+
+    Ok(())
+}
+
 /// Get Ingress Object
 ///
 /// # Description
@@ -80,6 +204,50 @@ pub fn get_ingress_object(dk: &[DockerService], conf: Option<ConfigIngress>) ->
     None
 }
 
+/// Get Volume Claim Objects
+///
+/// # Description
+/// Retrieve the PersistentVolumeClaim of the named volumes used by the services. Volumes which are only
+/// used by StatefulSets are skipped as they're created by the claim templates of the StatefulSets
+///
+/// # Arguments
+/// * `dk` &[DockerService]
+/// * `kubes` &[Kube]
+/// * `named` &[DockerNamedVolume] top-level volumes of the compose file
+/// * `conf` &[ConfigVolume]
+///
+/// # Return
+/// Vec<KubeVolumeClaim>
+pub fn get_volume_claim_objects(dk: &[DockerService], kubes: &[Kube], named: &[DockerNamedVolume], conf: &[ConfigVolume]) -> Vec<KubeVolumeClaim> {
+    let is_stateful = |name: &str| kubes
+        .iter()
+        .any(|k| k.ctrl.name == name && k.ctrl.ctrl == ControllerKind::StatefulSet);
+
+    let (stateful, shared): (Vec<&DockerService>, Vec<&DockerService>) = dk
+        .iter()
+        .partition(|d| is_stateful(&d.name));
+
+    let volumes: Vec<DockerVolume> = shared
+        .iter()
+        .flat_map(|d| d.volumes.clone())
+        .collect();
+
+    let templates: Vec<&str> = stateful
+        .iter()
+        .flat_map(|d| d.volumes.iter())
+        .filter(|v| v.is_named() && !v.external)
+        .map(|v| v.source.as_str())
+        .collect();
+
+    let named: Vec<DockerNamedVolume> = named
+        .iter()
+        .filter(|n| !templates.contains(&n.name.as_str()))
+        .cloned()
+        .collect();
+
+    get_volume_claims(&volumes, &named, conf)
+}
+
 /// Get Object Path
 ///
 /// # Description
@@ -3,12 +3,30 @@
 /// Module use to create a K8S controller datastructure
 use std::iter::Iterator;
 use std::path::PathBuf;
+use std::collections::BTreeMap;
 use serde::{Serialize, Deserialize};
 use crate::docker::parser::{DockerService};
-use crate::confiture::config::{ConfigDeployment};
+use crate::docker::deploy::{DeployMode, RestartCondition};
+use crate::docker::port::{DockerPort, Protocol};
+use crate::confiture::config::{ConfigDeployment, ConfigVolume};
+use super::volume::{PodVolume, VolumeMount, KubeVolumeClaim, get_pod_volumes, get_volume_claim_templates};
+use super::service::get_headless_name;
+use super::job::KubeJob;
+use super::probe::{KubeProbe, get_probes};
+use super::resources::{KubeResources, get_resources};
+use super::dependency::{KubeInitContainer};
+use super::environment::{SecretEnvVar};
+use super::secret::{get_file_volumes, get_fs_group};
+use super::security::{KubeSecurityContext, get_security_context};
+use super::rollout::{DeploymentStrategy, get_deployment_strategy, get_min_ready_seconds, get_pre_stop};
+use super::placement::{KubeAffinity, TopologySpreadConstraint, get_node_placement, get_affinity, get_topology_spread_constraints};
+use super::labels::{get_selector, get_labels};
+use super::host::{HostAlias, KubeDnsConfig, get_host_aliases, get_dns_config, get_dns_policy, get_hostname};
+use crate::core::logger::{log, LogType};
 
 /// Constant
 const CONTROLLER_FILENAME: &str = "controller.yaml";
+const DEFAULT_REPLICAS: u32 = 1;
 
 /// Controller Kind
 ///
@@ -18,7 +36,102 @@ pub enum ControllerKind {
     Deployment,
     ReplicaSet,
     StatefulSet,
-    DaemonSet
+    DaemonSet,
+    Job,
+    CronJob
+}
+
+impl Default for ControllerKind {
+    fn default() -> Self {
+        ControllerKind::Deployment
+    }
+}
+
+/// Pod Management Policy
+///
+/// List of the policies use by a StatefulSet to create & delete its pods
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, PartialEq)]
+pub enum PodManagementPolicy {
+    OrderedReady,
+    Parallel
+}
+
+/// Update Strategy Kind
+///
+/// List of the update strategies supported by a DaemonSet & a StatefulSet
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, PartialEq)]
+pub enum UpdateStrategyKind {
+    RollingUpdate,
+    OnDelete
+}
+
+/// Int Or String
+///
+/// Value which could either be a number or a percentage (e.g: maxUnavailable)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum IntOrString {
+    Int(u32),
+    Str(String)
+}
+
+/// Update Strategy
+///
+/// # Description
+/// Structure representing the strategy use to replace the pods of a DaemonSet or a StatefulSet
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UpdateStrategy {
+    pub kind: UpdateStrategyKind,
+    pub max_unavailable: Option<IntOrString>
+}
+
+/// Toleration Operator
+///
+/// List of the operators supported by a toleration
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, PartialEq)]
+pub enum TolerationOperator {
+    Equal,
+    Exists
+}
+
+impl Default for TolerationOperator {
+    fn default() -> Self {
+        TolerationOperator::Equal
+    }
+}
+
+/// Taint Effect
+///
+/// List of the taint effects which could be tolerated
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, PartialEq)]
+pub enum TaintEffect {
+    NoSchedule,
+    PreferNoSchedule,
+    NoExecute
+}
+
+/// Toleration
+///
+/// # Description
+/// Structure representing a toleration of the pods of a controller
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Toleration {
+    pub key: Option<String>,
+    #[serde(default)]
+    pub operator: TolerationOperator,
+    pub value: Option<String>,
+    pub effect: Option<TaintEffect>
+}
+
+/// Container Port
+///
+/// # Description
+/// Structure representing a port exposed by the container of a controller
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ContainerPort {
+    pub name: String,
+    pub port: u16,
+    pub protocol: Protocol
 }
 
 /// KubeContainer
@@ -30,42 +143,199 @@ pub struct KubeController {
     pub ctrl: ControllerKind,
     pub name: String,
     pub image: String,
-    pub replicas: u8,
+    pub replicas: u32,
     // Path
     pub path: PathBuf,
     // Lists
-    pub commands: Vec<String>,
-    pub labels: Vec<String>,
-    pub env: Vec<String>,
-    pub ports: Vec<u16>
+    // entrypoint of the image
+    pub command: Vec<String>,
+    // arguments of the entrypoint
+    pub args: Vec<String>,
+    pub labels: BTreeMap<String, String>,
+    pub selector: BTreeMap<String, String>,
+    // labels of the pods (labels of the service & selector)
+    pub pod_labels: BTreeMap<String, String>,
+    pub config_map_ref: Option<String>,
+    pub secret_env: Vec<SecretEnvVar>,
+    pub ports: Vec<ContainerPort>,
+    pub volumes: Vec<PodVolume>,
+    pub volume_mounts: Vec<VolumeMount>,
+    pub init_containers: Vec<KubeInitContainer>,
+    pub probes: Vec<KubeProbe>,
+    pub resources: Option<KubeResources>,
+    pub tolerations: Vec<Toleration>,
+    // placement
+    pub node_selector: BTreeMap<String, String>,
+    pub affinity: Option<KubeAffinity>,
+    pub topology_spread_constraints: Vec<TopologySpreadConstraint>,
+    pub working_dir: Option<String>,
+    pub security_context: Option<KubeSecurityContext>,
+    // group owning the volumes of the pod
+    pub fs_group: Option<i64>,
+    pub tty: bool,
+    pub stdin: bool,
+    // namespaces shared with the host
+    pub host_network: bool,
+    pub host_pid: bool,
+    pub host_ipc: bool,
+    pub host_users: bool,
+    // name resolution
+    pub hostname: Option<String>,
+    pub subdomain: Option<String>,
+    pub host_aliases: Vec<HostAlias>,
+    pub dns_policy: Option<String>,
+    pub dns_config: Option<KubeDnsConfig>,
+    // termination
+    pub termination_grace_period_seconds: Option<u32>,
+    pub pre_stop: Vec<String>,
+    // Deployment
+    pub strategy: Option<DeploymentStrategy>,
+    pub min_ready_seconds: Option<u32>,
+    // DaemonSet & StatefulSet
+    pub update_strategy: Option<UpdateStrategy>,
+    // StatefulSet
+    pub service_name: Option<String>,
+    pub pod_management_policy: Option<PodManagementPolicy>,
+    pub volume_claims: Vec<KubeVolumeClaim>,
+    // Job & CronJob
+    pub job: Option<KubeJob>
 }
 
 impl KubeController {
     /// New
     ///
     /// # Description
-    /// Create a new KubeController
+    /// Create a new KubeController. When the controller isn't set by the confiture, it's guessed from the
+    /// docker-compose service (see get_controller_kind)
     ///
     /// # Arguments
     /// * `dk` Docker service struct
     /// * `option` ConfigDeployment
+    /// * `volumes` &[ConfigVolume] use to configure the claim templates of a StatefulSet
+    /// * `kube_path` &PathBuf
     ///
     /// # Return
     /// Option<KubeController>
-    pub fn new(dk: DockerService, option: &ConfigDeployment, kube_path: &PathBuf) -> Option<KubeController> {
+    pub fn new(dk: DockerService, option: &ConfigDeployment, volumes: &[ConfigVolume], kube_path: &PathBuf) -> Option<KubeController> {
         // Controller filename
         let mut ctrl_path = PathBuf::from(&kube_path);
         ctrl_path.push(CONTROLLER_FILENAME);
 
+        let mut kind = option.controller.unwrap_or_else(|| get_controller_kind(&dk, option));
+        // a CronJob can't be created without a schedule
+        if kind == ControllerKind::CronJob && get_schedule(option).is_none() {
+            log(
+                LogType::Warning,
+                &format!("The CronJob {} doesn't have any job.schedule and is generated as a Job", dk.name),
+                None
+            );
+            kind = ControllerKind::Job;
+        }
+
+        let (mut pod_volumes, mut volume_mounts) = get_pod_volumes(&dk.volumes);
+        // secrets & configs are mounted as files
+        let (mut file_volumes, mut file_mounts) = get_file_volumes(&dk.secrets, &dk.configs, &option.file_keys);
+        pod_volumes.append(&mut file_volumes);
+        volume_mounts.append(&mut file_mounts);
+        let mut volume_claims = Vec::new();
+        let mut service_name = None;
+        let mut pod_management_policy = None;
+        let mut update_strategy = None;
+        let mut strategy = None;
+        let mut min_ready_seconds = get_min_ready_seconds(&dk.deploy);
+        let mut job = None;
+
+        match kind {
+            // A StatefulSet request a dedicated claim per replica through its templates
+            // instead of sharing a PersistentVolumeClaim
+            ControllerKind::StatefulSet => {
+                volume_claims = get_volume_claim_templates(&dk.volumes, volumes);
+                pod_volumes.retain(|v| !volume_claims.iter().any(|c| Some(&c.name) == v.claim.as_ref()));
+                service_name = Some(get_headless_name(&dk.name));
+                pod_management_policy = Some(option.pod_management_policy.unwrap_or(PodManagementPolicy::OrderedReady));
+                update_strategy = option.update_strategy.clone();
+            },
+            ControllerKind::DaemonSet => {
+                update_strategy = Some(option.update_strategy.clone().unwrap_or(UpdateStrategy {
+                    kind: UpdateStrategyKind::RollingUpdate,
+                    max_unavailable: None
+                }));
+            },
+            ControllerKind::Deployment => {
+                strategy = get_deployment_strategy(&dk.name, &dk.deploy, option.strategy.as_ref());
+            },
+            ControllerKind::Job => {
+                job = Some(KubeJob::new(dk.deploy.restart, option.job.as_ref(), None));
+                min_ready_seconds = None;
+            },
+            ControllerKind::CronJob => {
+                job = Some(KubeJob::new(dk.deploy.restart, option.job.as_ref(), get_schedule(option)));
+                min_ready_seconds = None;
+            },
+            _ => {}
+        }
+
+        let security_context = get_security_context(&dk.name, dk.user.as_deref(), &dk.security);
+        let fs_group = get_fs_group(&dk.name, &dk.secrets, &dk.configs);
+        let (hostname, subdomain) = get_hostname(&dk.host);
+        let selector = get_selector(&dk.name, &dk.project);
+        let pod_labels = get_labels(&dk.name, &dk.labels, &selector);
+        // the labels of the deploy field are only set on the controller
+        let mut labels = dk.labels.clone();
+        labels.extend(dk.deploy.labels.clone());
+        let labels = get_labels(&dk.name, &labels, &selector);
+        let placement = &dk.deploy.placement;
+        let (node_selector, node_required) = get_node_placement(&dk.name, &placement.constraints);
+        let affinity = get_affinity(&dk.name, placement, node_required, option.anti_affinity.as_ref(), &selector);
+        let topology_spread_constraints = get_topology_spread_constraints(&dk.name, placement, &option.topology_spread_constraints, &selector);
+        let host_aliases = get_host_aliases(&dk.name, &dk.host.extra_hosts);
         let ctrl = KubeController {
-            ctrl: option.controller,
+            ctrl: kind,
+            selector,
+            pod_labels,
             name: dk.name,
             image: dk.image,
-            replicas: option.replicas,
-            commands: dk.commands,
-            labels: dk.labels,
-            env: dk.environment,
+            replicas: option.replicas.or(dk.deploy.replicas).unwrap_or(DEFAULT_REPLICAS),
+            command: dk.entrypoint,
+            args: dk.command,
+            labels,
+            // resolved by the builder which move the environment to a ConfigMap & a Secret
+            config_map_ref: None,
+            secret_env: Vec::new(),
             ports: retrieve_container_port(dk.ports),
+            volumes: pod_volumes,
+            volume_mounts,
+            // resolved by the builder as it requires the other services
+            init_containers: Vec::new(),
+            probes: get_probes(dk.healthcheck.as_ref(), option.probes.as_ref()),
+            resources: get_resources(&dk.deploy.resources, option.resources.as_ref()),
+            tolerations: option.tolerations.clone(),
+            node_selector,
+            affinity,
+            topology_spread_constraints,
+            security_context,
+            fs_group,
+            working_dir: dk.working_dir,
+            tty: dk.tty,
+            stdin: dk.stdin_open,
+            host_network: dk.security.host_network,
+            host_pid: dk.security.host_pid,
+            host_ipc: dk.security.host_ipc,
+            host_users: dk.security.host_users,
+            hostname,
+            subdomain,
+            host_aliases,
+            dns_policy: get_dns_policy(&dk.host, dk.security.host_network, option.replace_cluster_dns.unwrap_or(false)),
+            dns_config: get_dns_config(&dk.host),
+            termination_grace_period_seconds: dk.deploy.stop_grace_period,
+            pre_stop: get_pre_stop(dk.deploy.stop_signal.as_deref()),
+            strategy,
+            min_ready_seconds,
+            update_strategy,
+            service_name,
+            pod_management_policy,
+            volume_claims,
+            job,
             path: ctrl_path
         };
 
@@ -73,27 +343,80 @@ impl KubeController {
     }
 }
 
+/// Get Controller Kind
+///
+/// # Description
+/// Guess the kind of controller of a docker-compose service
+/// - a service with a schedule is a CronJob
+/// - a service which is not restarted once it's done (`no` or `on-failure`) is a Job
+/// - a service deployed with the `global` mode is a DaemonSet
+/// - otherwise it's a Deployment
+///
+/// # Arguments
+/// * `dk` &DockerService
+/// * `option` &ConfigDeployment
+///
+/// # Return
+/// ControllerKind
+fn get_controller_kind(dk: &DockerService, option: &ConfigDeployment) -> ControllerKind {
+    if get_schedule(option).is_some() {
+        return ControllerKind::CronJob;
+    }
+
+    match (dk.deploy.restart, dk.deploy.mode) {
+        (RestartCondition::No, _) | (RestartCondition::OnFailure, _) => ControllerKind::Job,
+        (_, DeployMode::Global) => ControllerKind::DaemonSet,
+        _ => ControllerKind::Deployment
+    }
+}
+
+/// Get Schedule
+///
+/// # Description
+/// Retrieve the schedule of a CronJob set in the confiture. An empty schedule is ignored
+///
+/// # Arguments
+/// * `option` &ConfigDeployment
+///
+/// # Return
+/// Option<String>
+fn get_schedule(option: &ConfigDeployment) -> Option<String> {
+    option.job
+        .as_ref()
+        .and_then(|j| j.schedule.as_ref())
+        .map(|s| s.trim().to_owned())
+        .filter(|s| !s.is_empty())
+}
+
 /// Retrieve Container Port
 ///
 /// # Description
-/// Retrieve internal docker container ports (never though that splitting a vector of string would be hard)
+/// Retrieve the ports exposed by the container. Ports which target the same container port are only kept once
 ///
 /// # Arguments
-/// * `docker_ports` Vec<String> ports of a docker services
+/// * `docker_ports` Vec<DockerPort> ports of a docker services
 ///
 /// # Return
-/// Vec<u16>
-fn retrieve_container_port(docker_ports: Vec<String>) -> Vec<u16> {
-    docker_ports
-    .into_iter()
-    .map(|p| {
-        p.split(':')
-            .enumerate()
-            .filter(|(idx, _)| *idx > (0 as usize))
-            .map(|(_, value)| String::from(value))
-            .last()
-            .unwrap_or_default()
-    })
-    .map(|port_string| port_string.parse::<u16>().unwrap_or(0))
-    .collect()
+/// Vec<ContainerPort>
+fn retrieve_container_port(docker_ports: Vec<DockerPort>) -> Vec<ContainerPort> {
+    let mut ports: Vec<ContainerPort> = Vec::new();
+    for p in docker_ports {
+        if ports.iter().any(|cp| cp.port == p.target && cp.protocol == p.protocol) {
+            continue;
+        }
+
+        // the name of a port is unique within the container
+        let mut name = p.get_name();
+        if ports.iter().any(|cp| cp.name == name) {
+            name = format!("{}-{}", p.protocol.prefix(), p.target);
+        }
+
+        ports.push(ContainerPort {
+            name,
+            port: p.target,
+            protocol: p.protocol
+        });
+    }
+
+    ports
 }
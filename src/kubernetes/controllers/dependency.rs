@@ -0,0 +1,90 @@
+/// Dependency
+///
+/// Module use to create the init containers waiting for the dependencies of a docker-compose service
+use serde::{Serialize};
+use crate::docker::parser::{DockerService};
+use crate::docker::dependency::{DependencyCondition};
+use crate::docker::port::{Protocol};
+use crate::confiture::config::{ConfigDeployment};
+use super::service::SVC_SUFFIX;
+use super::helper::get_dns_name;
+use crate::core::logger::{log, LogType};
+
+/// Constant
+const INIT_IMAGE: &str = "busybox:1.36";
+const INIT_PREFIX: &str = "wait-for-";
+const SHELL: &str = "sh";
+const SHELL_ARG: &str = "-c";
+const RETRY_DELAY: u8 = 2;
+
+/// Kube Init Container
+///
+/// # Description
+/// Structure representing an init container which wait for a dependency to be reachable
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct KubeInitContainer {
+    pub name: String,
+    pub image: String,
+    pub command: Vec<String>
+}
+
+/// Get Init Containers
+///
+/// # Description
+/// Create an init container for each dependency of a docker-compose service. The init container wait
+/// until the Service generated for the dependency accept connections on its first TCP port (`nc -z`), thus
+/// the `service_healthy` condition is only approximated as the healthcheck of the dependency isn't run.
+/// Dependencies which are not generated, which don't expose any TCP port or which are expected to complete (Job)
+/// can't be awaited through a Service and are skipped with a warning. The confiture could disable the init
+/// containers with `wait_for_dependencies`
+///
+/// # Arguments
+/// * `dk` &DockerService
+/// * `services` &[DockerService] services being generated
+/// * `option` &ConfigDeployment
+///
+/// # Return
+/// Vec<KubeInitContainer>
+pub fn get_init_containers(dk: &DockerService, services: &[DockerService], option: &ConfigDeployment) -> Vec<KubeInitContainer> {
+    if !option.wait_for_dependencies.unwrap_or(true) {
+        return Vec::new();
+    }
+
+    dk.depends_on
+        .iter()
+        .filter_map(|dep| {
+            let service = services.iter().find(|s| s.name == dep.name);
+            let port = service.and_then(|s| s.ports.iter().find(|p| p.protocol == Protocol::Tcp));
+
+            let reason = match (dep.condition, service, port) {
+                (_, None, _) => "is not part of the generated services",
+                (DependencyCondition::CompletedSuccessfully, _, _) => "is expected to complete",
+                (_, _, None) => "doesn't expose any TCP port",
+                (_, _, Some(port)) => return Some((dep, port.get_published()))
+            };
+
+            log(
+                LogType::Warning,
+                &format!("The dependency {} of the service {} {} and is not awaited", dep.name, dk.name, reason),
+                None
+            );
+            None
+        })
+        .map(|(dep, port)| {
+            let host = format!("{}{}", dep.name, SVC_SUFFIX);
+            let script = format!(
+                "until nc -z {} {}; do echo waiting for {}; sleep {}; done",
+                host,
+                port,
+                host,
+                RETRY_DELAY
+            );
+
+            KubeInitContainer {
+                name: get_dns_name(&format!("{}{}", INIT_PREFIX, dep.name)),
+                image: String::from(INIT_IMAGE),
+                command: vec![String::from(SHELL), String::from(SHELL_ARG), script]
+            }
+        })
+        .collect()
+}
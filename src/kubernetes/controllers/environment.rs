@@ -0,0 +1,155 @@
+/// Environment
+///
+/// Module use to create the K8S ConfigMap & Secret holding the environment of a docker-compose service
+use std::path::PathBuf;
+use std::collections::BTreeMap;
+use serde::{Serialize};
+use crate::docker::parser::{DockerService};
+use super::labels::{get_selector, get_labels};
+
+/// Constant
+const ENV_SUFFIX: &str = "-env";
+const CONFIG_MAP_FILENAME: &str = "configmap.yaml";
+const SECRET_FILENAME: &str = "secret.yaml";
+const WILDCARD: char = '*';
+
+/// Kube Env Object
+///
+/// # Description
+/// Structure representing a ConfigMap or a Secret created from the environment of a service
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct KubeEnvObject {
+    pub path: PathBuf,
+    pub name: String,
+    pub labels: BTreeMap<String, String>,
+    pub data: BTreeMap<String, String>
+}
+
+/// Secret Env Var
+///
+/// # Description
+/// Structure representing an environment variable whose value is read from a Secret
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SecretEnvVar {
+    pub name: String,
+    pub secret: String,
+    pub key: String
+}
+
+impl KubeEnvObject {
+    /// Get Secret Env Vars
+    ///
+    /// # Description
+    /// Retrieve the environment variables referencing each key of the Secret. The variables keep
+    /// the order of the environment of the service
+    ///
+    /// # Arguments
+    /// * `environment` &[(String, String)] environment of the service
+    ///
+    /// # Return
+    /// Vec<SecretEnvVar>
+    pub fn get_secret_env_vars(&self, environment: &[(String, String)]) -> Vec<SecretEnvVar> {
+        environment
+            .iter()
+            .map(|(key, _)| key)
+            .filter(|key| self.data.contains_key(*key))
+            .map(|key| SecretEnvVar {
+                name: key.to_owned(),
+                secret: self.name.to_owned(),
+                key: key.to_owned()
+            })
+            .collect()
+    }
+}
+
+/// Get Environment
+///
+/// # Description
+/// Split the environment of a docker-compose service into a ConfigMap for the plain values and a
+/// Secret for the variables matching one of the sensitive patterns (e.g: *_PASSWORD)
+///
+/// # Arguments
+/// * `dk` &DockerService
+/// * `patterns` &[String]
+/// * `kube_path` &PathBuf
+///
+/// # Return
+/// (Option<KubeEnvObject>, Option<KubeEnvObject>) the ConfigMap & the Secret
+pub fn get_environment(dk: &DockerService, patterns: &[String], kube_path: &PathBuf) -> (Option<KubeEnvObject>, Option<KubeEnvObject>) {
+    let (secrets, values): (BTreeMap<String, String>, BTreeMap<String, String>) = dk.environment
+        .iter()
+        .cloned()
+        .partition(|(key, _)| is_sensitive(key, patterns));
+
+    let labels = get_labels(&dk.name, &dk.labels, &get_selector(&dk.name, &dk.project));
+    let create = |data: BTreeMap<String, String>, filename: &str| {
+        if data.is_empty() {
+            return None;
+        }
+
+        let mut path = PathBuf::from(kube_path);
+        path.push(filename);
+
+        Some(KubeEnvObject {
+            path,
+            name: format!("{}{}", dk.name, ENV_SUFFIX),
+            labels: labels.clone(),
+            data
+        })
+    };
+
+    (create(values, CONFIG_MAP_FILENAME), create(secrets, SECRET_FILENAME))
+}
+
+/// Is Sensitive
+///
+/// # Description
+/// Check whenever the name of an environment variable match one of the sensitive patterns.
+/// The patterns are case insensitive and support the `*` wildcard
+///
+/// # Arguments
+/// * `key` &str
+/// * `patterns` &[String]
+///
+/// # Return
+/// bool
+pub fn is_sensitive(key: &str, patterns: &[String]) -> bool {
+    let key = key.to_uppercase();
+    patterns
+        .iter()
+        .any(|p| match_pattern(&key, &p.to_uppercase()))
+}
+
+/// Match Pattern
+///
+/// # Description
+/// Check whenever a value match a pattern containing wildcards
+///
+/// # Arguments
+/// * `value` &str
+/// * `pattern` &str
+///
+/// # Return
+/// bool
+fn match_pattern(value: &str, pattern: &str) -> bool {
+    let parts: Vec<&str> = pattern.split(WILDCARD).collect();
+    if parts.len() == 1 {
+        return value == pattern;
+    }
+
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !value.starts_with(first) || value.len() < first.len() + last.len() {
+        return false;
+    }
+
+    // the middle parts have to appear in order between the prefix and the suffix
+    let mut rest = &value[first.len()..value.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false
+        }
+    }
+
+    value.ends_with(last)
+}
@@ -11,4 +11,24 @@ pub trait KubeEnumHelper<T> {
     /// # Return
     /// * `option` T
     fn from_str(value: &str) -> Option<T>;
-}
\ No newline at end of file
+}
+
+/// Get Dns Name
+///
+/// # Description
+/// Convert a docker-compose name to a valid K8S resource name (lowercase alphanumeric & '-')
+///
+/// # Arguments
+/// * `value` &str
+///
+/// # Return
+/// String
+pub fn get_dns_name(value: &str) -> String {
+    let name: String = value
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+
+    String::from(name.trim_matches('-'))
+}
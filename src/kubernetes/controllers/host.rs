@@ -0,0 +1,170 @@
+/// Host
+///
+/// Module use to create the hostname, the host aliases & the DNS configuration of a pod
+use std::net::IpAddr;
+use serde::{Serialize};
+use super::helper::get_dns_name;
+use crate::docker::host::{DockerHost, DockerExtraHost};
+use crate::core::logger::{log, LogType};
+
+/// Constant
+const DNS_POLICY_NONE: &str = "None";
+const DNS_POLICY_HOST_NETWORK: &str = "ClusterFirstWithHostNet";
+const DNS_OPTION_SEPARATOR: char = ':';
+
+/// Host Alias
+///
+/// # Description
+/// Structure representing the hostnames resolved to an IP through the /etc/hosts of the pod
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct HostAlias {
+    pub ip: String,
+    pub hostnames: Vec<String>
+}
+
+/// Kube Dns Config
+///
+/// # Description
+/// Structure representing the dnsConfig of a pod
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct KubeDnsConfig {
+    pub nameservers: Vec<String>,
+    pub searches: Vec<String>,
+    pub options: Vec<DnsOption>
+}
+
+/// Dns Option
+///
+/// # Description
+/// Structure representing an option of the resolver (e.g: ndots:2)
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DnsOption {
+    pub name: String,
+    pub value: Option<String>
+}
+
+/// Get Host Aliases
+///
+/// # Description
+/// Retrieve the host aliases of the extra hosts of a service. The hostnames are grouped by IP.
+/// An entry which doesn't target an IP (e.g: host-gateway) is skipped with a warning
+///
+/// # Arguments
+/// * `name` &str name of the service
+/// * `hosts` &[DockerExtraHost]
+///
+/// # Return
+/// Vec<HostAlias>
+pub fn get_host_aliases(name: &str, hosts: &[DockerExtraHost]) -> Vec<HostAlias> {
+    let mut aliases: Vec<HostAlias> = Vec::new();
+    for h in hosts {
+        if h.ip.parse::<IpAddr>().is_err() {
+            log(
+                LogType::Warning,
+                &format!("The extra host {} of the service {} doesn't target an IP and is skipped", h.hostname, name),
+                None
+            );
+            continue;
+        }
+
+        match aliases.iter_mut().find(|a| a.ip == h.ip) {
+            Some(alias) => {
+                if !alias.hostnames.contains(&h.hostname) {
+                    alias.hostnames.push(h.hostname.clone());
+                }
+            },
+            None => aliases.push(HostAlias {
+                ip: h.ip.clone(),
+                hostnames: vec![h.hostname.clone()]
+            })
+        }
+    }
+
+    aliases
+}
+
+/// Get Dns Config
+///
+/// # Description
+/// Retrieve the dnsConfig of a service from its dns, dns_search & dns_opt fields
+///
+/// # Arguments
+/// * `host` &DockerHost
+///
+/// # Return
+/// Option<KubeDnsConfig>
+pub fn get_dns_config(host: &DockerHost) -> Option<KubeDnsConfig> {
+    let options = host.dns_opt
+        .iter()
+        .map(|o| {
+            let mut parts = o.splitn(2, DNS_OPTION_SEPARATOR);
+            match (parts.next(), parts.next()) {
+                (Some(name), Some(value)) => DnsOption {
+                    name: name.trim().to_owned(),
+                    value: Some(value.trim().to_owned())
+                },
+                _ => DnsOption {
+                    name: o.clone(),
+                    value: None
+                }
+            }
+        })
+        .collect();
+
+    let config = KubeDnsConfig {
+        nameservers: host.dns.clone(),
+        searches: host.dns_search.clone(),
+        options
+    };
+
+    Some(config).filter(|c| *c != KubeDnsConfig::default())
+}
+
+/// Get Dns Policy
+///
+/// # Description
+/// Retrieve the dnsPolicy of a pod. The dnsConfig is merged with the cluster DNS so that the pod keeps resolving
+/// the services of the cluster, a pod using the network of the host included. The cluster DNS is only replaced
+/// by the nameservers of the service when the confiture asks for it
+///
+/// # Arguments
+/// * `host` &DockerHost
+/// * `host_network` bool
+/// * `replace` bool replace the cluster DNS
+///
+/// # Return
+/// Option<String>
+pub fn get_dns_policy(host: &DockerHost, host_network: bool, replace: bool) -> Option<String> {
+    if replace && !host.dns.is_empty() {
+        return Some(String::from(DNS_POLICY_NONE));
+    }
+
+    Some(String::from(DNS_POLICY_HOST_NETWORK)).filter(|_| host_network)
+}
+
+/// Get Hostname
+///
+/// # Description
+/// Retrieve the hostname & the subdomain of a pod. A fully qualified hostname (e.g: db.internal) is split
+/// into a hostname & a subdomain unless the domainname of the service is set. Only the first label of the
+/// domain is kept as the subdomain is resolved through the headless Service of the same name
+///
+/// # Arguments
+/// * `host` &DockerHost
+///
+/// # Return
+/// (Option<String>, Option<String>)
+pub fn get_hostname(host: &DockerHost) -> (Option<String>, Option<String>) {
+    let mut parts = host.hostname.as_ref().map(|h| h.splitn(2, '.'));
+    let hostname = parts.as_mut().and_then(|p| p.next());
+    let domain = parts.as_mut().and_then(|p| p.next());
+
+    let subdomain = host.domainname
+        .as_deref()
+        .or(domain)
+        .and_then(|d| d.split('.').next())
+        .map(get_dns_name)
+        .filter(|d| !d.is_empty());
+
+    (hostname.map(get_dns_name).filter(|h| !h.is_empty()), subdomain)
+}
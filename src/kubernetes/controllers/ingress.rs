@@ -2,7 +2,6 @@ use serde::{Serialize};
 use super::service::SVC_SUFFIX;
 use crate::confiture::config::ConfigIngress;
 use crate::docker::parser::DockerService;
-use crate::kubernetes::controllers::service::{get_ports};
 
 /// Kube Ingress
 ///
@@ -39,24 +38,19 @@ impl KubeIngress {
     /// # Return
     /// KubeIngress
     pub fn new(docker: &[DockerService], config: ConfigIngress) -> KubeIngress {
-        let specified_ingress: Vec<String> = config.services
-            .iter()
-            .map(|s| String::from(&s.name))
-            .collect();
-
         let n: Vec<IngressBackend> = docker
             .iter()
-            .filter(|ns| specified_ingress.contains(&ns.name))
-            .enumerate()
-            .map(|(i, ns)| {
+            .filter(|ns| !ns.ports.is_empty())
+            .filter_map(|ns| {
+                let svc = config.services.iter().find(|s| s.name == ns.name)?;
                 let mut name = String::from(&ns.name);
                 name.push_str(SVC_SUFFIX);
-                let mapped_ports = get_ports(&ns.ports[0]);
-                IngressBackend {
+
+                Some(IngressBackend {
                     service_name: name,
-                    service_port: mapped_ports[0],
-                    path: String::from(&config.services[i].path)
-                }
+                    service_port: ns.ports[0].get_published(),
+                    path: String::from(&svc.path)
+                })
             })
             .collect();
 
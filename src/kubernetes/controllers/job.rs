@@ -0,0 +1,88 @@
+/// Job
+///
+/// Module use to create the K8S Job & CronJob specific datastructures
+use serde::{Serialize, Deserialize};
+use crate::docker::deploy::{RestartCondition};
+use crate::confiture::config::{ConfigJob};
+
+/// Constant
+const DEFAULT_BACKOFF_LIMIT: u32 = 6;
+const DEFAULT_COMPLETIONS: u32 = 1;
+const DEFAULT_PARALLELISM: u32 = 1;
+
+/// Concurrency Policy
+///
+/// List of the policies use by a CronJob when a job is still running
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, PartialEq)]
+pub enum ConcurrencyPolicy {
+    Allow,
+    Forbid,
+    Replace
+}
+
+impl Default for ConcurrencyPolicy {
+    fn default() -> Self {
+        ConcurrencyPolicy::Allow
+    }
+}
+
+/// Job Restart Policy
+///
+/// List of the restart policies supported by the pods of a Job
+#[derive(Serialize, Clone, Debug, Copy, PartialEq)]
+pub enum JobRestartPolicy {
+    Never,
+    OnFailure
+}
+
+/// Kube Job
+///
+/// # Description
+/// Structure representing the spec of a Job or a CronJob
+#[derive(Serialize, Debug, Clone)]
+pub struct KubeJob {
+    pub restart_policy: JobRestartPolicy,
+    pub backoff_limit: u32,
+    pub completions: u32,
+    pub parallelism: u32,
+    pub schedule: Option<String>,
+    pub concurrency_policy: ConcurrencyPolicy
+}
+
+impl KubeJob {
+    /// New
+    ///
+    /// # Description
+    /// Create a new KubeJob. The restart policy of the pods is based on the compose `restart` field
+    ///
+    /// # Arguments
+    /// * `restart` RestartCondition
+    /// * `option` Option<&ConfigJob>
+    /// * `schedule` Option<String> only set for a CronJob
+    ///
+    /// # Return
+    /// KubeJob
+    pub fn new(restart: RestartCondition, option: Option<&ConfigJob>, schedule: Option<String>) -> KubeJob {
+        let restart_policy = match restart {
+            RestartCondition::No => JobRestartPolicy::Never,
+            _ => JobRestartPolicy::OnFailure
+        };
+
+        KubeJob {
+            restart_policy,
+            backoff_limit: option
+                .and_then(|o| o.backoff_limit)
+                .unwrap_or(DEFAULT_BACKOFF_LIMIT),
+            completions: option
+                .and_then(|o| o.completions)
+                .unwrap_or(DEFAULT_COMPLETIONS),
+            parallelism: option
+                .and_then(|o| o.parallelism)
+                .unwrap_or(DEFAULT_PARALLELISM),
+            schedule,
+            concurrency_policy: option
+                .and_then(|o| o.concurrency_policy)
+                .unwrap_or_default()
+        }
+    }
+}
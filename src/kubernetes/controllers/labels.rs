@@ -0,0 +1,174 @@
+/// Labels
+///
+/// Module use to create the labels selecting the pods of a service & the labels of the K8S objects
+use std::collections::BTreeMap;
+use crate::core::logger::{log, LogType};
+
+/// Constant
+pub const NAME_LABEL: &str = "app.kubernetes.io/name";
+pub const INSTANCE_LABEL: &str = "app.kubernetes.io/instance";
+pub const MANAGED_BY_LABEL: &str = "app.kubernetes.io/managed-by";
+const MANAGER: &str = "capoomobi";
+const MAX_LABEL_VALUE_LENGTH: usize = 63;
+const MAX_LABEL_PREFIX_LENGTH: usize = 253;
+
+/// Get Selector
+///
+/// # Description
+/// Retrieve the labels use to select the pods of a service. The pods are selected by the name of the service
+/// & the name of the compose project, the labels of the compose service are never used as they could be shared
+/// by several services
+///
+/// # Arguments
+/// * `name` &str name of the service
+/// * `project` &str name of the compose project
+///
+/// # Return
+/// BTreeMap<String, String>
+pub fn get_selector(name: &str, project: &str) -> BTreeMap<String, String> {
+    let instance = if project.is_empty() { name } else { project };
+
+    let mut selector = BTreeMap::new();
+    selector.insert(String::from(NAME_LABEL), get_label_value(name));
+    selector.insert(String::from(INSTANCE_LABEL), get_label_value(instance));
+
+    selector
+}
+
+/// Get Labels
+///
+/// # Description
+/// Retrieve the labels of an object of a service. The labels of the compose service are kept as metadata
+/// while the labels of the selector & the managed-by label take precedence. A compose label which would be
+/// overridden or which isn't a valid Kubernetes label (e.g: a value containing a space) is skipped with a warning
+///
+/// # Arguments
+/// * `name` &str name of the service
+/// * `labels` &BTreeMap<String, String> labels of the compose service
+/// * `selector` &BTreeMap<String, String>
+///
+/// # Return
+/// BTreeMap<String, String>
+pub fn get_labels(name: &str, labels: &BTreeMap<String, String>, selector: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+    let mut managed = selector.clone();
+    managed.insert(String::from(MANAGED_BY_LABEL), String::from(MANAGER));
+
+    for (key, value) in labels {
+        if managed.get(key).map_or(false, |v| v != value) {
+            log(
+                LogType::Warning,
+                &format!("The label {} of the service {} is reserved and is skipped", key, name),
+                None
+            );
+        }
+    }
+
+    let mut merged: BTreeMap<String, String> = labels
+        .iter()
+        .filter(|(key, value)| {
+            let valid = is_valid_key(key) && is_valid_value(value);
+            if !valid {
+                log(
+                    LogType::Warning,
+                    &format!("The label {} of the service {} isn't a valid Kubernetes label and is skipped", key, name),
+                    None
+                );
+            }
+            valid
+        })
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    merged.extend(managed);
+
+    merged
+}
+
+/// Get Project Labels
+///
+/// # Description
+/// Retrieve the labels of an object shared by the services of the compose project (e.g: top-level secrets)
+///
+/// # Arguments
+/// * `project` &str name of the compose project
+///
+/// # Return
+/// BTreeMap<String, String>
+pub fn get_project_labels(project: &str) -> BTreeMap<String, String> {
+    let mut labels = BTreeMap::new();
+    labels.insert(String::from(MANAGED_BY_LABEL), String::from(MANAGER));
+    if !project.is_empty() {
+        labels.insert(String::from(INSTANCE_LABEL), get_label_value(project));
+    }
+
+    labels
+}
+
+/// Get Label Value
+///
+/// # Description
+/// Convert a name to a valid label value (63 characters at most, alphanumeric, '-', '_' & '.',
+/// starting & ending with an alphanumeric character)
+///
+/// # Arguments
+/// * `value` &str
+///
+/// # Return
+/// String
+fn get_label_value(value: &str) -> String {
+    let label: String = value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '-' })
+        .take(MAX_LABEL_VALUE_LENGTH)
+        .collect();
+
+    String::from(label.trim_matches(|c: char| !c.is_ascii_alphanumeric()))
+}
+
+/// Is Valid Key
+///
+/// # Description
+/// Check that a key is a valid label key: an optional DNS subdomain prefix followed by a name
+/// (e.g: com.example/tier)
+///
+/// # Arguments
+/// * `key` &str
+///
+/// # Return
+/// bool
+fn is_valid_key(key: &str) -> bool {
+    let mut parts = key.rsplitn(2, '/');
+    let name = parts.next().unwrap_or("");
+    let prefix = parts.next();
+
+    let valid_prefix = prefix.map_or(true, |p| {
+        p.len() <= MAX_LABEL_PREFIX_LENGTH
+            && p.split('.').all(|part| !part.is_empty()
+                && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+                && !part.starts_with('-')
+                && !part.ends_with('-'))
+    });
+
+    valid_prefix && !name.is_empty() && is_valid_value(name)
+}
+
+/// Is Valid Value
+///
+/// # Description
+/// Check that a value is a valid label value (63 characters at most, alphanumeric, '-', '_' & '.',
+/// starting & ending with an alphanumeric character). An empty value is valid
+///
+/// # Arguments
+/// * `value` &str
+///
+/// # Return
+/// bool
+fn is_valid_value(value: &str) -> bool {
+    if value.is_empty() {
+        return true;
+    }
+
+    value.len() <= MAX_LABEL_VALUE_LENGTH
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && value.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && value.ends_with(|c: char| c.is_ascii_alphanumeric())
+}
@@ -1,4 +1,17 @@
 pub mod controller;
 pub mod service;
 pub mod ingress;
+pub mod volume;
+pub mod mount;
+pub mod job;
+pub mod probe;
+pub mod resources;
+pub mod dependency;
+pub mod environment;
+pub mod secret;
+pub mod security;
+pub mod host;
+pub mod rollout;
+pub mod placement;
+pub mod labels;
 mod helper;
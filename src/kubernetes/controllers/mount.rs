@@ -0,0 +1,182 @@
+/// Mount
+///
+/// Module use to translate the bind mounts, the tmpfs & the anonymous volumes of a service into pod volumes
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+use super::helper::get_dns_name;
+use super::resources::to_memory_quantity;
+use super::secret::KubeFileObject;
+use super::volume::{PodVolume, VolumeMount, EmptyDirVolume};
+use crate::docker::volume::{DockerVolume, VolumeKind};
+use crate::docker::secret::FileKind;
+use crate::core::logger::{log, LogType};
+
+/// Constant
+const MEMORY_MEDIUM: &str = "Memory";
+
+/// Mount Strategy
+///
+/// List of the strategies use to translate a mount of a service
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, PartialEq)]
+pub enum MountStrategy {
+    ConfigMap,
+    HostPath,
+    EmptyDir,
+    Skip
+}
+
+/// Config Mount
+///
+/// # Description
+/// Structure representing the strategy of a mount set in the confiture. The mount is found by its target
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ConfigMount {
+    pub target: String,
+    pub strategy: MountStrategy,
+    pub size_limit: Option<String>
+}
+
+/// Get Mount Volumes
+///
+/// # Description
+/// Retrieve the pod volumes & the container mounts of the bind mounts, the tmpfs & the anonymous volumes
+/// of a service. A hostPath is only used when it's requested and every mount which can't be translated
+/// is skipped with a warning. When the confiture doesn't set a strategy for a mount
+/// - a bind mount of a file or a folder of the project is stored in a ConfigMap
+/// - a tmpfs is a memory-backed emptyDir
+/// - an anonymous volume is an emptyDir
+///
+/// # Arguments
+/// * `name` &str name of the service
+/// * `volumes` &[DockerVolume]
+/// * `options` &[ConfigMount]
+/// * `labels` &BTreeMap<String, String> labels of the ConfigMaps
+///
+/// # Return
+/// (Vec<PodVolume>, Vec<VolumeMount>, Vec<KubeFileObject>)
+pub fn get_mount_volumes(
+    name: &str,
+    volumes: &[DockerVolume],
+    options: &[ConfigMount],
+    labels: &BTreeMap<String, String>
+) -> (Vec<PodVolume>, Vec<VolumeMount>, Vec<KubeFileObject>) {
+    let mut pod_volumes: Vec<PodVolume> = Vec::new();
+    let mut mounts = Vec::new();
+    let mut config_maps = Vec::new();
+
+    for v in volumes.iter().filter(|v| !v.is_named()) {
+        let option = options.iter().find(|o| o.target == v.target);
+        let strategy = match option.map(|o| o.strategy) {
+            Some(s) => s,
+            None => get_default_strategy(v)
+        };
+
+        // a mount could target the same folder name in different paths
+        let base = format!("{}-{}", get_dns_name(name), get_dns_name(&v.target));
+        let mut volume_name = base.clone();
+        let mut idx = 1;
+        while pod_volumes.iter().any(|p| p.name == volume_name) {
+            volume_name = format!("{}-{}", base, idx);
+            idx += 1;
+        }
+
+        let mut pod_volume = PodVolume {
+            name: volume_name.clone(),
+            ..Default::default()
+        };
+        let mut sub_path = None;
+
+        match (strategy, v.kind) {
+            (MountStrategy::Skip, _) => continue,
+            (MountStrategy::ConfigMap, VolumeKind::Bind) if v.content.is_some() => {
+                let object = get_config_map(&volume_name, v, labels);
+                if v.content.as_ref().map_or(false, |c| c.is_file) {
+                    sub_path = object.data.keys().chain(object.binary_data.keys()).next().cloned();
+                }
+
+                pod_volume.config_map = Some(object.name.clone());
+                config_maps.push(object);
+            },
+            (MountStrategy::HostPath, VolumeKind::Bind) => pod_volume.host_path = Some(v.source.clone()),
+            (MountStrategy::EmptyDir, _) => {
+                let size = option
+                    .and_then(|o| o.size_limit.clone())
+                    .or_else(|| v.size.as_ref().and_then(|s| to_memory_quantity(s)));
+
+                pod_volume.empty_dir = Some(EmptyDirVolume {
+                    medium: Some(String::from(MEMORY_MEDIUM)).filter(|_| v.kind == VolumeKind::Tmpfs),
+                    size_limit: size
+                });
+            },
+            _ => {
+                log(
+                    LogType::Warning,
+                    &format!("The mount {} of the service {} can't be translated and is skipped", v.target, name),
+                    None
+                );
+                continue;
+            }
+        }
+
+        pod_volumes.push(pod_volume);
+        mounts.push(VolumeMount {
+            name: volume_name,
+            path: v.target.clone(),
+            read_only: v.read_only,
+            sub_path
+        });
+    }
+
+    (pod_volumes, mounts, config_maps)
+}
+
+/// Get Default Strategy
+///
+/// # Description
+/// Retrieve the strategy of a mount which isn't set in the confiture
+///
+/// # Arguments
+/// * `volume` &DockerVolume
+///
+/// # Return
+/// MountStrategy
+fn get_default_strategy(volume: &DockerVolume) -> MountStrategy {
+    match volume.kind {
+        VolumeKind::Bind => MountStrategy::ConfigMap,
+        _ => MountStrategy::EmptyDir
+    }
+}
+
+/// Get Config Map
+///
+/// # Description
+/// Create the ConfigMap holding the files of a bind mount. Plain text is stored in `data` and the other
+/// files are base64 encoded in `binary_data`
+///
+/// # Arguments
+/// * `name` &str name of the ConfigMap
+/// * `volume` &DockerVolume
+/// * `labels` &BTreeMap<String, String>
+///
+/// # Return
+/// KubeFileObject
+fn get_config_map(name: &str, volume: &DockerVolume, labels: &BTreeMap<String, String>) -> KubeFileObject {
+    let mut data = BTreeMap::new();
+    let mut binary_data = BTreeMap::new();
+    let files = volume.content.iter().flat_map(|c| c.files.iter());
+
+    for f in files {
+        match std::str::from_utf8(&f.data) {
+            Ok(t) => data.insert(f.name.clone(), String::from(t)),
+            Err(_) => binary_data.insert(f.name.clone(), base64::encode(&f.data))
+        };
+    }
+
+    KubeFileObject {
+        name: String::from(name),
+        kind: FileKind::Config,
+        labels: labels.clone(),
+        data,
+        binary_data
+    }
+}
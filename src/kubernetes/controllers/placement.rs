@@ -0,0 +1,362 @@
+/// Placement
+///
+/// Module use to create the node selector, the affinity & the topology spread constraints of a pod
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+use crate::docker::deploy::{DockerPlacement, DockerConstraint, ConstraintOperator};
+use crate::core::logger::{log, LogType};
+
+/// Constant
+const NODE_LABEL_PREFIX: &str = "node.labels.";
+const NODE_ROLE: &str = "node.role";
+const MANAGER_ROLE: &str = "manager";
+const WORKER_ROLE: &str = "worker";
+const CONTROL_PLANE_LABEL: &str = "node-role.kubernetes.io/control-plane";
+const HOSTNAME_LABEL: &str = "kubernetes.io/hostname";
+const ARCHITECTURES: [(&str, &str); 3] = [("x86_64", "amd64"), ("aarch64", "arm64"), ("armv7l", "arm")];
+
+/// Node Operator
+///
+/// List of the operators supported by a node selector requirement
+#[derive(Serialize, Clone, Debug, Copy, PartialEq)]
+pub enum NodeOperator {
+    In,
+    NotIn,
+    Exists,
+    DoesNotExist
+}
+
+/// Node Selector Requirement
+///
+/// # Description
+/// Structure representing an expression which should be matched by the labels of a node
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct NodeSelectorRequirement {
+    pub key: String,
+    pub operator: NodeOperator,
+    pub values: Vec<String>
+}
+
+/// Affinity Kind
+///
+/// List of the ways a pod affinity is enforced by the scheduler
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, PartialEq)]
+pub enum AffinityKind {
+    Required,
+    Preferred
+}
+
+/// Pod Anti Affinity
+///
+/// # Description
+/// Structure representing the anti affinity between the pods of a controller
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PodAntiAffinity {
+    pub kind: AffinityKind,
+    #[serde(default = "get_hostname_label")]
+    pub topology_key: String,
+    // labels of the pods of the controller
+    #[serde(skip_deserializing)]
+    pub selector: BTreeMap<String, String>
+}
+
+/// Unsatisfiable Action
+///
+/// List of the actions of the scheduler when a topology spread constraint can't be satisfied
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, PartialEq)]
+pub enum UnsatisfiableAction {
+    DoNotSchedule,
+    ScheduleAnyway
+}
+
+impl Default for UnsatisfiableAction {
+    fn default() -> Self {
+        UnsatisfiableAction::ScheduleAnyway
+    }
+}
+
+/// Topology Spread Constraint
+///
+/// # Description
+/// Structure representing how the pods of a controller are spread over a topology (e.g: zones, nodes)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TopologySpreadConstraint {
+    #[serde(default = "get_default_max_skew")]
+    pub max_skew: u32,
+    pub topology_key: String,
+    #[serde(default)]
+    pub when_unsatisfiable: UnsatisfiableAction,
+    // labels of the pods of the controller
+    #[serde(skip_deserializing)]
+    pub selector: BTreeMap<String, String>
+}
+
+/// Kube Affinity
+///
+/// # Description
+/// Structure representing the affinity of a pod
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct KubeAffinity {
+    pub node_required: Vec<NodeSelectorRequirement>,
+    pub pod_anti_affinity: Option<PodAntiAffinity>
+}
+
+/// Get Node Placement
+///
+/// # Description
+/// Retrieve the node selector & the required node affinity of the placement constraints of a service.
+/// An equality constraint is added to the node selector while the other constraints are node affinity
+/// expressions. A constraint on a field which doesn't have any node label equivalent (e.g: node.id)
+/// is skipped with a warning
+///
+/// # Arguments
+/// * `name` &str name of the service
+/// * `constraints` &[DockerConstraint]
+///
+/// # Return
+/// (BTreeMap<String, String>, Vec<NodeSelectorRequirement>)
+pub fn get_node_placement(name: &str, constraints: &[DockerConstraint]) -> (BTreeMap<String, String>, Vec<NodeSelectorRequirement>) {
+    let mut selector = BTreeMap::new();
+    let mut required: Vec<NodeSelectorRequirement> = Vec::new();
+
+    for c in constraints {
+        if c.field == NODE_ROLE {
+            match get_role_requirement(c) {
+                Some(r) => required.push(r),
+                None => warn_constraint(name, c)
+            }
+            continue;
+        }
+
+        let key = match get_node_label(&c.field) {
+            Some(k) => k,
+            None => {
+                warn_constraint(name, c);
+                continue;
+            }
+        };
+
+        let value = ARCHITECTURES
+            .iter()
+            .find(|(docker, _)| c.field == "node.platform.arch" && *docker == c.value)
+            .map(|(_, kube)| String::from(*kube))
+            .unwrap_or_else(|| c.value.clone());
+
+        // a second equality on the same label can't be expressed by the node selector
+        let operator = match c.operator {
+            ConstraintOperator::Equal if !selector.contains_key(&key) => {
+                selector.insert(key, value);
+                continue;
+            },
+            ConstraintOperator::Equal => NodeOperator::In,
+            ConstraintOperator::NotEqual => NodeOperator::NotIn
+        };
+
+        match required.iter_mut().find(|r| r.key == key && r.operator == operator && operator == NodeOperator::NotIn) {
+            Some(r) => r.values.push(value),
+            None => required.push(NodeSelectorRequirement {
+                key,
+                operator,
+                values: vec![value]
+            })
+        }
+    }
+
+    (selector, required)
+}
+
+/// Get Affinity
+///
+/// # Description
+/// Retrieve the affinity of a pod. The anti affinity of the confiture takes precedence, otherwise
+/// a service limited to one replica per node (`max_replicas_per_node: 1`) requires its pods to be
+/// scheduled on different nodes
+///
+/// # Arguments
+/// * `name` &str name of the service
+/// * `placement` &DockerPlacement
+/// * `node_required` Vec<NodeSelectorRequirement>
+/// * `option` Option<&PodAntiAffinity>
+/// * `selector` &BTreeMap<String, String> labels of the pods
+///
+/// # Return
+/// Option<KubeAffinity>
+pub fn get_affinity(
+    name: &str,
+    placement: &DockerPlacement,
+    node_required: Vec<NodeSelectorRequirement>,
+    option: Option<&PodAntiAffinity>,
+    selector: &BTreeMap<String, String>
+) -> Option<KubeAffinity> {
+    let pod_anti_affinity = match (option, placement.max_replicas_per_node) {
+        (Some(o), _) => Some(o.clone()),
+        (None, Some(1)) => Some(PodAntiAffinity {
+            kind: AffinityKind::Required,
+            topology_key: get_hostname_label(),
+            selector: BTreeMap::new()
+        }),
+        (None, Some(max)) => {
+            log(
+                LogType::Warning,
+                &format!("The max_replicas_per_node {} of the service {} isn't supported and is skipped", max, name),
+                None
+            );
+            None
+        },
+        (None, None) => None
+    };
+
+    let affinity = KubeAffinity {
+        node_required,
+        pod_anti_affinity: pod_anti_affinity.map(|a| PodAntiAffinity {
+            selector: selector.clone(),
+            ..a
+        })
+    };
+
+    Some(affinity).filter(|a| *a != KubeAffinity::default())
+}
+
+/// Get Topology Spread Constraints
+///
+/// # Description
+/// Retrieve the topology spread constraints of a pod. Each placement preference of the service spreads
+/// the pods over a node label as evenly as possible. The constraints of the confiture replace the ones
+/// using the same topology key
+///
+/// # Arguments
+/// * `name` &str name of the service
+/// * `placement` &DockerPlacement
+/// * `options` &[TopologySpreadConstraint]
+/// * `selector` &BTreeMap<String, String> labels of the pods
+///
+/// # Return
+/// Vec<TopologySpreadConstraint>
+pub fn get_topology_spread_constraints(
+    name: &str,
+    placement: &DockerPlacement,
+    options: &[TopologySpreadConstraint],
+    selector: &BTreeMap<String, String>
+) -> Vec<TopologySpreadConstraint> {
+    let preferences = placement.preferences
+        .iter()
+        .filter_map(|p| {
+            let key = get_node_label(p);
+            if key.is_none() {
+                log(
+                    LogType::Warning,
+                    &format!("The placement preference {} of the service {} isn't supported and is skipped", p, name),
+                    None
+                );
+            }
+
+            key
+        })
+        .filter(|key| !options.iter().any(|o| o.topology_key == *key))
+        .map(|topology_key| TopologySpreadConstraint {
+            max_skew: get_default_max_skew(),
+            topology_key,
+            when_unsatisfiable: UnsatisfiableAction::ScheduleAnyway,
+            selector: BTreeMap::new()
+        });
+
+    preferences
+        .chain(options.iter().cloned())
+        .map(|c| TopologySpreadConstraint {
+            selector: selector.clone(),
+            ..c
+        })
+        .collect()
+}
+
+/// Get Node Label
+///
+/// # Description
+/// Retrieve the node label matching a field of a swarm node
+///
+/// # Arguments
+/// * `field` &str (e.g: node.labels.zone, node.hostname)
+///
+/// # Return
+/// Option<String>
+fn get_node_label(field: &str) -> Option<String> {
+    if field.starts_with(NODE_LABEL_PREFIX) {
+        return Some(String::from(&field[NODE_LABEL_PREFIX.len()..]));
+    }
+
+    match field {
+        "node.hostname" => Some(get_hostname_label()),
+        "node.platform.os" => Some(String::from("kubernetes.io/os")),
+        "node.platform.arch" => Some(String::from("kubernetes.io/arch")),
+        _ => None
+    }
+}
+
+/// Get Role Requirement
+///
+/// # Description
+/// Retrieve the requirement of a constraint on the role of the node. A swarm manager is
+/// a node of the control plane
+///
+/// # Arguments
+/// * `constraint` &DockerConstraint
+///
+/// # Return
+/// Option<NodeSelectorRequirement>
+fn get_role_requirement(constraint: &DockerConstraint) -> Option<NodeSelectorRequirement> {
+    let is_manager = match constraint.value.as_str() {
+        MANAGER_ROLE => true,
+        WORKER_ROLE => false,
+        _ => return None
+    };
+
+    let operator = if is_manager == (constraint.operator == ConstraintOperator::Equal) {
+        NodeOperator::Exists
+    } else {
+        NodeOperator::DoesNotExist
+    };
+
+    Some(NodeSelectorRequirement {
+        key: String::from(CONTROL_PLANE_LABEL),
+        operator,
+        values: Vec::new()
+    })
+}
+
+/// Warn Constraint
+///
+/// # Description
+/// Warn that a placement constraint is skipped
+///
+/// # Arguments
+/// * `name` &str name of the service
+/// * `constraint` &DockerConstraint
+fn warn_constraint(name: &str, constraint: &DockerConstraint) {
+    log(
+        LogType::Warning,
+        &format!("The placement constraint {} of the service {} isn't supported and is skipped", constraint.field, name),
+        None
+    );
+}
+
+/// Get Hostname Label
+///
+/// # Description
+/// Retrieve the label holding the hostname of a node
+///
+/// # Return
+/// String
+fn get_hostname_label() -> String {
+    String::from(HOSTNAME_LABEL)
+}
+
+/// Get Default Max Skew
+///
+/// # Description
+/// Retrieve the default max skew of a topology spread constraint
+///
+/// # Return
+/// u32
+fn get_default_max_skew() -> u32 {
+    1
+}
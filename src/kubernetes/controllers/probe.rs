@@ -0,0 +1,268 @@
+/// Probe
+///
+/// Module use to create the K8S probes of a container from the healthcheck of a docker-compose service
+use serde::{Serialize, Deserialize};
+use crate::docker::healthcheck::{DockerHealthcheck, HealthcheckTest};
+use crate::confiture::config::{ConfigProbes};
+
+/// Constant
+const SHELL: &str = "sh";
+const SHELL_ARG: &str = "-c";
+const DEFAULT_PERIOD: u32 = 10;
+const DEFAULT_RETRIES: u32 = 3;
+const LOCAL_HOSTS: [&str; 4] = ["localhost", "127.0.0.1", "0.0.0.0", "[::1]"];
+const SHELL_OPERATORS: [&str; 4] = ["||", "&&", ";", "|"];
+const OR_OPERATOR: &str = "||";
+const EXIT_FAILURE: &str = "exit 1";
+const HTTP_SCHEMES: [(&str, &str, u16); 2] = [("http://", "HTTP", 80), ("https://", "HTTPS", 443)];
+
+/// Probe Kind
+///
+/// List of the probes supported by a K8S container
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, PartialEq)]
+pub enum ProbeKind {
+    Liveness,
+    Readiness,
+    Startup
+}
+
+impl ProbeKind {
+    /// Get Field Name
+    ///
+    /// # Description
+    /// Retrieve the name of the field of the probe in the container spec
+    ///
+    /// # Return
+    /// &str
+    fn get_field_name(self) -> &'static str {
+        match self {
+            ProbeKind::Liveness => "livenessProbe",
+            ProbeKind::Readiness => "readinessProbe",
+            ProbeKind::Startup => "startupProbe"
+        }
+    }
+}
+
+/// Http Get Action
+///
+/// Structure representing an HTTP request made by a probe
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct HttpGetAction {
+    pub path: String,
+    pub port: u16,
+    pub scheme: String
+}
+
+/// Probe Handler
+///
+/// List of the actions which could be done by a probe
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProbeHandler {
+    Exec(Vec<String>),
+    HttpGet(HttpGetAction),
+    TcpSocket(u16)
+}
+
+/// Kube Probe
+///
+/// Structure use to store the value of a K8S probe
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct KubeProbe {
+    pub name: String,
+    pub exec: Option<Vec<String>>,
+    pub http_get: Option<HttpGetAction>,
+    pub tcp_socket: Option<u16>,
+    pub initial_delay_seconds: Option<u32>,
+    pub period_seconds: Option<u32>,
+    pub timeout_seconds: Option<u32>,
+    pub failure_threshold: Option<u32>,
+    pub success_threshold: Option<u32>
+}
+
+/// Get Probes
+///
+/// # Description
+/// Retrieve the probes of a container from the healthcheck of a docker-compose service.
+/// By default a liveness & a readiness probe are generated, a startup probe is added when the healthcheck
+/// has a `start_period`. The confiture could choose the kind of probes and override the thresholds
+///
+/// # Arguments
+/// * `healthcheck` Option<&DockerHealthcheck>
+/// * `option` Option<&ConfigProbes>
+///
+/// # Return
+/// Vec<KubeProbe>
+pub fn get_probes(healthcheck: Option<&DockerHealthcheck>, option: Option<&ConfigProbes>) -> Vec<KubeProbe> {
+    let hc = match healthcheck {
+        Some(h) => h,
+        None => return Vec::new()
+    };
+
+    let kinds = match option.and_then(|o| o.kinds.clone()) {
+        Some(k) => k,
+        None if hc.start_period.is_some() => vec![ProbeKind::Liveness, ProbeKind::Readiness, ProbeKind::Startup],
+        None => vec![ProbeKind::Liveness, ProbeKind::Readiness]
+    };
+
+    let handler = get_probe_handler(&hc.test);
+    let has_startup = kinds.contains(&ProbeKind::Startup);
+    let period = option.and_then(|o| o.period_seconds).or(hc.interval);
+    let failure = option.and_then(|o| o.failure_threshold).or(hc.retries);
+
+    kinds
+        .into_iter()
+        .map(|kind| {
+            let mut probe = KubeProbe {
+                name: String::from(kind.get_field_name()),
+                exec: None,
+                http_get: None,
+                tcp_socket: None,
+                initial_delay_seconds: option.and_then(|o| o.initial_delay_seconds),
+                period_seconds: period,
+                timeout_seconds: option.and_then(|o| o.timeout_seconds).or(hc.timeout),
+                failure_threshold: failure,
+                success_threshold: None
+            };
+
+            match &handler {
+                ProbeHandler::Exec(cmd) => probe.exec = Some(cmd.clone()),
+                ProbeHandler::HttpGet(action) => probe.http_get = Some(action.clone()),
+                ProbeHandler::TcpSocket(port) => probe.tcp_socket = Some(*port)
+            };
+
+            match kind {
+                // the startup probe give the container `start_period` seconds to start
+                ProbeKind::Startup => {
+                    probe.initial_delay_seconds = None;
+                    if let (None, Some(start)) = (option.and_then(|o| o.failure_threshold), hc.start_period) {
+                        let period = period.filter(|p| *p > 0).unwrap_or(DEFAULT_PERIOD);
+                        let attempts = (start + period - 1) / period;
+                        probe.failure_threshold = Some(attempts.max(hc.retries.unwrap_or(DEFAULT_RETRIES)));
+                    }
+                },
+                ProbeKind::Readiness => probe.success_threshold = option.and_then(|o| o.success_threshold),
+                ProbeKind::Liveness => {}
+            };
+
+            // without a startup probe the other probes wait for the start period
+            if kind != ProbeKind::Startup && !has_startup && probe.initial_delay_seconds.is_none() {
+                probe.initial_delay_seconds = hc.start_period;
+            }
+
+            probe
+        })
+        .collect()
+}
+
+/// Get Probe Handler
+///
+/// # Description
+/// Translate the test of a healthcheck into the action of a probe. A curl / wget of a local url is
+/// translated into an httpGet action, a `nc` on a local port into a tcpSocket action. A shell command is
+/// only translated when it's a single command, optionally followed by `|| exit 1`, as the commands chained
+/// to it (e.g: `| grep -q UP`) check more than the response. Any other command is executed within the container
+///
+/// # Arguments
+/// * `test` &HealthcheckTest
+///
+/// # Return
+/// ProbeHandler
+pub fn get_probe_handler(test: &HealthcheckTest) -> ProbeHandler {
+    let (tokens, exec): (Vec<String>, Vec<String>) = match test {
+        HealthcheckTest::Cmd(args) => (args.clone(), args.clone()),
+        HealthcheckTest::Shell(cmd) => {
+            let mut parts = cmd.trim().splitn(2, OR_OPERATOR);
+            let first = parts.next().unwrap_or("");
+            let command = match parts.next().map(str::trim) {
+                Some(EXIT_FAILURE) | None => first,
+                Some(_) => cmd.as_str()
+            };
+
+            // chained commands are kept in an exec probe
+            let tokens = match SHELL_OPERATORS.iter().any(|op| command.contains(op)) {
+                true => Vec::new(),
+                false => command
+                    .split_whitespace()
+                    .map(|t| t.trim_matches(|c| c == '"' || c == '\'').to_owned())
+                    .collect()
+            };
+
+            (tokens, vec![String::from(SHELL), String::from(SHELL_ARG), cmd.to_owned()])
+        }
+    };
+
+    let program = tokens
+        .first()
+        .and_then(|p| p.rsplit('/').next())
+        .unwrap_or("");
+
+    let handler = match program {
+        "curl" | "wget" => tokens
+            .iter()
+            .find_map(|t| parse_local_url(t))
+            .map(ProbeHandler::HttpGet),
+        "nc" => parse_local_socket(&tokens[1..]).map(ProbeHandler::TcpSocket),
+        _ => None
+    };
+
+    handler.unwrap_or(ProbeHandler::Exec(exec))
+}
+
+/// Parse Local Url
+///
+/// # Description
+/// Parse an url which target the container itself (e.g: http://localhost:8080/health)
+///
+/// # Arguments
+/// * `value` &str
+///
+/// # Return
+/// Option<HttpGetAction>
+fn parse_local_url(value: &str) -> Option<HttpGetAction> {
+    let (prefix, scheme, default_port) = HTTP_SCHEMES
+        .iter()
+        .find(|(prefix, _, _)| value.starts_with(prefix))?;
+    let rest = &value[prefix.len()..];
+
+    let (authority, path) = match rest.find('/') {
+        Some(idx) => (&rest[..idx], &rest[idx..]),
+        None => (rest, "/")
+    };
+
+    let (host, port) = match authority.rfind(':') {
+        Some(idx) if !authority.ends_with(']') => (&authority[..idx], authority[idx + 1..].parse::<u16>().ok()?),
+        _ => (authority, *default_port)
+    };
+
+    if !LOCAL_HOSTS.contains(&host) {
+        return None;
+    }
+
+    Some(HttpGetAction {
+        path: String::from(path),
+        port,
+        scheme: String::from(*scheme)
+    })
+}
+
+/// Parse Local Socket
+///
+/// # Description
+/// Parse the arguments of a `nc` command which target a port of the container (e.g: nc -z localhost 5432)
+///
+/// # Arguments
+/// * `args` &[String]
+///
+/// # Return
+/// Option<u16>
+fn parse_local_socket(args: &[String]) -> Option<u16> {
+    let values: Vec<&String> = args
+        .iter()
+        .filter(|a| !a.starts_with('-'))
+        .collect();
+
+    match values.as_slice() {
+        [host, port] if LOCAL_HOSTS.contains(&host.as_str()) => port.parse::<u16>().ok(),
+        _ => None
+    }
+}
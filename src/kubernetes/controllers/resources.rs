@@ -0,0 +1,145 @@
+/// Resources
+///
+/// Module use to create the K8S resources (requests & limits) of a container
+use serde::{Serialize, Deserialize};
+use crate::docker::deploy::{DockerResource, DockerResources};
+
+/// Resource Quantities
+///
+/// # Description
+/// Structure representing the cpu & the memory of a resource with the K8S quantity notation (e.g: 500m, 512Mi)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ResourceQuantities {
+    pub cpu: Option<String>,
+    pub memory: Option<String>
+}
+
+/// Kube Resources
+///
+/// # Description
+/// Structure use to store the value of the K8S resources of a container
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct KubeResources {
+    pub limits: Option<ResourceQuantities>,
+    pub requests: Option<ResourceQuantities>
+}
+
+/// Get Resources
+///
+/// # Description
+/// Retrieve the resources of a container from the limits & the reservations of a docker-compose service.
+/// The values of the confiture override the one of the docker-compose service
+///
+/// # Arguments
+/// * `resources` &DockerResources
+/// * `option` Option<&KubeResources>
+///
+/// # Return
+/// Option<KubeResources>
+pub fn get_resources(resources: &DockerResources, option: Option<&KubeResources>) -> Option<KubeResources> {
+    let limits = merge_quantities(
+        &resources.limits,
+        option.and_then(|o| o.limits.as_ref())
+    );
+
+    let requests = merge_quantities(
+        &resources.reservations,
+        option.and_then(|o| o.requests.as_ref())
+    );
+
+    if limits.is_none() && requests.is_none() {
+        return None;
+    }
+
+    Some(KubeResources {
+        limits,
+        requests
+    })
+}
+
+/// Merge Quantities
+///
+/// # Description
+/// Convert a docker-compose resource into K8S quantities and apply the overrides of the confiture
+///
+/// # Arguments
+/// * `resource` &DockerResource
+/// * `option` Option<&ResourceQuantities>
+///
+/// # Return
+/// Option<ResourceQuantities>
+fn merge_quantities(resource: &DockerResource, option: Option<&ResourceQuantities>) -> Option<ResourceQuantities> {
+    let cpu = option
+        .and_then(|o| o.cpu.clone())
+        .or_else(|| resource.cpus.as_ref().and_then(|c| to_cpu_quantity(c)));
+
+    let memory = option
+        .and_then(|o| o.memory.clone())
+        .or_else(|| resource.memory.as_ref().and_then(|m| to_memory_quantity(m)));
+
+    if cpu.is_none() && memory.is_none() {
+        return None;
+    }
+
+    Some(ResourceQuantities {
+        cpu,
+        memory
+    })
+}
+
+/// To Cpu Quantity
+///
+/// # Description
+/// Convert a docker-compose number of cpus into a K8S cpu quantity (e.g: 0.5 -> 500m, 2 -> 2)
+///
+/// # Arguments
+/// * `value` &str
+///
+/// # Return
+/// Option<String>
+pub fn to_cpu_quantity(value: &str) -> Option<String> {
+    let cpus = value.trim().parse::<f64>().ok()?;
+    let millis = (cpus * 1000.0).round() as u64;
+    if millis == 0 {
+        return None;
+    }
+
+    if millis % 1000 == 0 {
+        return Some((millis / 1000).to_string());
+    }
+
+    Some(format!("{}m", millis))
+}
+
+/// To Memory Quantity
+///
+/// # Description
+/// Convert a docker-compose byte value into a K8S memory quantity (e.g: 512M -> 512Mi, 1gb -> 1Gi)
+///
+/// # Arguments
+/// * `value` &str
+///
+/// # Return
+/// Option<String>
+pub fn to_memory_quantity(value: &str) -> Option<String> {
+    let lower = value.trim().to_lowercase();
+    // the byte suffix is optional (e.g: 512mb, 100b)
+    let raw = match lower.ends_with('b') {
+        true => &lower[..lower.len() - 1],
+        false => &lower
+    };
+    let (number, unit) = match raw.chars().last() {
+        Some('k') => (&raw[..raw.len() - 1], "Ki"),
+        Some('m') => (&raw[..raw.len() - 1], "Mi"),
+        Some('g') => (&raw[..raw.len() - 1], "Gi"),
+        Some('t') => (&raw[..raw.len() - 1], "Ti"),
+        _ => (raw, "")
+    };
+
+    let amount = number.parse::<f64>().ok()?;
+    if amount <= 0.0 {
+        return None;
+    }
+
+    Some(format!("{}{}", number, unit))
+}
@@ -0,0 +1,149 @@
+/// Rollout
+///
+/// Module use to create the strategy & the termination settings of a controller from the compose deploy
+use serde::{Serialize, Deserialize};
+use super::controller::IntOrString;
+use crate::docker::deploy::{DockerDeploy, DockerUpdateConfig, UpdateOrder};
+use crate::core::logger::{log, LogType};
+
+/// Constant
+const DEFAULT_PARALLELISM: u32 = 1;
+const ALL_PODS: &str = "100%";
+const PAUSE_ACTION: &str = "pause";
+const DEFAULT_STOP_SIGNAL: &str = "TERM";
+const SIGNAL_PREFIX: &str = "SIG";
+
+/// Deployment Strategy Kind
+///
+/// List of the strategies use by a Deployment to replace its pods
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, PartialEq)]
+pub enum DeploymentStrategyKind {
+    RollingUpdate,
+    Recreate
+}
+
+/// Deployment Strategy
+///
+/// # Description
+/// Structure representing the strategy of a Deployment
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DeploymentStrategy {
+    pub kind: DeploymentStrategyKind,
+    pub max_surge: Option<IntOrString>,
+    pub max_unavailable: Option<IntOrString>
+}
+
+/// Get Deployment Strategy
+///
+/// # Description
+/// Retrieve the strategy of a Deployment. The strategy of the confiture takes precedence, otherwise
+/// the `update_config` of the service is translated into a rolling update. A Deployment is rolled back
+/// with its own strategy thus the `rollback_config` is only used when the service doesn't have any `update_config`
+/// - `start-first` starts `parallelism` new pods before stopping the old ones (maxSurge)
+/// - `stop-first` stops `parallelism` old pods before starting the new ones (maxUnavailable)
+///
+/// # Arguments
+/// * `name` &str name of the service
+/// * `deploy` &DockerDeploy
+/// * `option` Option<&DeploymentStrategy>
+///
+/// # Return
+/// Option<DeploymentStrategy>
+pub fn get_deployment_strategy(name: &str, deploy: &DockerDeploy, option: Option<&DeploymentStrategy>) -> Option<DeploymentStrategy> {
+    if let Some(strategy) = option {
+        return Some(strategy.clone());
+    }
+
+    if deploy.update_config.is_some() && deploy.rollback_config.is_some() && deploy.update_config != deploy.rollback_config {
+        log(
+            LogType::Warning,
+            &format!("The rollback_config of the service {} is skipped as a rollback uses the update_config", name),
+            None
+        );
+    }
+
+    let config = deploy.update_config.as_ref().or(deploy.rollback_config.as_ref())?;
+    if let Some(action) = config.failure_action.as_ref().filter(|a| *a != PAUSE_ACTION) {
+        log(
+            LogType::Warning,
+            &format!("The failure_action {} of the service {} isn't supported, the rollout is paused on failure", action, name),
+            None
+        );
+    }
+
+    let (max_surge, max_unavailable) = match config.order {
+        UpdateOrder::StartFirst => (get_parallelism(config), IntOrString::Int(0)),
+        UpdateOrder::StopFirst => (IntOrString::Int(0), get_parallelism(config))
+    };
+
+    Some(DeploymentStrategy {
+        kind: DeploymentStrategyKind::RollingUpdate,
+        max_surge: Some(max_surge),
+        max_unavailable: Some(max_unavailable)
+    })
+}
+
+/// Get Min Ready Seconds
+///
+/// # Description
+/// Retrieve the time a new pod should be ready before the next pods are replaced from the delay
+/// of the `update_config` (or of the `rollback_config`)
+///
+/// # Arguments
+/// * `deploy` &DockerDeploy
+///
+/// # Return
+/// Option<u32>
+pub fn get_min_ready_seconds(deploy: &DockerDeploy) -> Option<u32> {
+    deploy.update_config
+        .as_ref()
+        .or(deploy.rollback_config.as_ref())
+        .and_then(|c| c.delay)
+        .filter(|d| *d > 0)
+}
+
+/// Get Pre Stop
+///
+/// # Description
+/// Retrieve the command of the preStop hook sending the `stop_signal` of the service to the main process
+/// of the container. Kubernetes always sends a SIGTERM thus no hook is needed for this signal
+///
+/// # Arguments
+/// * `signal` Option<&str> (e.g: SIGQUIT)
+///
+/// # Return
+/// Vec<String>
+pub fn get_pre_stop(signal: Option<&str>) -> Vec<String> {
+    let signal = match signal {
+        Some(s) if s.starts_with(SIGNAL_PREFIX) => &s[SIGNAL_PREFIX.len()..],
+        Some(s) => s,
+        None => return Vec::new()
+    };
+
+    if signal == DEFAULT_STOP_SIGNAL || signal == "15" {
+        return Vec::new();
+    }
+
+    vec![
+        String::from("/bin/sh"),
+        String::from("-c"),
+        format!("kill -{} 1", signal)
+    ]
+}
+
+/// Get Parallelism
+///
+/// # Description
+/// Retrieve the number of pods replaced at once. A parallelism of 0 replaces every pod at once
+///
+/// # Arguments
+/// * `config` &DockerUpdateConfig
+///
+/// # Return
+/// IntOrString
+fn get_parallelism(config: &DockerUpdateConfig) -> IntOrString {
+    match config.parallelism.unwrap_or(DEFAULT_PARALLELISM) {
+        0 => IntOrString::Str(String::from(ALL_PODS)),
+        p => IntOrString::Int(p)
+    }
+}
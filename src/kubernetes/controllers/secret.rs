@@ -0,0 +1,225 @@
+/// Secret
+///
+/// Module use to create the K8S Secret & ConfigMap of the docker-compose secrets & configs
+/// and to mount them into the pods
+use std::collections::{BTreeMap, HashMap};
+use serde::{Serialize};
+use super::helper::get_dns_name;
+use super::volume::{PodVolume, VolumeMount, KeyToPath};
+use super::labels::get_project_labels;
+use crate::docker::secret::{DockerFileObject, DockerFileReference, FileKind};
+use crate::core::logger::{log, LogType};
+
+/// Constant
+const SECRET_SUFFIX: &str = "-secret.yaml";
+const CONFIG_MAP_SUFFIX: &str = "-configmap.yaml";
+
+/// Kube File Object
+///
+/// # Description
+/// Structure representing a Secret or a ConfigMap holding the file of a docker-compose secret or config.
+/// Plain text is stored in `data` and base64 encoded content in `binary_data`
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct KubeFileObject {
+    pub name: String,
+    pub kind: FileKind,
+    pub labels: BTreeMap<String, String>,
+    pub data: BTreeMap<String, String>,
+    pub binary_data: BTreeMap<String, String>
+}
+
+impl KubeFileObject {
+    /// New
+    ///
+    /// # Description
+    /// Create a new KubeFileObject. The content of a secret is always base64 encoded while the content
+    /// of a config is embedded unless it's not a valid UTF-8 text
+    ///
+    /// # Arguments
+    /// * `obj` &DockerFileObject
+    /// * `labels` &BTreeMap<String, String>
+    ///
+    /// # Return
+    /// KubeFileObject
+    pub fn new(obj: &DockerFileObject, labels: &BTreeMap<String, String>) -> KubeFileObject {
+        let mut data = BTreeMap::new();
+        let mut binary_data = BTreeMap::new();
+        let text = match obj.kind {
+            FileKind::Secret => None,
+            FileKind::Config => std::str::from_utf8(&obj.data).ok()
+        };
+
+        match text {
+            Some(t) => data.insert(obj.name.clone(), String::from(t)),
+            None => binary_data.insert(obj.name.clone(), base64::encode(&obj.data))
+        };
+
+        KubeFileObject {
+            name: get_dns_name(&obj.name),
+            kind: obj.kind,
+            labels: labels.clone(),
+            data,
+            binary_data
+        }
+    }
+
+    /// Get Filename
+    ///
+    /// # Description
+    /// Retrieve the name of the file where the object is written
+    ///
+    /// # Return
+    /// String
+    pub fn get_filename(&self) -> String {
+        match self.kind {
+            FileKind::Secret => format!("{}{}", self.name, SECRET_SUFFIX),
+            FileKind::Config => format!("{}{}", self.name, CONFIG_MAP_SUFFIX)
+        }
+    }
+}
+
+/// Get File Objects
+///
+/// # Description
+/// Retrieve the Secrets & the ConfigMaps of the top-level secrets & configs. External files are
+/// expected to already exist in the cluster. The objects are shared by the services thus they're
+/// only labelled with the compose project
+///
+/// # Arguments
+/// * `objects` &[DockerFileObject]
+/// * `project` &str name of the compose project
+///
+/// # Return
+/// Vec<KubeFileObject>
+pub fn get_file_objects(objects: &[DockerFileObject], project: &str) -> Vec<KubeFileObject> {
+    let labels = get_project_labels(project);
+    objects
+        .iter()
+        .filter(|o| !o.external)
+        .map(|o| KubeFileObject::new(o, &labels))
+        .collect()
+}
+
+/// Get File Volumes
+///
+/// # Description
+/// Retrieve the pod volumes & the container mounts of the secrets & the configs of a service.
+/// Each file is mounted with a subPath so that it's available at the same target as with docker-compose
+/// without hiding the other files of the folder. The keys of an external object are unknown, the file
+/// is read from the key set in the confiture or from the key named after the compose file
+///
+/// # Arguments
+/// * `secrets` &[DockerFileReference]
+/// * `configs` &[DockerFileReference]
+/// * `keys` &HashMap<String, String> keys of the external objects by the name of their compose file
+///
+/// # Return
+/// (Vec<PodVolume>, Vec<VolumeMount>)
+pub fn get_file_volumes(
+    secrets: &[DockerFileReference],
+    configs: &[DockerFileReference],
+    keys: &HashMap<String, String>
+) -> (Vec<PodVolume>, Vec<VolumeMount>) {
+    let mut pod_volumes: Vec<PodVolume> = Vec::new();
+    let mut mounts = Vec::new();
+
+    let references = secrets
+        .iter()
+        .map(|r| (r, FileKind::Secret))
+        .chain(configs.iter().map(|r| (r, FileKind::Config)));
+
+    for (r, kind) in references {
+        let object = r.external_name
+            .clone()
+            .unwrap_or_else(|| get_dns_name(&r.source));
+
+        let prefix = match kind {
+            FileKind::Secret => "secret",
+            FileKind::Config => "config"
+        };
+
+        // a file could be mounted several times with different targets or modes
+        let base = format!("{}-{}", prefix, get_dns_name(&r.source));
+        let mut name = base.clone();
+        let mut idx = 1;
+        while pod_volumes.iter().any(|v| v.name == name) {
+            name = format!("{}-{}", base, idx);
+            idx += 1;
+        }
+
+        let key = match r.external_name {
+            Some(_) => keys.get(&r.source).cloned().unwrap_or_else(|| r.source.clone()),
+            None => r.source.clone()
+        };
+
+        let (secret, config_map) = match kind {
+            FileKind::Secret => (Some(object), None),
+            FileKind::Config => (None, Some(object))
+        };
+
+        pod_volumes.push(PodVolume {
+            name: name.clone(),
+            secret,
+            config_map,
+            items: vec![KeyToPath {
+                key,
+                path: r.source.clone(),
+                mode: r.mode
+            }],
+            ..Default::default()
+        });
+
+        mounts.push(VolumeMount {
+            name,
+            path: r.target.clone(),
+            read_only: true,
+            sub_path: Some(r.source.clone())
+        });
+    }
+
+    (pod_volumes, mounts)
+}
+
+/// Get Fs Group
+///
+/// # Description
+/// Retrieve the fsGroup of the pod from the gid of the secrets & the configs of a service. Kubernetes
+/// can't set the owner of a single file thus the uid is skipped and only one group is applied to
+/// every volume of the pod
+///
+/// # Arguments
+/// * `name` &str name of the service
+/// * `secrets` &[DockerFileReference]
+/// * `configs` &[DockerFileReference]
+///
+/// # Return
+/// Option<i64>
+pub fn get_fs_group(name: &str, secrets: &[DockerFileReference], configs: &[DockerFileReference]) -> Option<i64> {
+    let mut fs_group: Option<i64> = None;
+    for r in secrets.iter().chain(configs.iter()) {
+        if let Some(uid) = &r.uid {
+            log(
+                LogType::Warning,
+                &format!("The uid {} of the file {} of the service {} is not supported and is skipped", uid, r.source, name),
+                None
+            );
+        }
+
+        let gid = match &r.gid {
+            Some(g) => g,
+            None => continue
+        };
+
+        match (gid.parse::<i64>(), fs_group) {
+            (Ok(g), None) => fs_group = Some(g),
+            (Ok(g), Some(group)) if g == group => {},
+            _ => log(
+                LogType::Warning,
+                &format!("The gid {} of the file {} of the service {} can't be used as the fsGroup of the pod and is skipped", gid, r.source, name),
+                None
+            )
+        }
+    }
+
+    fs_group
+}
@@ -0,0 +1,258 @@
+/// Security
+///
+/// Module use to create the K8S security context of a container
+use std::path::Path;
+use serde::{Serialize};
+use crate::docker::security::DockerSecurity;
+use crate::core::logger::{log, LogType};
+
+/// Constant
+const UNCONFINED: &str = "unconfined";
+const RUNTIME_DEFAULT: [&str; 2] = ["default", "runtime/default"];
+const OPTION_SEPARATORS: [char; 2] = [':', '='];
+const CURRENT_DIR_PREFIX: &str = ".";
+
+/// Profile Kind
+///
+/// List of the kinds of seccomp & apparmor profiles supported by K8S
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum ProfileKind {
+    RuntimeDefault,
+    Unconfined,
+    Localhost
+}
+
+/// Kube Profile
+///
+/// # Description
+/// Structure representing a seccomp or an apparmor profile
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct KubeProfile {
+    pub kind: ProfileKind,
+    pub localhost_profile: Option<String>
+}
+
+/// Kube Capabilities
+///
+/// # Description
+/// Structure representing the capabilities added & dropped from a container
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct KubeCapabilities {
+    pub add: Vec<String>,
+    pub drop: Vec<String>
+}
+
+/// Se Linux Options
+///
+/// # Description
+/// Structure representing the SELinux labels of a container
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct SeLinuxOptions {
+    pub user: Option<String>,
+    pub role: Option<String>,
+    pub kind: Option<String>,
+    pub level: Option<String>
+}
+
+/// Kube Security Context
+///
+/// # Description
+/// Structure use to store the security context of a container
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct KubeSecurityContext {
+    pub run_as_user: Option<i64>,
+    pub run_as_group: Option<i64>,
+    pub privileged: bool,
+    pub read_only_root_filesystem: bool,
+    pub allow_privilege_escalation: Option<bool>,
+    pub capabilities: Option<KubeCapabilities>,
+    pub seccomp_profile: Option<KubeProfile>,
+    pub app_armor_profile: Option<KubeProfile>,
+    pub se_linux_options: Option<SeLinuxOptions>
+}
+
+/// Get Security Context
+///
+/// # Description
+/// Retrieve the security context of a container from the `user` and the security options of a
+/// docker-compose service. K8S only supports numeric ids, a user name (e.g: postgres) is skipped with a warning
+///
+/// # Arguments
+/// * `name` &str name of the service
+/// * `user` Option<&str> (e.g: 1000 or 1000:1000)
+/// * `security` &DockerSecurity
+///
+/// # Return
+/// Option<KubeSecurityContext>
+pub fn get_security_context(name: &str, user: Option<&str>, security: &DockerSecurity) -> Option<KubeSecurityContext> {
+    let mut ctx = KubeSecurityContext {
+        privileged: security.privileged,
+        read_only_root_filesystem: security.read_only,
+        ..Default::default()
+    };
+
+    if let Some(value) = user {
+        let mut parts = value.splitn(2, ':');
+        ctx.run_as_user = parse_id(name, parts.next());
+        ctx.run_as_group = parse_id(name, parts.next());
+    }
+
+    if !security.cap_add.is_empty() || !security.cap_drop.is_empty() {
+        ctx.capabilities = Some(KubeCapabilities {
+            add: security.cap_add.clone(),
+            drop: security.cap_drop.clone()
+        });
+    }
+
+    for opt in &security.security_opt {
+        apply_security_opt(name, opt, &mut ctx);
+    }
+
+    if ctx == KubeSecurityContext::default() {
+        return None;
+    }
+
+    Some(ctx)
+}
+
+/// Apply Security Opt
+///
+/// # Description
+/// Apply a `security_opt` of docker-compose on a security context. The supported options are
+/// no-new-privileges, seccomp, apparmor & label (SELinux). The other options are skipped with a warning
+///
+/// # Arguments
+/// * `name` &str name of the service
+/// * `opt` &str (e.g: seccomp:unconfined, no-new-privileges:true, label=type:svirt_apache_t)
+/// * `ctx` &mut KubeSecurityContext
+fn apply_security_opt(name: &str, opt: &str, ctx: &mut KubeSecurityContext) {
+    // docker accepts both `key:value` and `key=value`
+    let (key, value) = match opt.find(&OPTION_SEPARATORS[..]) {
+        Some(idx) => (&opt[..idx], Some(&opt[idx + 1..])),
+        None => (opt, None)
+    };
+
+    match (key, value) {
+        ("no-new-privileges", None) | ("no-new-privileges", Some("true")) => ctx.allow_privilege_escalation = Some(false),
+        ("no-new-privileges", Some(_)) => {},
+        ("seccomp", Some(profile)) => ctx.seccomp_profile = get_seccomp_profile(name, profile),
+        ("apparmor", Some(profile)) => ctx.app_armor_profile = Some(get_profile(profile)),
+        ("label", Some(label)) => apply_label(name, label, ctx),
+        _ => log(
+            LogType::Warning,
+            &format!("The security_opt {} of the service {} isn't supported and is skipped", opt, name),
+            None
+        )
+    }
+}
+
+/// Apply Label
+///
+/// # Description
+/// Apply a SELinux label (e.g: user:system_u, type:svirt_apache_t) on a security context
+///
+/// # Arguments
+/// * `name` &str name of the service
+/// * `label` &str
+/// * `ctx` &mut KubeSecurityContext
+fn apply_label(name: &str, label: &str, ctx: &mut KubeSecurityContext) {
+    let mut parts = label.splitn(2, ':');
+    let field = parts.next().unwrap_or(label);
+    let value = parts.next().map(String::from);
+
+    let options = ctx.se_linux_options.get_or_insert_with(SeLinuxOptions::default);
+    match (field, value) {
+        ("user", Some(v)) => options.user = Some(v),
+        ("role", Some(v)) => options.role = Some(v),
+        ("type", Some(v)) => options.kind = Some(v),
+        ("level", Some(v)) => options.level = Some(v),
+        _ => log(
+            LogType::Warning,
+            &format!("The label {} of the service {} isn't supported and is skipped", label, name),
+            None
+        )
+    }
+
+    if ctx.se_linux_options == Some(SeLinuxOptions::default()) {
+        ctx.se_linux_options = None;
+    }
+}
+
+/// Get Seccomp Profile
+///
+/// # Description
+/// Retrieve the seccomp profile of a container. Docker reads a seccomp profile from a file of the docker host
+/// whereas K8S resolves a localhost profile relative to the seccomp directory of the kubelet. Thus an absolute
+/// or a relative path of the host (e.g: /path/profile.json, ./profile.json) is skipped with a warning
+///
+/// # Arguments
+/// * `name` &str name of the service
+/// * `value` &str
+///
+/// # Return
+/// Option<KubeProfile>
+fn get_seccomp_profile(name: &str, value: &str) -> Option<KubeProfile> {
+    if Path::new(value).is_absolute() || value.starts_with(CURRENT_DIR_PREFIX) {
+        log(
+            LogType::Warning,
+            &format!("The seccomp profile {} of the service {} is a file of the docker host and is skipped", value, name),
+            None
+        );
+
+        return None;
+    }
+
+    Some(get_profile(value))
+}
+
+/// Get Profile
+///
+/// # Description
+/// Retrieve a seccomp or an apparmor profile. A profile which is neither `unconfined` nor the default
+/// profile of the runtime is a profile of the node
+///
+/// # Arguments
+/// * `value` &str
+///
+/// # Return
+/// KubeProfile
+fn get_profile(value: &str) -> KubeProfile {
+    if value == UNCONFINED {
+        return KubeProfile { kind: ProfileKind::Unconfined, localhost_profile: None };
+    }
+
+    if RUNTIME_DEFAULT.contains(&value) {
+        return KubeProfile { kind: ProfileKind::RuntimeDefault, localhost_profile: None };
+    }
+
+    KubeProfile {
+        kind: ProfileKind::Localhost,
+        localhost_profile: Some(String::from(value))
+    }
+}
+
+/// Parse Id
+///
+/// # Description
+/// Parse a numeric user or group id
+///
+/// # Arguments
+/// * `name` &str name of the service
+/// * `value` Option<&str>
+///
+/// # Return
+/// Option<i64>
+fn parse_id(name: &str, value: Option<&str>) -> Option<i64> {
+    let id = value.map(str::trim).filter(|v| !v.is_empty())?;
+    match id.parse::<i64>() {
+        Ok(i) => Some(i),
+        Err(_) => {
+            log(
+                LogType::Warning,
+                &format!("The user {} of the service {} isn't a numeric id and is skipped", id, name),
+                None
+            );
+            None
+        }
+    }
+}
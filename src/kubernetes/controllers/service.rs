@@ -2,14 +2,18 @@
 ///
 /// Module use to create a K8S Service datastructure
 use std::path::PathBuf;
+use std::collections::BTreeMap;
 use serde::{Serialize, Deserialize};
 use crate::docker::parser::{DockerService};
+use crate::docker::port::{DockerPort, Protocol};
 use crate::confiture::config::{ConfigService};
+use super::labels::{get_selector, get_labels};
 
 /// Constant
 pub const SVC_SUFFIX: &str = "-svc";
+pub const HEADLESS_SUFFIX: &str = "-headless";
 const SERVICE_FILENAME: &str = "service.yaml";
-const PORT_SEPARATOR: &str = ":";
+const HEADLESS_FILENAME: &str = "headless.yaml";
 
 /// Service Type
 ///
@@ -21,6 +25,25 @@ pub enum ServiceType {
     LoadBalancer
 }
 
+impl Default for ServiceType {
+    fn default() -> Self {
+        ServiceType::ClusterIP
+    }
+}
+
+/// Service Port
+///
+/// Structure representing a port exposed by a K8S service
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize)]
+pub struct ServicePort {
+    pub name: String,
+    pub protocol: Protocol,
+    pub port: u16,
+    pub target_port: u16,
+    pub node_port: Option<u16>
+}
+
 /// Kube Service
 ///
 /// Structure use to store the value of a K8S service
@@ -29,11 +52,11 @@ pub enum ServiceType {
 pub struct KubeService {
     pub path: PathBuf,
     pub name: String,
-    pub host_port: u16,
-    pub target_port: u16,
     pub kind: ServiceType,
-    pub labels: Vec<String>,
-    pub nodeport: u16,
+    pub headless: bool,
+    pub labels: BTreeMap<String, String>,
+    pub selector: BTreeMap<String, String>,
+    pub ports: Vec<ServicePort>
 }
 
 impl KubeService {
@@ -60,36 +83,115 @@ impl KubeService {
             return None;
         }
 
-        let mapped_ports = get_ports(&dk.ports[0]);
+        let selector = get_selector(&dk.name, &dk.project);
         let svc = KubeService {
             name: svc_name,
-            host_port: mapped_ports[0],
-            target_port: mapped_ports[1],
             kind: option.kind,
-            labels: dk.labels,
-            nodeport: option.nodeport,
+            headless: false,
+            labels: get_labels(&dk.name, &dk.labels, &selector),
+            selector,
+            ports: get_service_ports(&dk.ports, option),
             path: svc_path
         };
 
         Some(svc)
     }
+
+    /// New Headless
+    ///
+    /// # Description
+    /// Create the headless service which is governing the network identity of the pods of a StatefulSet
+    /// or resolving the hostname of the pods within their subdomain
+    ///
+    /// # Arguments
+    /// * `dk` DockerService
+    /// * `name` &str name of the headless service
+    /// * `kube_path` &PathBuf
+    ///
+    /// # Return
+    /// KubeService
+    pub fn new_headless(dk: DockerService, name: &str, kube_path: &PathBuf) -> KubeService {
+        let mut svc_path = PathBuf::from(kube_path);
+        svc_path.push(HEADLESS_FILENAME);
+
+        let selector = get_selector(&dk.name, &dk.project);
+        KubeService {
+            name: String::from(name),
+            kind: ServiceType::ClusterIP,
+            headless: true,
+            ports: get_service_ports(&dk.ports, &ConfigService::default()),
+            labels: get_labels(&dk.name, &dk.labels, &selector),
+            selector,
+            path: svc_path
+        }
+    }
+}
+
+/// Get Headless Name
+///
+/// # Description
+/// Retrieve the name of the headless service of a docker-compose service
+///
+/// # Arguments
+/// * `name` &str
+///
+/// # Return
+/// String
+pub fn get_headless_name(name: &str) -> String {
+    format!("{}{}", name, HEADLESS_SUFFIX)
 }
 
-/// Get Ports
+/// Get Service Ports
 ///
 /// # Description
-/// Retrieve ports
+/// Retrieve the list of named ports exposed by the service. Each published port is exposed once, the name
+/// of a port publishing a container port which is already exposed is suffixed by its published port
+/// (e.g: tcp-80-8080). A node port is only assigned when the service type allow it. The `nodeport`
+/// of the confiture is use as a fallback for the first port
 ///
 /// # Arguments
-/// * `ports` &str
+/// * `ports` &[DockerPort]
+/// * `option` &ConfigService
 ///
 /// # Return
-/// Vec<u16>
-pub fn get_ports(ports: &str) -> Vec<u16> {
-    let mapped_ports: Vec<u16> = ports
-        .split(PORT_SEPARATOR)
-        .map(|port| port.parse::<u16>().unwrap_or(0))
-        .collect();
-
-    mapped_ports
+/// Vec<ServicePort>
+fn get_service_ports(ports: &[DockerPort], option: &ConfigService) -> Vec<ServicePort> {
+    let mut svc_ports: Vec<ServicePort> = Vec::new();
+    for (idx, p) in ports.iter().enumerate() {
+        let port = p.get_published();
+        if svc_ports.iter().any(|sp| sp.port == port && sp.protocol == p.protocol) {
+            continue;
+        }
+
+        let mut name = p.get_name();
+        if svc_ports.iter().any(|sp| sp.name == name) {
+            name = format!("{}-{}", name, port);
+        }
+
+        let node_port = match option.kind {
+            ServiceType::ClusterIP => None,
+            _ => {
+                let configured = option.ports
+                    .iter()
+                    .find(|cp| cp.port == port && cp.protocol == p.protocol)
+                    .map(|cp| cp.nodeport);
+
+                match configured {
+                    Some(np) => Some(np),
+                    None if idx == 0 && option.nodeport > 0 => Some(option.nodeport),
+                    None => None
+                }
+            }
+        };
+
+        svc_ports.push(ServicePort {
+            name,
+            protocol: p.protocol,
+            port,
+            target_port: p.target,
+            node_port
+        });
+    }
+
+    svc_ports
 }
@@ -0,0 +1,214 @@
+/// Volume
+///
+/// Module use to create the K8S volume datastructures (PersistentVolumeClaim, pod volumes & mounts)
+use serde::{Serialize, Deserialize};
+use super::helper::get_dns_name;
+use crate::docker::volume::{DockerVolume, DockerNamedVolume};
+use crate::confiture::config::{ConfigVolume};
+
+/// Constant
+pub const PVC_SUFFIX: &str = "-pvc.yaml";
+const DEFAULT_SIZE: &str = "1Gi";
+
+/// Access Mode
+///
+/// List of access modes supported by a PersistentVolumeClaim
+#[allow(clippy::enum_variant_names)]
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, PartialEq)]
+pub enum AccessMode {
+    ReadWriteOnce,
+    ReadOnlyMany,
+    ReadWriteMany,
+    ReadWriteOncePod
+}
+
+/// Kube Volume Claim
+///
+/// Structure use to store the value of a K8S PersistentVolumeClaim
+#[derive(Debug, Clone)]
+#[derive(Serialize)]
+pub struct KubeVolumeClaim {
+    pub name: String,
+    pub size: String,
+    pub storage_class: Option<String>,
+    pub access_mode: AccessMode
+}
+
+/// Pod Volume
+///
+/// Structure representing a volume declared in the spec of a pod
+#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Serialize)]
+pub struct PodVolume {
+    pub name: String,
+    pub claim: Option<String>,
+    pub secret: Option<String>,
+    pub config_map: Option<String>,
+    pub items: Vec<KeyToPath>,
+    pub default_mode: Option<u32>,
+    pub empty_dir: Option<EmptyDirVolume>,
+    pub host_path: Option<String>
+}
+
+/// Empty Dir Volume
+///
+/// Structure representing a scratch volume living as long as the pod (e.g: tmpfs)
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize)]
+pub struct EmptyDirVolume {
+    pub medium: Option<String>,
+    pub size_limit: Option<String>
+}
+
+/// Key To Path
+///
+/// Structure representing a key of a Secret or a ConfigMap projected as a file in a volume
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize)]
+pub struct KeyToPath {
+    pub key: String,
+    pub path: String,
+    pub mode: Option<u32>
+}
+
+/// Volume Mount
+///
+/// Structure representing a volume mounted in a container
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize)]
+pub struct VolumeMount {
+    pub name: String,
+    pub path: String,
+    pub read_only: bool,
+    pub sub_path: Option<String>
+}
+
+impl KubeVolumeClaim {
+    /// New
+    ///
+    /// # Description
+    /// Create a new KubeVolumeClaim
+    ///
+    /// # Arguments
+    /// * `name` &str name of the docker-compose volume
+    /// * `option` Option<&ConfigVolume>
+    ///
+    /// # Return
+    /// KubeVolumeClaim
+    pub fn new(name: &str, option: Option<&ConfigVolume>) -> KubeVolumeClaim {
+        KubeVolumeClaim {
+            name: get_dns_name(name),
+            size: option
+                .and_then(|o| o.size.clone())
+                .unwrap_or_else(|| String::from(DEFAULT_SIZE)),
+            storage_class: option.and_then(|o| o.storage_class.clone()),
+            access_mode: option
+                .and_then(|o| o.access_mode)
+                .unwrap_or(AccessMode::ReadWriteOnce)
+        }
+    }
+
+    /// Get Filename
+    ///
+    /// # Description
+    /// Retrieve the name of the file where the claim is written
+    ///
+    /// # Return
+    /// String
+    pub fn get_filename(&self) -> String {
+        format!("{}{}", self.name, PVC_SUFFIX)
+    }
+}
+
+/// Get Volume Claims
+///
+/// # Description
+/// Retrieve the PersistentVolumeClaim of every named volume which is used by the services or declared
+/// in the top-level `volumes` key. External volumes are expected to already exist in the cluster
+///
+/// # Arguments
+/// * `volumes` &[DockerVolume] volumes used by the services
+/// * `named` &[DockerNamedVolume] top-level volumes
+/// * `options` &[ConfigVolume]
+///
+/// # Return
+/// Vec<KubeVolumeClaim>
+pub fn get_volume_claims(volumes: &[DockerVolume], named: &[DockerNamedVolume], options: &[ConfigVolume]) -> Vec<KubeVolumeClaim> {
+    let mut names: Vec<&str> = named
+        .iter()
+        .filter(|v| !v.external)
+        .map(|v| v.name.as_str())
+        .collect();
+
+    for v in volumes.iter().filter(|v| v.is_named()) {
+        let external = named.iter().any(|n| n.external && n.name == v.source);
+        if !external && !names.contains(&v.source.as_str()) {
+            names.push(&v.source);
+        }
+    }
+
+    names
+        .into_iter()
+        .map(|name| KubeVolumeClaim::new(name, options.iter().find(|o| o.name == name)))
+        .collect()
+}
+
+/// Get Volume Claim Templates
+///
+/// # Description
+/// Retrieve the claim templates of a StatefulSet from the named volumes used by the service.
+/// External volumes are skipped as they're expected to be bound to an existing claim
+///
+/// # Arguments
+/// * `volumes` &[DockerVolume] volumes used by the service
+/// * `options` &[ConfigVolume]
+///
+/// # Return
+/// Vec<KubeVolumeClaim>
+pub fn get_volume_claim_templates(volumes: &[DockerVolume], options: &[ConfigVolume]) -> Vec<KubeVolumeClaim> {
+    let mut claims: Vec<KubeVolumeClaim> = Vec::new();
+    for v in volumes.iter().filter(|v| v.is_named() && !v.external) {
+        let claim = KubeVolumeClaim::new(&v.source, options.iter().find(|o| o.name == v.source));
+        if !claims.iter().any(|c| c.name == claim.name) {
+            claims.push(claim);
+        }
+    }
+
+    claims
+}
+
+/// Get Pod Volumes
+///
+/// # Description
+/// Retrieve the pod volumes & the container mounts of the named volumes of a service. An external
+/// volume with a `name` is bound to the existing claim of the same name
+///
+/// # Arguments
+/// * `volumes` &[DockerVolume]
+///
+/// # Return
+/// (Vec<PodVolume>, Vec<VolumeMount>)
+pub fn get_pod_volumes(volumes: &[DockerVolume]) -> (Vec<PodVolume>, Vec<VolumeMount>) {
+    let mut pod_volumes: Vec<PodVolume> = Vec::new();
+    let mut mounts = Vec::new();
+
+    for v in volumes.iter().filter(|v| v.is_named()) {
+        let name = get_dns_name(&v.source);
+        if !pod_volumes.iter().any(|p| p.name == name) {
+            pod_volumes.push(PodVolume {
+                name: name.clone(),
+                claim: Some(v.external_name.clone().unwrap_or_else(|| name.clone())),
+                ..Default::default()
+            });
+        }
+
+        mounts.push(VolumeMount {
+            name,
+            path: v.target.clone(),
+            read_only: v.read_only,
+            sub_path: None
+        });
+    }
+
+    (pod_volumes, mounts)
+}
@@ -1,8 +1,14 @@
 use serde::{Serialize};
 use crate::assets::loader::{K8SAssetType};
 use crate::kubernetes::builder::{Kube};
+use crate::kubernetes::io::output;
 use crate::kubernetes::template::controller::{ControllerTmplBuilder};
 use crate::kubernetes::template::service::{ServiceTmplBuilder};
+use crate::kubernetes::template::volume::{VolumeClaimTmplBuilder};
+use crate::kubernetes::template::environment::{EnvTmplBuilder};
+use crate::kubernetes::controllers::volume::{KubeVolumeClaim};
+use crate::kubernetes::controllers::secret::{KubeFileObject};
+use crate::docker::secret::{FileKind};
 use crate::kubernetes::template::helper::common::TemplateBuilder;
 use crate::core::errors::cli_error::{ErrHelper};
 
@@ -16,10 +22,58 @@ use crate::core::errors::cli_error::{ErrHelper};
 pub fn render_kubes_objects(kubes: Vec<Kube>) {
     let ctrl_tmpl = ControllerTmplBuilder {};
     let svc_tmpl  = ServiceTmplBuilder {}; 
+    let env_tmpl  = EnvTmplBuilder {};
 
     for k in kubes.into_iter() {
-        display_template(&ctrl_tmpl, K8SAssetType::Controller, k.ctrl);
-        display_template(&svc_tmpl, K8SAssetType::Service, k.svc);
+        let asset = output::get_controller_asset(k.ctrl.ctrl);
+        display_template(&ctrl_tmpl, asset, k.ctrl);
+        if let Some(svc) = k.svc {
+            display_template(&svc_tmpl, K8SAssetType::Service, svc);
+        }
+        if let Some(headless) = k.headless {
+            display_template(&svc_tmpl, K8SAssetType::Service, headless);
+        }
+        if let Some(config_map) = k.config_map {
+            display_template(&env_tmpl, K8SAssetType::ConfigMap, config_map);
+        }
+        if let Some(secret) = k.secret {
+            display_template(&env_tmpl, K8SAssetType::Secret, secret);
+        }
+    }
+}
+
+/// Render Volume Claims
+/// 
+/// # Description
+/// Display the output of the PersistentVolumeClaim objects
+/// 
+/// # Arguments
+/// * `claims` Vec<KubeVolumeClaim>
+pub fn render_volume_claims(claims: Vec<KubeVolumeClaim>) {
+    let pvc_tmpl = VolumeClaimTmplBuilder {};
+
+    for c in claims.into_iter() {
+        display_template(&pvc_tmpl, K8SAssetType::VolumeClaim, c);
+    }
+}
+
+/// Render File Objects
+/// 
+/// # Description
+/// Display the output of the Secret & ConfigMap objects of the docker-compose secrets & configs
+/// 
+/// # Arguments
+/// * `files` Vec<KubeFileObject>
+pub fn render_file_objects(files: Vec<KubeFileObject>) {
+    let env_tmpl = EnvTmplBuilder {};
+
+    for f in files.into_iter() {
+        let asset = match f.kind {
+            FileKind::Secret => K8SAssetType::Secret,
+            FileKind::Config => K8SAssetType::ConfigMap
+        };
+
+        display_template(&env_tmpl, asset, f);
     }
 }
 
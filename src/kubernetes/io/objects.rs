@@ -3,6 +3,8 @@ use serde::{Serialize};
 use async_std::task::{spawn, block_on};
 use async_std::{fs};
 use crate::kubernetes::template::ingress::IngressTmplBuilder;
+use crate::kubernetes::template::volume::VolumeClaimTmplBuilder;
+use crate::kubernetes::template::environment::EnvTmplBuilder;
 use crate::kubernetes::io::output;
 use crate::assets::loader::{K8SAssetType};
 use crate::core::errors::cli_error::{CliErr, ErrHelper, ErrMessage};
@@ -17,7 +19,10 @@ use crate::core::errors::message::io::{
 /// # Description
 /// List of K8S Objects (exclude from controller & services)
 pub enum Objects {
-    Ingress
+    Ingress,
+    VolumeClaim,
+    Secret,
+    ConfigMap
 }
 
 /// Create
@@ -27,25 +32,27 @@ pub enum Objects {
 ///
 /// # Arguments
 /// * `data` T where T = Serialize
-/// * `filename` String
+/// * `filename` &str
 /// * `pp` PathBuf (project path)
 /// * `kind` Objects
 ///
 /// # Return
 /// Result<(), ()>
-pub fn create<T: Serialize>(data: T, filename: &'static str, kind: Objects) -> Result<(), ()> {
-    let template = match kind {
-        Objects::Ingress => IngressTmplBuilder {}
-    };
-
+pub fn create<T: Serialize>(data: T, filename: &str, kind: Objects) -> Result<(), ()> {
     // render the component by using the template
-    let res = output::render_component(&template, &data, K8SAssetType::Ingress);
+    let res = match kind {
+        Objects::Ingress => output::render_component(&IngressTmplBuilder {}, &data, K8SAssetType::Ingress),
+        Objects::VolumeClaim => output::render_component(&VolumeClaimTmplBuilder {}, &data, K8SAssetType::VolumeClaim),
+        Objects::Secret => output::render_component(&EnvTmplBuilder {}, &data, K8SAssetType::Secret),
+        Objects::ConfigMap => output::render_component(&EnvTmplBuilder {}, &data, K8SAssetType::ConfigMap)
+    };
     if let Err(e) = res {
         e.log_pretty();
         return Err(());
     }
 
     let rendered_tmpl = res.unwrap();
+    let filename = String::from(filename);
     // Create the object asynchronously
     let task = spawn(async move {
         let path = config::get_current_project_path();
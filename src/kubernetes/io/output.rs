@@ -5,6 +5,7 @@
 use serde::{Serialize};
 use crate::kubernetes::template::helper::common::TemplateBuilder;
 use crate::assets::loader::{K8SAssetType};
+use crate::kubernetes::controllers::controller::{ControllerKind};
 use crate::core::errors::cli_error::{CliErr};
 
 /// Render Component
@@ -22,3 +23,21 @@ use crate::core::errors::cli_error::{CliErr};
 pub fn render_component<T: Serialize>(tmpl: &impl TemplateBuilder, cmp: &T, k8s_type: K8SAssetType) -> Result<String, CliErr> {
     tmpl.render(cmp, k8s_type)
 }
+
+/// Get Controller Asset
+/// 
+/// # Description
+/// Retrieve the template use to render a kind of controller
+/// 
+/// # Arguments
+/// * `kind` ControllerKind
+/// 
+/// # Return
+/// K8SAssetType
+pub fn get_controller_asset(kind: ControllerKind) -> K8SAssetType {
+    match kind {
+        ControllerKind::Job => K8SAssetType::Job,
+        ControllerKind::CronJob => K8SAssetType::CronJob,
+        _ => K8SAssetType::Controller
+    }
+}
@@ -6,6 +6,7 @@ use crate::kubernetes::io::output;
 use crate::kubernetes::builder::{Kube};
 use crate::kubernetes::template::controller::{ControllerTmplBuilder};
 use crate::kubernetes::template::service::{ServiceTmplBuilder};
+use crate::kubernetes::template::environment::{EnvTmplBuilder};
 use crate::assets::loader::{K8SAssetType};
 use crate::core::errors::cli_error::{CliErr, ErrHelper, ErrMessage};
 use crate::core::errors::message::io::CREATING_FILE;
@@ -28,7 +29,8 @@ fn create_controller(k: &[Kube]) -> Vec<impl Future<Output = io::Result<()>>> {
     let mut vec = Vec::new();
 
     for c in k {
-        let tmpl = output::render_component(&ctrl_tmpl, &c.ctrl, K8SAssetType::Controller);
+        let asset = output::get_controller_asset(c.ctrl.ctrl);
+        let tmpl = output::render_component(&ctrl_tmpl, &c.ctrl, asset);
         match tmpl {
             Ok(t) => {
                 let future = fs::write(c.ctrl.path.clone(), t.clone());
@@ -44,7 +46,7 @@ fn create_controller(k: &[Kube]) -> Vec<impl Future<Output = io::Result<()>>> {
 /// Create Service
 ///
 /// # Description
-/// Create the service files asynchronously. We're retrieving a vector of
+/// Create the service files (including the headless services of the StatefulSets) asynchronously. We're retrieving a vector of
 /// future that are going to be resolve in a async std thread
 ///
 /// # Arguments
@@ -56,16 +58,53 @@ fn create_service(k: &[Kube]) -> Vec<impl Future<Output = io::Result<()>>> {
     let svc_tmpl = ServiceTmplBuilder {};
     let mut vec = Vec::new();
 
-    for s in k {
-        if let Some(svc) = &s.svc {
-            let tmpl = output::render_component(&svc_tmpl, &svc, K8SAssetType::Service);
-            match tmpl {
-                Ok(t) => {
-                    let future = fs::write(svc.path.clone(), t.clone());
-                    vec.push(future);
-                },
-                Err(err) => err.log_pretty()
-            }
+    let services = k
+        .iter()
+        .flat_map(|s| s.svc.iter().chain(s.headless.iter()));
+
+    for svc in services {
+        let tmpl = output::render_component(&svc_tmpl, &svc, K8SAssetType::Service);
+        match tmpl {
+            Ok(t) => {
+                let future = fs::write(svc.path.clone(), t.clone());
+                vec.push(future);
+            },
+            Err(err) => err.log_pretty()
+        }
+    }
+
+    vec
+}
+
+/// Create Environment
+///
+/// # Description
+/// Create the ConfigMap & the Secret files holding the environment of the services asynchronously
+///
+/// # Arguments
+/// * `k` &[Kube]
+///
+/// # Return
+/// Vec<impl Future<Output = io::Result<()>>>
+fn create_environment(k: &[Kube]) -> Vec<impl Future<Output = io::Result<()>>> {
+    let env_tmpl = EnvTmplBuilder {};
+    let mut vec = Vec::new();
+
+    let objects = k
+        .iter()
+        .flat_map(|e| {
+            e.config_map.iter().map(|c| (c, K8SAssetType::ConfigMap))
+                .chain(e.secret.iter().map(|s| (s, K8SAssetType::Secret)))
+        });
+
+    for (obj, asset) in objects {
+        let tmpl = output::render_component(&env_tmpl, obj, asset);
+        match tmpl {
+            Ok(t) => {
+                let future = fs::write(obj.path.clone(), t.clone());
+                vec.push(future);
+            },
+            Err(err) => err.log_pretty()
         }
     }
 
@@ -106,6 +145,7 @@ fn parse_output(res: Vec<Result<(), io::Error>>) -> Vec<Result<(), CliErr>> {
 pub fn create_default_object(k: Vec<Kube>) -> Result<(), ()> {
     let ctrl_fut = create_controller(&k);
     let svc_fut  = create_service(&k);
+    let env_fut  = create_environment(&k);
 
     // Create the async task to run the vec of futures
     let ctrl_task = task::spawn(async move {
@@ -128,10 +168,21 @@ pub fn create_default_object(k: Vec<Kube>) -> Result<(), ()> {
         return Ok(());
     });
 
+    let env_task = task::spawn(async move {
+        let tasks = join_all(env_fut).await;
+        let out: Vec<Result<(), CliErr>> = parse_output(tasks);
+        if !out.is_empty() {
+            return Err(out);
+        }
+
+        Ok(())
+    });
+
     // run the tasks and wait for their results
     task::block_on(async {
         let sres = svc_task.await;
         let cres = ctrl_task.await;
+        let eres = env_task.await;
 
         if let Err(e) = sres {
             print_errors(e);
@@ -143,6 +194,11 @@ pub fn create_default_object(k: Vec<Kube>) -> Result<(), ()> {
             return Err(());
         }
 
+        if let Err(e) = eres {
+            print_errors(e);
+            return Err(());
+        }
+
         Ok(())
     })
 }
@@ -0,0 +1,16 @@
+/// Environment
+/// 
+/// # Path
+/// kubernetes/template/environment.rs
+/// 
+/// # Description
+/// Module use to template the Kubernetes ConfigMap & Secret holding the environment of a service
+use crate::kubernetes::template::helper::common::{TemplateBuilder};
+
+/// Env Tmpl Builder
+/// 
+/// # Description
+/// Struct use to build the ConfigMap & the Secret templates
+pub struct EnvTmplBuilder {}
+
+impl TemplateBuilder for EnvTmplBuilder {}
@@ -10,6 +10,22 @@ pub struct Mapper;
 #[derive(Clone, Copy)]
 pub struct Lengthie;
 
+#[derive(Clone, Copy)]
+pub struct Quote;
+
+#[derive(Clone, Copy)]
+pub struct Indent;
+
+/// Buffer use to capture the output of a block
+struct Buffer(String);
+
+impl Output for Buffer {
+    fn write(&mut self, seg: &str) -> Result<(), std::io::Error> {
+        self.0.push_str(seg);
+        Ok(())
+    }
+}
+
 /// Default Padding value
 const DEFAULT_PADDING: u64 = 0;
 
@@ -33,8 +49,8 @@ impl HelperDef for Dictionnary {
         };
 
         if let Some(v) = list.unwrap().value().as_array() {
-            for key in v {
-                let value = formatter(key, ident, "-");
+            for item in v {
+                let value = formatter(&get_string(item), None, ident);
                 out.write(value.as_str())?;
             }
         }
@@ -62,9 +78,9 @@ impl HelperDef for Mapper {
             None => DEFAULT_PADDING
         };
 
-        if let Some(v) = list.unwrap().value().as_array() {
-            for key in v {
-                let value = formatter(key, ident, "");
+        if let Some(v) = list.unwrap().value().as_object() {
+            for (key, item) in v {
+                let value = formatter(key, Some(item), ident);
                 out.write(value.as_str())?;
             }
         }
@@ -110,25 +126,86 @@ impl HelperDef for Lengthie {
     }
 }
 
-/// Formatter
+impl HelperDef for Quote {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper,
+        _: &Handlebars,
+        _: &Context,
+        _: &mut RenderContext,
+        out: &mut dyn Output
+    ) -> HelperResult {
+        let value = match h.param(0) {
+            Some(v) => v.value(),
+            None => return Err(RenderError::new(ARGUMENT))
+        };
+
+        out.write(quote(&get_string(value)).as_str())?;
+        Ok(())
+    }
+}
+
+impl HelperDef for Indent {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output
+    ) -> HelperResult {
+        let ident = match h.param(0) {
+            Some(v) => v.value().as_u64().unwrap_or(DEFAULT_PADDING),
+            None => DEFAULT_PADDING
+        };
+
+        let mut buffer = Buffer(String::new());
+        if let Some(t) = h.template() {
+            t.render(r, ctx, rc, &mut buffer)?;
+        }
+
+        let padding = format!("\n{:ident$}", "", ident=ident as usize);
+        out.write(buffer.0.replace('\n', &padding).as_str())?;
+        Ok(())
+    }
+}
+
+/// Get String
 ///
 /// # Description
-/// Format a JsonArray based on the criteria (map or array yaml)
+/// Retrieve the raw string of a JsonValue without the json quotes
 ///
 /// # Return
 /// String
-fn formatter(key: &handlebars::JsonValue, ident: u64, prefix: &str) -> String {
-    let s: Vec<String> = format!("{}", key)
-        .split('=')
-        .map(|value| value.replace('"', ""))
-        .collect();
-
-    if s.len() > 1 {
-        let value = format!("\n {:ident$} {}{}: {}", "", prefix, s[0], s[1], ident=ident as usize);
-        return value;
+fn get_string(value: &handlebars::JsonValue) -> String {
+    match value.as_str() {
+        Some(s) => String::from(s),
+        None => value.to_string()
     }
+}
 
-    let value = format!("\n {:ident$}- {}{}", "", prefix, s[0], ident=ident as usize);
-    value
+/// Quote
+///
+/// # Description
+/// Wrap a value in a yaml double quoted string so that values such as `true` or `8080` stay strings
+///
+/// # Return
+/// String
+fn quote(value: &str) -> String {
+    handlebars::JsonValue::String(String::from(value)).to_string()
+}
+
+/// Formatter
+///
+/// # Description
+/// Format a key with an optional value as a yaml map entry or as a yaml list item
+///
+/// # Return
+/// String
+fn formatter(key: &str, value: Option<&handlebars::JsonValue>, ident: u64) -> String {
+    match value {
+        Some(v) => format!("\n {:ident$} {}: {}", "", key, quote(&get_string(v)), ident=ident as usize),
+        None => format!("\n {:ident$}- {}", "", key, ident=ident as usize)
+    }
 }
 
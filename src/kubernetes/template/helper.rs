@@ -29,12 +29,22 @@ pub mod common {
         /// Result<Y, CliErr>
         fn render<T>(&self, data: &T, kind: K8SAssetType) -> Result<String, CliErr> where T : Serialize {
             let mut handlebars = Handlebars::new();
-            // Print Yaml dictionnary
-            handlebars.register_helper("dictionnary", Box::new(formatter::Dictionnary));
             // Print Array yaml
+            handlebars.register_helper("dictionnary", Box::new(formatter::Dictionnary));
+            // Print Yaml map
             handlebars.register_helper("mapper", Box::new(formatter::Mapper));
             // Display block if the array is fill
             handlebars.register_helper("lengthie", Box::new(formatter::Lengthie));
+            // Print a value as a yaml quoted string
+            handlebars.register_helper("quote", Box::new(formatter::Quote));
+            // Indent every lines of a block (e.g: shared partials)
+            handlebars.register_helper("indent", Box::new(formatter::Indent));
+
+            // Pod spec shared by the controllers templates
+            let pod = retrieve_asset_content(K8SAssetType::Pod)?;
+            if let Err(e) = handlebars.register_partial("pod", pod) {
+                return Err(CliErr::new(RENDERING, &e.to_string(), ErrMessage::RendererError));
+            }
 
             let content_opt = retrieve_asset_content(kind);
             if let Err(e) = content_opt {
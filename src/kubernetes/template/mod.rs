@@ -1,5 +1,7 @@
 pub mod controller;
 pub mod service;
 pub mod ingress;
+pub mod volume;
+pub mod environment;
 pub mod helper;
 mod formatter;
\ No newline at end of file
@@ -0,0 +1,16 @@
+/// Volume
+/// 
+/// # Path
+/// kubernetes/template/volume.rs
+/// 
+/// # Description
+/// Module use to template a Kubernetes PersistentVolumeClaim
+use crate::kubernetes::template::helper::common::{TemplateBuilder};
+
+/// Volume Claim Tmpl Builder
+/// 
+/// # Description
+/// Struct use to build the persistent volume claim template
+pub struct VolumeClaimTmplBuilder {}
+
+impl TemplateBuilder for VolumeClaimTmplBuilder {}
@@ -1,12 +1,18 @@
 #[cfg(test)]
 mod confiture {
     use crate::confiture::config;
-    use crate::kubernetes::controllers::controller::ControllerKind;
+    use crate::kubernetes::controllers::controller::{ControllerKind, UpdateStrategyKind, IntOrString, TolerationOperator, TaintEffect};
     use crate::kubernetes::controllers::service::ServiceType;
+    use crate::kubernetes::controllers::volume::AccessMode;
+    use crate::kubernetes::controllers::probe::ProbeKind;
+    use crate::kubernetes::controllers::rollout::DeploymentStrategyKind;
+    use crate::kubernetes::controllers::placement::{AffinityKind, UnsatisfiableAction};
+    use crate::docker::parser::DockerService;
+    use serde_json::json;
 
     #[test]
     fn expect_to_load_confiture() {
-        let cnf = config::load("./example");
+        let cnf = config::load("./example", &[]);
         if cnf.is_none() {
             panic!("Expect to retrieve a confiture.json");
         }
@@ -14,22 +20,92 @@ mod confiture {
 
     #[test]
     fn expect_to_get_confiture_value() {
-        let cnf = config::load("./example").unwrap();
+        let cnf = config::load("./example", &[]).unwrap();
         let value = cnf.get_config_confiture_map();
 
         // testing the values
         // deployment
-        assert_eq!(value.get("web").unwrap().deployment.replicas, 1);
-        assert_eq!(value.get("web").unwrap().deployment.controller, ControllerKind::Deployment);
+        assert_eq!(value.get("web").unwrap().deployment.replicas, Some(1));
+        assert_eq!(value.get("web").unwrap().deployment.controller, Some(ControllerKind::Deployment));
 
         // service
         assert_eq!(value.get("web").unwrap().service.kind, ServiceType::NodePort);
         assert_eq!(value.get("web").unwrap().service.nodeport, 30302);
     }
 
+    #[test]
+    fn expect_to_get_nodeport_per_port() {
+        let cnf = config::load("./example", &[]).unwrap();
+        let value = cnf.get_config_confiture_map();
+        let ports = &value.get("front").unwrap().service.ports;
+
+        assert_eq!(ports[0].port, 8080);
+        assert_eq!(ports[0].nodeport, 30380);
+        assert_eq!(ports[1].port, 9100);
+        assert_eq!(ports[1].nodeport, 30391);
+
+        // the replicas of the compose file are used
+        assert!(value.get("front").unwrap().deployment.replicas.is_none());
+
+        let strategy = value.get("front").unwrap().deployment.strategy.as_ref().unwrap();
+        assert_eq!(strategy.kind, DeploymentStrategyKind::Recreate);
+        assert!(strategy.max_surge.is_none());
+    }
+
+    #[test]
+    fn expect_to_get_placement_options() {
+        let cnf = config::load("./example", &[]).unwrap();
+        let value = cnf.get_config_confiture_map();
+        let deployment = &value.get("bobba").unwrap().deployment;
+        let spread = &deployment.topology_spread_constraints[0];
+        let anti_affinity = deployment.anti_affinity.as_ref().unwrap();
+
+        assert_eq!(spread.max_skew, 1);
+        assert_eq!(spread.topology_key, "kubernetes.io/hostname");
+        assert_eq!(spread.when_unsatisfiable, UnsatisfiableAction::DoNotSchedule);
+        assert_eq!(anti_affinity.kind, AffinityKind::Preferred);
+        assert_eq!(anti_affinity.topology_key, "kubernetes.io/hostname");
+    }
+
+    #[test]
+    fn expect_to_get_daemonset_options() {
+        let cnf = config::load("./example", &[]).unwrap();
+        let value = cnf.get_config_confiture_map();
+        let deployment = &value.get("agent").unwrap().deployment;
+        let strategy = deployment.update_strategy.as_ref().unwrap();
+
+        assert!(deployment.controller.is_none());
+        assert_eq!(strategy.kind, UpdateStrategyKind::RollingUpdate);
+        assert_eq!(strategy.max_unavailable, Some(IntOrString::Str("25%".to_owned())));
+        assert_eq!(deployment.tolerations[0].key, Some("node-role.kubernetes.io/master".to_owned()));
+        assert_eq!(deployment.tolerations[0].operator, TolerationOperator::Exists);
+        assert_eq!(deployment.tolerations[0].effect, Some(TaintEffect::NoSchedule));
+    }
+
+    #[test]
+    fn expect_to_get_probes_options() {
+        let cnf = config::load("./example", &[]).unwrap();
+        let value = cnf.get_config_confiture_map();
+        let probes = value.get("mysql").unwrap().deployment.probes.as_ref().unwrap();
+
+        assert_eq!(probes.kinds, Some(vec![ProbeKind::Liveness, ProbeKind::Readiness]));
+        assert_eq!(probes.failure_threshold, Some(5));
+        assert!(probes.period_seconds.is_none());
+    }
+
+    #[test]
+    fn expect_to_get_volumes() {
+        let cnf = config::load("./example", &[]).unwrap();
+
+        assert_eq!(cnf.volumes[0].name, "db-data");
+        assert_eq!(cnf.volumes[0].size, Some("10Gi".to_owned()));
+        assert_eq!(cnf.volumes[0].storage_class, Some("standard".to_owned()));
+        assert_eq!(cnf.volumes[0].access_mode, Some(AccessMode::ReadWriteOnce));
+    }
+
     #[test]
     fn expect_to_get_ingress() {
-        let cnf = config::load("./example").unwrap();
+        let cnf = config::load("./example", &[]).unwrap();
         let ingress = cnf.ingress.unwrap();
 
         assert_eq!(ingress.ip, "30.10.20.30");
@@ -38,8 +114,109 @@ mod confiture {
     }
 
     #[test]
-    fn expect_missing_property_to_return_none() {
-        let cnf = config::load("./example_failure");
+    fn expect_invalid_property_to_return_none() {
+        let cnf = config::load("./example_failure", &[]);
         assert!(cnf.is_none());
     }
+
+    #[test]
+    fn expect_to_get_default_sensitive_patterns() {
+        let cnf = config::load("./example", &[]).unwrap();
+        let patterns = cnf.get_sensitive_patterns();
+
+        assert!(patterns.contains(&"*_PASSWORD".to_owned()));
+        assert!(patterns.contains(&"*_TOKEN".to_owned()));
+    }
+
+    #[test]
+    fn expect_to_merge_extensions() {
+        let services = vec![
+            DockerService {
+                name: "web".to_owned(),
+                extension: Some(json!({
+                    "deployment": { "replicas": 5, "wait_for_dependencies": true, "resources": { "limits": { "cpu": "1" } } }
+                })),
+                ..Default::default()
+            },
+            DockerService {
+                name: "api".to_owned(),
+                extension: Some(json!({
+                    "deployment": { "replicas": 2, "controller": "Deployment" },
+                    "service": { "kind": "ClusterIP" }
+                })),
+                ..Default::default()
+            }
+        ];
+
+        let cnf = config::load("./example", &services).unwrap();
+        let value = cnf.get_config_confiture_map();
+
+        // the confiture.json takes precedence over the extension
+        let web = &value.get("web").unwrap().deployment;
+        assert_eq!(web.replicas, Some(1));
+        assert_eq!(web.wait_for_dependencies, Some(false));
+        assert_eq!(web.resources.as_ref().unwrap().limits.as_ref().unwrap().cpu, Some("1".to_owned()));
+
+        let api = value.get("api").unwrap();
+        assert_eq!(api.deployment.replicas, Some(2));
+        assert_eq!(api.service.kind, ServiceType::ClusterIP);
+    }
+
+    #[test]
+    fn expect_to_apply_defaults() {
+        let services = vec![
+            DockerService {
+                name: "worker".to_owned(),
+                ..Default::default()
+            },
+            DockerService {
+                name: "cron".to_owned(),
+                extension: Some(json!({ "skip": true })),
+                ..Default::default()
+            }
+        ];
+
+        let cnf = config::load("./example", &services).unwrap();
+        let value = cnf.get_config_confiture_map();
+
+        // a service without any entry uses the defaults
+        let worker = value.get("worker").unwrap();
+        assert_eq!(worker.deployment.wait_for_dependencies, Some(true));
+        assert_eq!(worker.deployment.replicas, None);
+        assert_eq!(worker.service.kind, ServiceType::ClusterIP);
+        assert!(!cnf.is_skipped("worker"));
+
+        // the entries of the confiture.json take precedence over the defaults
+        let web = value.get("web").unwrap();
+        assert_eq!(web.deployment.wait_for_dependencies, Some(false));
+        assert_eq!(web.service.kind, ServiceType::NodePort);
+        assert!(cnf.is_skipped("cron"));
+    }
+
+    #[test]
+    fn expect_missing_confiture_to_use_defaults() {
+        let services = vec![
+            DockerService {
+                name: "worker".to_owned(),
+                ..Default::default()
+            }
+        ];
+
+        let cnf = config::load("./src", &services).unwrap();
+        let value = cnf.get_config_confiture_map();
+
+        let worker = value.get("worker").unwrap();
+        assert_eq!(worker.deployment.controller, None);
+        assert_eq!(worker.deployment.replicas, None);
+        assert_eq!(worker.service.kind, ServiceType::ClusterIP);
+        assert_eq!(cnf.get_sensitive_patterns().len(), 5);
+    }
+
+    #[test]
+    fn expect_to_allow_privileged_services() {
+        let cnf = config::load("./example", &[]).unwrap();
+
+        assert!(cnf.is_privileged_allowed("agent"));
+        assert!(!cnf.is_privileged_allowed("web"));
+    }
 }
@@ -4,29 +4,407 @@ mod loader {
 
     #[test]
     fn expect_to_load_dk() {
-        match loader::load("./example", "docker-compose.yaml") {
+        match loader::load("./example", "docker-compose.yaml", None) {
             Ok(res) => assert!(res.len() > 0),
             Err(err) => panic!(err)
         }
     }
 
+    #[test]
+    fn expect_to_load_dk_with_env_file() {
+        match loader::load("./example", "docker-compose.yaml", Some("./example/.env")) {
+            Ok(res) => assert_eq!(res[0]["services"]["front"]["environment"]["NODE_ENV"].as_str().unwrap(), "production"),
+            Err(err) => panic!("{}", err)
+        }
+    }
+
+    #[test]
+    fn expect_to_not_load_missing_env_file() {
+        match loader::load("./example", "docker-compose.yaml", Some("./example/unknown.env")) {
+            Ok(_) => panic!("Expect the env file to be missing"),
+            Err(err) => assert_eq!(&err.message.to_string(), "Unable to read the env file use by the interpolation")
+        };
+    }
+
     #[test]
     fn expect_to_not_load_dk() {
-        match loader::load("./lol", "unexisted-docker-compose.yaml") {
+        match loader::load("./lol", "unexisted-docker-compose.yaml", None) {
             Ok(_) => panic!("Expect to not have found any file"),
             Err(err) => assert_eq!(&err.message.to_string(), "Unable to find the docker-compose.yaml file")
         };
     }
 }
 
+#[cfg(test)]
+mod merge {
+    use std::fs;
+    use std::env;
+    use yaml_rust::YamlLoader;
+    use crate::docker::{loader, merge};
+
+    #[test]
+    fn expect_to_merge_compose_files() {
+        let files = vec![String::from("docker-compose.yaml"), String::from("docker-compose.prod.yaml")];
+        let yaml = match loader::load_files("./example", &files, None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let web = &yaml[0]["services"]["web"];
+        assert_eq!(web["image"].as_str().unwrap(), "golang:1.12.6-alpine");
+        assert_eq!(web["environment"]["GO111MODULE"].as_str().unwrap(), "off");
+        assert_eq!(web["environment"]["GIN_MODE"].as_str().unwrap(), "release");
+        assert_eq!(web["labels"]["app"].as_str().unwrap(), "go");
+        assert_eq!(web["labels"]["tier"].as_str().unwrap(), "backend");
+        assert_eq!(web["command"].as_vec().unwrap().len(), 2);
+
+        let mysql = &yaml[0]["services"]["mysql"];
+        assert_eq!(mysql["ports"].as_vec().unwrap().len(), 2);
+        let volumes = mysql["volumes"].as_vec().unwrap();
+        assert_eq!(volumes.len(), 3);
+        assert_eq!(volumes[1].as_str().unwrap(), "db-backup-prod:/backup");
+        assert!(!yaml[0]["volumes"]["db-backup-prod"].is_badvalue());
+    }
+
+    #[test]
+    fn expect_to_merge_references_by_source() {
+        let base = YamlLoader::load_from_str("secrets: [db_password, {source: tls_cert, target: a.crt}]").unwrap();
+        let over = YamlLoader::load_from_str("secrets: [{source: tls_cert, target: b.crt}, api_key]").unwrap();
+        let merged = merge::merge_yaml(base[0].clone(), over[0].clone());
+
+        let secrets = merged["secrets"].as_vec().unwrap();
+        assert_eq!(secrets.len(), 3);
+        assert_eq!(secrets[1]["target"].as_str().unwrap(), "b.crt");
+        assert_eq!(secrets[2].as_str().unwrap(), "api_key");
+    }
+
+    #[test]
+    fn expect_to_detect_compose_files() {
+        match loader::get_compose_files("./example") {
+            Ok(files) => assert_eq!(files, vec![String::from("docker-compose.yaml")]),
+            Err(err) => panic!("{}", err)
+        };
+
+        let folder = env::temp_dir().join("capoomobi-compose-detection");
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join("compose.yml"), "services: {}").unwrap();
+        fs::write(folder.join("compose.override.yaml"), "services: {}").unwrap();
+
+        let files = loader::get_compose_files(folder.to_str().unwrap());
+        fs::remove_dir_all(&folder).unwrap();
+        match files {
+            Ok(f) => assert_eq!(f, vec![String::from("compose.yml"), String::from("compose.override.yaml")]),
+            Err(err) => panic!("{}", err)
+        };
+    }
+
+    #[test]
+    fn expect_to_not_detect_compose_file() {
+        match loader::get_compose_files("./src") {
+            Ok(_) => panic!("Expect to not have found any compose file"),
+            Err(err) => assert_eq!(&err.message.to_string(), "Unable to find a compose.yaml, compose.yml, docker-compose.yaml or docker-compose.yml file in the folder")
+        };
+    }
+}
+
+#[cfg(test)]
+mod extends {
+    use std::{env, fs};
+    use std::collections::BTreeMap;
+    use yaml_rust::YamlLoader;
+    use crate::docker::{loader, extends, parser};
+
+    #[test]
+    fn expect_to_resolve_extends_and_merge_keys() {
+        let yaml = match loader::load("./example", "docker-compose-extends.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let api = &yaml[0]["services"]["api"];
+        assert_eq!(api["image"].as_str().unwrap(), "node:14");
+        assert_eq!(api["restart"].as_str().unwrap(), "on-failure");
+        assert_eq!(api["ports"].as_vec().unwrap().len(), 1);
+        assert_eq!(api["environment"]["NODE_ENV"].as_str().unwrap(), "production");
+        assert_eq!(api["environment"]["LOG_LEVEL"].as_str().unwrap(), "debug");
+        assert!(api["extends"].is_badvalue());
+        assert!(api["<<"].is_badvalue());
+
+        let worker = &yaml[0]["services"]["worker"];
+        assert_eq!(worker["image"].as_str().unwrap(), "node:14");
+        assert_eq!(worker["labels"][0].as_str().unwrap(), "team=capoo");
+        assert_eq!(worker["command"].as_vec().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn expect_to_parse_extension() {
+        let yaml = match loader::load("./example", "docker-compose-extends.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let services = parser::get_docker_services(yaml).unwrap();
+        let extension = services[0].extension.as_ref().unwrap();
+        assert_eq!(extension["deployment"]["replicas"].as_u64().unwrap(), 2);
+        assert_eq!(extension["service"]["kind"].as_str().unwrap(), "ClusterIP");
+        assert!(services[1].extension.is_some());
+    }
+
+    #[test]
+    fn expect_to_rebase_paths_of_other_folder() {
+        let folder = env::temp_dir().join("capoomobi-extends");
+        fs::create_dir_all(folder.join("common")).unwrap();
+        fs::write(folder.join("compose.yaml"), "services: {web: {extends: {file: common/services.yaml, service: base}, volumes: [./web:/web]}}").unwrap();
+        fs::write(
+            folder.join("common").join("services.yaml"),
+            "services: {base: {image: node, build: {context: .}, env_file: [web.env, {path: ./secret.env, required: false}], \
+            volumes: [./conf:/etc/conf, data:/data, {type: bind, source: ./logs, target: /logs}, /var/run/docker.sock:/var/run/docker.sock]}}"
+        ).unwrap();
+
+        let yaml = loader::load(folder.to_str().unwrap(), "compose.yaml", None);
+        fs::remove_dir_all(&folder).unwrap();
+
+        let web = match yaml {
+            Ok(res) => res[0]["services"]["web"].clone(),
+            Err(err) => panic!("{}", err)
+        };
+
+        assert_eq!(web["build"]["context"].as_str().unwrap(), "./common");
+        assert_eq!(web["env_file"][0].as_str().unwrap(), "./common/web.env");
+        assert_eq!(web["env_file"][1]["path"].as_str().unwrap(), "./common/secret.env");
+
+        let volumes = web["volumes"].as_vec().unwrap();
+        assert_eq!(volumes[0].as_str().unwrap(), "./common/conf:/etc/conf");
+        assert_eq!(volumes[1].as_str().unwrap(), "data:/data");
+        assert_eq!(volumes[2]["source"].as_str().unwrap(), "./common/logs");
+        assert_eq!(volumes[3].as_str().unwrap(), "/var/run/docker.sock:/var/run/docker.sock");
+        // the paths of the extending service are kept
+        assert_eq!(volumes[4].as_str().unwrap(), "./web:/web");
+    }
+
+    #[test]
+    fn expect_cycle_to_fail() {
+        let content = YamlLoader::load_from_str("services: {a: {extends: b}, b: {extends: a}}").unwrap();
+        match extends::resolve_extends(content[0].clone(), "./example", "docker-compose.yaml", &BTreeMap::new()) {
+            Ok(_) => panic!("Expect the cycle to be detected"),
+            Err(err) => assert_eq!(&err.message.to_string(), "A cycle has been detected in the extends of the service")
+        };
+    }
+
+    #[test]
+    fn expect_missing_service_to_fail() {
+        let content = YamlLoader::load_from_str("services: {a: {extends: unknown}}").unwrap();
+        match extends::resolve_extends(content[0].clone(), "./example", "docker-compose.yaml", &BTreeMap::new()) {
+            Ok(_) => panic!("Expect the extended service to be missing"),
+            Err(err) => assert_eq!(&err.message.to_string(), "Unable to find the service extended by")
+        };
+    }
+}
+
+#[cfg(test)]
+mod selection {
+    use crate::docker::{loader, parser, selection};
+    use crate::docker::parser::DockerService;
+
+    fn get_services() -> Vec<DockerService> {
+        let yaml = match loader::load("./example", "docker-compose-profiles.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        parser::get_docker_services(yaml).unwrap()
+    }
+
+    fn get_names(services: &[DockerService]) -> Vec<&str> {
+        services.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn expect_to_parse_profiles() {
+        let services = get_services();
+        assert!(services[0].profiles.is_empty());
+        assert_eq!(services[3].profiles, vec!["debug".to_owned(), "data".to_owned()]);
+    }
+
+    #[test]
+    fn expect_to_select_services_without_profile() {
+        let services = match selection::select_services(get_services(), &[], &[]) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        assert_eq!(get_names(&services), vec!["web", "api"]);
+    }
+
+    #[test]
+    fn expect_to_select_services_of_profiles() {
+        let services = selection::select_services(get_services(), &["debug".to_owned()], &[]).unwrap();
+        assert_eq!(get_names(&services), vec!["web", "api", "adminer", "db"]);
+
+        let services = selection::select_services(get_services(), &["*".to_owned()], &[]).unwrap();
+        assert_eq!(services.len(), 5);
+    }
+
+    #[test]
+    fn expect_to_select_listed_services_with_dependencies() {
+        let services = selection::select_services(get_services(), &[], &["web".to_owned(), "adminer".to_owned()]).unwrap();
+        assert_eq!(get_names(&services), vec!["web", "api", "adminer", "db"]);
+    }
+
+    #[test]
+    fn expect_unknown_service_to_fail() {
+        match selection::select_services(get_services(), &[], &["unknown".to_owned()]) {
+            Ok(_) => panic!("Expect the service to be unknown"),
+            Err(err) => assert_eq!(&err.message.to_string(), "Unable to find the selected service in the docker-compose.yaml")
+        };
+    }
+
+    #[test]
+    fn expect_to_select_used_files() {
+        let yaml = loader::load("./example", "docker-compose-profiles.yaml", None).unwrap();
+        let files = parser::get_docker_files(&yaml);
+        let services = selection::select_services(get_services(), &[], &[]).unwrap();
+
+        let selected = selection::select_files(files, &services);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].name, "api_key");
+    }
+}
+
+#[cfg(test)]
+mod interpolation {
+    use std::collections::BTreeMap;
+    use yaml_rust::{Yaml, YamlLoader};
+    use crate::docker::interpolation::{interpolate, interpolate_value, resolve_environment};
+
+    // method use to get the variables available for the interpolation
+    fn setup() -> BTreeMap<String, String> {
+        let mut vars = BTreeMap::new();
+        vars.insert("IMAGE_TAG".to_owned(), "1.2.0".to_owned());
+        vars.insert("EMPTY".to_owned(), String::new());
+        vars
+    }
+
+    #[test]
+    fn expect_to_substitute_variables() {
+        let vars = setup();
+        let content = "image: nginx:${IMAGE_TAG}\ntag: $IMAGE_TAG-alpine";
+
+        assert_eq!(interpolate_value(content, &vars).unwrap(), "image: nginx:1.2.0\ntag: 1.2.0-alpine");
+        assert_eq!(interpolate_value("port: ${UNKNOWN}", &vars).unwrap(), "port: ");
+    }
+
+    #[test]
+    fn expect_to_use_defaults() {
+        let vars = setup();
+
+        assert_eq!(interpolate_value("${PORT:-8080}", &vars).unwrap(), "8080");
+        assert_eq!(interpolate_value("${EMPTY:-8080}", &vars).unwrap(), "8080");
+        assert_eq!(interpolate_value("${EMPTY-8080}", &vars).unwrap(), "");
+        assert_eq!(interpolate_value("${IMAGE_TAG:-latest}", &vars).unwrap(), "1.2.0");
+        assert_eq!(interpolate_value("${PORT:-${IMAGE_TAG}}", &vars).unwrap(), "1.2.0");
+        assert_eq!(interpolate_value("${IMAGE_TAG:+set}", &vars).unwrap(), "set");
+        assert_eq!(interpolate_value("${EMPTY:+set}", &vars).unwrap(), "");
+        assert_eq!(interpolate_value("${EMPTY+set}", &vars).unwrap(), "set");
+    }
+
+    #[test]
+    fn expect_to_escape_dollar() {
+        let vars = setup();
+
+        assert_eq!(interpolate_value("cmd: echo $$HOME $${IMAGE_TAG}", &vars).unwrap(), "cmd: echo $HOME ${IMAGE_TAG}");
+        assert_eq!(interpolate_value("price: 5$", &vars).unwrap(), "price: 5$");
+    }
+
+    #[test]
+    fn expect_values_to_not_alter_document() {
+        let mut vars = setup();
+        vars.insert("TITLE".to_owned(), "a: b # [c] {d}".to_owned());
+        vars.insert("QUOTE".to_owned(), "\"*ref".to_owned());
+        vars.insert("REPLICAS".to_owned(), "3".to_owned());
+        vars.insert("MODE".to_owned(), "0o440".to_owned());
+
+        let content = "title: ${TITLE}\nquote: \"${QUOTE}\"\nreplicas: ${REPLICAS}\nmode: ${MODE}\n# ${REQUIRED:?unset}\nimage: nginx # ${REQUIRED:?unset}";
+        let doc = YamlLoader::load_from_str(content).unwrap().remove(0);
+        let yaml = interpolate(doc, &vars).unwrap();
+
+        assert_eq!(yaml["title"].as_str().unwrap(), "a: b # [c] {d}");
+        assert_eq!(yaml["quote"].as_str().unwrap(), "\"*ref");
+        // numbers keep their type unless they're written differently
+        assert_eq!(yaml["replicas"], Yaml::Integer(3));
+        assert_eq!(yaml["mode"].as_str().unwrap(), "0o440");
+        // comments are never interpolated
+        assert_eq!(yaml["image"].as_str().unwrap(), "nginx");
+    }
+
+    #[test]
+    fn expect_to_resolve_environment_without_value() {
+        let mut vars = setup();
+        vars.insert("API_TOKEN".to_owned(), "abc".to_owned());
+
+        let content = "services:\n  web:\n    environment:\n      - API_TOKEN\n      - UNSET\n      - MODE=prod\n  db:\n    environment:\n      API_TOKEN:\n      UNSET:\n      USER: mouse";
+        let doc = YamlLoader::load_from_str(content).unwrap().remove(0);
+        let yaml = resolve_environment(doc, &vars);
+
+        let web: Vec<&str> = yaml["services"]["web"]["environment"]
+            .as_vec()
+            .unwrap()
+            .iter()
+            .filter_map(|v| v.as_str())
+            .collect();
+        assert_eq!(web, vec!["API_TOKEN=abc", "MODE=prod"]);
+
+        let db = &yaml["services"]["db"]["environment"];
+        assert_eq!(db["API_TOKEN"].as_str().unwrap(), "abc");
+        assert!(db["UNSET"].is_badvalue());
+        assert_eq!(db["USER"].as_str().unwrap(), "mouse");
+    }
+
+    #[test]
+    fn expect_required_variable_to_fail() {
+        let vars = setup();
+
+        match interpolate_value("image: ${REQUIRED:?the image is required}", &vars) {
+            Ok(_) => panic!("Expect the variable to be required"),
+            Err(err) => {
+                assert_eq!(&err.message.to_string(), "A required variable of the docker-compose.yaml is not set");
+                assert_eq!(err.reason, "REQUIRED: the image is required");
+            }
+        }
+
+        assert!(interpolate_value("${EMPTY:?empty}", &vars).is_err());
+        assert!(interpolate_value("${EMPTY?empty}", &vars).is_ok());
+    }
+
+    #[test]
+    fn expect_invalid_format_to_fail() {
+        let vars = setup();
+
+        assert!(interpolate_value("${IMAGE_TAG", &vars).is_err());
+        assert!(interpolate_value("${IMAGE TAG}", &vars).is_err());
+        assert!(interpolate_value("${}", &vars).is_err());
+    }
+}
+
 #[cfg(test)]
 mod parser {
     use crate::docker::parser;
+    use crate::docker::port::{Protocol, parse_ports};
+    use crate::docker::volume::{VolumeKind, parse_tmpfs, parse_named_volumes, resolve_binds};
+    use crate::docker::deploy::{DeployMode, RestartCondition, UpdateOrder, ConstraintOperator};
+    use crate::docker::healthcheck::{HealthcheckTest, parse_healthcheck};
+    use crate::docker::dependency::{DependencyCondition, parse_dependencies};
+    use crate::docker::environment::{parse_env_content, load_env_files};
+    use crate::docker::secret::{FileKind, parse_file_references, load_file_objects};
+    use crate::docker::command::split_shell_words;
+    use crate::docker::host::parse_host;
+    use yaml_rust::YamlLoader;
+    use crate::tests::{to_map, to_pairs};
     use crate::docker::loader;
 
     #[test]
     fn expect_to_parse_dk() {
-        let yaml = match loader::load("./example", "docker-compose.yaml") {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
             Ok(res) => res,
             Err(err) => panic!(err)
         };
@@ -37,6 +415,88 @@ mod parser {
         };
     }
 
+    #[test]
+    fn expect_to_parse_command_fields() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let contents = parser::get_docker_services(yaml).unwrap();
+        let web = &contents[0];
+        assert!(web.entrypoint.is_empty());
+        assert_eq!(web.working_dir, Some("/go/src/app".to_owned()));
+        assert_eq!(web.user, Some("1000:1000".to_owned()));
+        assert!(web.tty);
+        assert!(web.stdin_open);
+
+        let bobba = contents.iter().find(|s| s.name == "bobba").unwrap();
+        assert_eq!(bobba.entrypoint, vec!["/docker-entrypoint.sh"]);
+        assert_eq!(bobba.command, vec!["--port", "8088", "--name", "bobba order"]);
+        assert!(bobba.working_dir.is_none());
+        assert!(!bobba.tty);
+    }
+
+    #[test]
+    fn expect_to_parse_security() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let contents = parser::get_docker_services(yaml).unwrap();
+        let web = &contents[0].security;
+        assert!(web.read_only);
+        assert!(!web.privileged);
+        assert_eq!(web.cap_drop, vec!["ALL"]);
+        assert_eq!(web.cap_add, vec!["NET_BIND_SERVICE"]);
+        assert_eq!(web.security_opt, vec!["no-new-privileges:true", "seccomp:unconfined"]);
+        assert!(!web.is_privileged());
+
+        let agent = &contents.iter().find(|s| s.name == "agent").unwrap().security;
+        assert!(agent.host_network);
+        assert!(agent.host_pid);
+        assert!(!agent.host_ipc);
+        assert!(agent.is_privileged());
+    }
+
+    #[test]
+    fn expect_to_parse_host() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let contents = parser::get_docker_services(yaml).unwrap();
+        let web = &contents[0].host;
+        assert_eq!(web.hostname, Some("api".to_owned()));
+        assert_eq!(web.domainname, Some("backend.local".to_owned()));
+        assert_eq!(web.extra_hosts.len(), 3);
+        assert_eq!(web.extra_hosts[0].hostname, "legacy-db");
+        assert_eq!(web.extra_hosts[1].hostname, "legacy-cache");
+        assert_eq!(web.extra_hosts[1].ip, "10.0.0.12");
+        assert_eq!(web.dns_search, vec!["internal.example.com"]);
+        assert_eq!(web.dns_opt, vec!["ndots:2", "use-vc"]);
+
+        let bobba = &contents.iter().find(|s| s.name == "bobba").unwrap().host;
+        assert_eq!(bobba.dns, vec!["1.1.1.1", "8.8.8.8"]);
+        assert!(bobba.hostname.is_none());
+
+        let yaml = YamlLoader::load_from_str("extra_hosts:\n  gateway: 10.0.0.1\n  ipv6: \"::1\"\ndns: 8.8.8.8").unwrap();
+        let host = parse_host(&yaml[0]);
+        assert_eq!(host.extra_hosts[1].hostname, "ipv6");
+        assert_eq!(host.extra_hosts[1].ip, "::1");
+        assert_eq!(host.dns, vec!["8.8.8.8"]);
+    }
+
+    #[test]
+    fn expect_to_split_shell_words() {
+        assert_eq!(split_shell_words("sh -c 'echo $HOME'"), vec!["sh", "-c", "echo $HOME"]);
+        assert_eq!(split_shell_words(r#"echo "a \"quoted\" word"  b"#), vec!["echo", "a \"quoted\" word", "b"]);
+        assert_eq!(split_shell_words(r#"run a\ b "" c"#), vec!["run", "a b", "", "c"]);
+        assert!(split_shell_words("   ").is_empty());
+    }
+
     #[test]
     fn expect_to_not_retrieve_svc() {
         match parser::get_docker_services(vec![]) {
@@ -47,7 +507,7 @@ mod parser {
 
     #[test]
     fn expect_to_retrieve_listed_services() {
-        let yaml = match loader::load("./example", "docker-compose.yaml") {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
             Ok(res) => res,
             Err(err) => panic!(err)
         };
@@ -64,7 +524,7 @@ mod parser {
 
     #[test]
     fn expect_to_map_values() {
-        let yaml = match loader::load("./example", "docker-compose.yaml") {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
             Ok(res) => res,
             Err(err) => panic!(err)
         };
@@ -76,17 +536,163 @@ mod parser {
 
         assert_eq!(contents[0].image, "golang:1.12.6-stretch");
         assert_eq!(contents[0].name, "web");
-        assert_eq!(contents[0].commands, vec!["sh start.sh"]);
-        assert_eq!(contents[0].labels, vec!["app=go", "tier=api"]);
-        assert_eq!(contents[0].environment, vec!["GO111MODULE=on"]);
-        assert_eq!(contents[0].ports, vec!["5000:5000"]);
+        assert_eq!(contents[0].command, vec!["sh", "start.sh"]);
+        assert_eq!(contents[0].labels, to_map(&[("app", "go"), ("tier", "api")]));
+        assert_eq!(contents[0].environment, to_pairs(&[("GO111MODULE", "on")]));
+        assert_eq!(contents[0].ports.len(), 1);
+        assert_eq!(contents[0].ports[0].target, 5000);
+        assert_eq!(contents[0].ports[0].published, Some(5000));
     }
 
     #[test]
-    fn expect_to_not_err_during_missing_fields() {
-        let empty_vec_string: Vec<String> = Vec::new();
+    fn expect_to_map_dictionnary_values() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let contents = match parser::get_docker_services(yaml) {
+            Some(res) => res,
+            None => panic!("No services has been founded")
+        };
+
+        assert_eq!(contents[2].name, "front");
+        assert_eq!(contents[2].labels, to_map(&[("app", "front")]));
+        assert_eq!(contents[2].environment, to_pairs(&[("NODE_ENV", "production"), ("PORT", "8080")]));
+    }
 
-        let yaml = match loader::load("./example", "docker-compose-missing-field.yaml") {
+    #[test]
+    fn expect_to_parse_ports_syntaxes() {
+        let yaml = match loader::load("./example", "docker-compose-ports.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let contents = parser::get_docker_services(yaml).unwrap();
+        let ports = &contents[0].ports;
+
+        assert_eq!(ports.len(), 8);
+        // ip:host:container/protocol
+        assert_eq!(ports[0].host_ip, Some("127.0.0.1".to_owned()));
+        assert_eq!(ports[0].published, Some(5353));
+        assert_eq!(ports[0].target, 53);
+        assert_eq!(ports[0].protocol, Protocol::Udp);
+        assert_eq!(ports[0].get_name(), "udp-53");
+        // range
+        assert_eq!(ports[1].published, Some(8000));
+        assert_eq!(ports[1].target, 9000);
+        assert_eq!(ports[3].published, Some(8002));
+        assert_eq!(ports[3].target, 9002);
+        // bare container port
+        assert_eq!(ports[4].published, None);
+        assert_eq!(ports[4].get_published(), 9153);
+        // long syntax
+        assert_eq!(ports[5].target, 8080);
+        assert_eq!(ports[5].published, Some(80));
+        assert_eq!(ports[5].protocol, Protocol::Tcp);
+        assert_eq!(ports[5].mode, Some("host".to_owned()));
+        assert_eq!(ports[6].get_name(), "metrics");
+        // a name longer than 15 characters is replaced by the generated one
+        assert_eq!(ports[7].name, None);
+        assert_eq!(ports[7].get_name(), "tcp-9091");
+    }
+
+    #[test]
+    fn expect_to_replace_invalid_port_names() {
+        let content = YamlLoader::load_from_str(
+            "[{target: 80, name: web-1}, {target: 81, name: -web}, {target: 82, name: web--a}, {target: 83, name: '8080'}, {target: 84, name: web_a}]"
+        ).unwrap();
+        let ports = parse_ports(&content[0]);
+
+        assert_eq!(ports[0].get_name(), "web-1");
+        assert_eq!(ports[1].get_name(), "tcp-81");
+        assert_eq!(ports[2].get_name(), "tcp-82");
+        assert_eq!(ports[3].get_name(), "tcp-83");
+        assert_eq!(ports[4].get_name(), "tcp-84");
+    }
+
+    #[test]
+    fn expect_to_parse_volumes() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let named = parser::get_docker_volumes(&yaml);
+        let contents = parser::get_docker_services(yaml).unwrap();
+        let volumes = &contents[1].volumes;
+
+        assert_eq!(volumes.len(), 3);
+        assert_eq!(volumes[0].kind, VolumeKind::Volume);
+        assert_eq!(volumes[0].source, "db-data");
+        assert_eq!(volumes[0].target, "/var/lib/mysql");
+        assert!(!volumes[0].read_only);
+        assert!(!volumes[0].external);
+        assert_eq!(volumes[1].source, "db-backup");
+        assert!(volumes[1].read_only);
+        assert!(volumes[1].external);
+        assert_eq!(volumes[2].kind, VolumeKind::Bind);
+        assert!(volumes[2].read_only);
+
+        assert_eq!(named.len(), 2);
+        assert_eq!(named[0].name, "db-data");
+        assert!(!named[0].external);
+        assert!(named[1].external);
+        assert!(named[1].external_name.is_none());
+        assert!(volumes[1].external_name.is_none());
+    }
+
+    #[test]
+    fn expect_to_parse_external_volume_names() {
+        let yaml = YamlLoader::load_from_str(
+            "{data: {external: true, name: data-claim}, legacy: {external: {name: legacy-claim}}, local: {name: local-data}}"
+        ).unwrap();
+        let named = parse_named_volumes(&yaml[0]);
+
+        assert_eq!(named[0].external_name, Some("data-claim".to_owned()));
+        assert_eq!(named[1].external_name, Some("legacy-claim".to_owned()));
+        assert!(!named[2].external);
+        assert!(named[2].external_name.is_none());
+    }
+
+    #[test]
+    fn expect_to_parse_tmpfs() {
+        let yaml = YamlLoader::load_from_str("- /run\n- /tmp:size=64m,mode=1777").unwrap();
+        let tmpfs = parse_tmpfs(&yaml[0]);
+
+        assert_eq!(tmpfs.len(), 2);
+        assert_eq!(tmpfs[0].kind, VolumeKind::Tmpfs);
+        assert_eq!(tmpfs[0].target, "/run");
+        assert_eq!(tmpfs[0].size, None);
+        assert_eq!(tmpfs[1].target, "/tmp");
+        assert_eq!(tmpfs[1].size, Some("64m".to_owned()));
+    }
+
+    #[test]
+    fn expect_to_resolve_binds() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let mut services = parser::get_docker_services(yaml).unwrap();
+        resolve_binds(&mut services, "./example");
+
+        let conf = &services[1].volumes[2];
+        assert!(conf.source.ends_with("example/conf"));
+        let content = conf.content.as_ref().unwrap();
+        assert!(!content.is_file);
+        assert_eq!(content.files[0].name, "charset.cnf");
+
+        // sources outside of the project are not loaded
+        let proc = services.iter().find(|s| s.name == "agent").unwrap();
+        assert_eq!(proc.volumes[0].source, "/proc");
+        assert!(proc.volumes[0].content.is_none());
+    }
+
+    #[test]
+    fn expect_to_not_err_during_missing_fields() {
+        let yaml = match loader::load("./example", "docker-compose-missing-field.yaml", None) {
             Ok(res) => res,
             Err(err) => panic!(err)
         };
@@ -98,15 +704,360 @@ mod parser {
 
         assert_eq!(contents[0].image, "golang:1.12.6-stretch");
         assert_eq!(contents[0].name, "web");
-        assert_eq!(contents[0].commands, vec![""]);
-        assert_eq!(contents[0].labels, vec![""]);
-        assert_eq!(contents[0].environment, empty_vec_string);
-        assert_eq!(contents[0].ports, empty_vec_string);
+        assert!(contents[0].command.is_empty());
+        assert!(contents[0].labels.is_empty());
+        assert!(contents[0].environment.is_empty());
+        assert!(contents[0].ports.is_empty());
+    }
+
+    #[test]
+    fn expect_to_parse_deploy_mode() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let contents = parser::get_docker_services(yaml).unwrap();
+        assert_eq!(contents[0].deploy.mode, DeployMode::Replicated);
+        assert_eq!(contents[4].name, "agent");
+        assert_eq!(contents[4].deploy.mode, DeployMode::Global);
+        assert_eq!(contents[4].deploy.restart, RestartCondition::Always);
+        assert_eq!(contents[5].deploy.restart, RestartCondition::No);
+    }
+
+    #[test]
+    fn expect_to_parse_deploy_replicas_and_labels() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let contents = parser::get_docker_services(yaml).unwrap();
+        let front = contents.iter().find(|s| s.name == "front").unwrap();
+        let bobba = contents.iter().find(|s| s.name == "bobba").unwrap();
+
+        assert_eq!(contents[0].deploy.replicas, None);
+        assert_eq!(contents[1].deploy.replicas, Some(5));
+        // legacy scale field
+        assert_eq!(front.deploy.replicas, Some(2));
+        assert_eq!(bobba.deploy.labels, to_map(&[("com.example.team", "orders")]));
+        assert!(!bobba.labels.contains_key("com.example.team"));
+    }
+
+    #[test]
+    fn expect_to_parse_update_config() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let contents = parser::get_docker_services(yaml).unwrap();
+        let bobba = &contents.iter().find(|s| s.name == "bobba").unwrap().deploy;
+        let update = bobba.update_config.as_ref().unwrap();
+
+        assert_eq!(update.parallelism, Some(2));
+        assert_eq!(update.delay, Some(10));
+        assert_eq!(update.order, UpdateOrder::StartFirst);
+        assert_eq!(update.failure_action, Some("rollback".to_owned()));
+        assert!(bobba.rollback_config.is_none());
+        assert_eq!(bobba.stop_grace_period, Some(90));
+        assert_eq!(bobba.stop_signal, Some("SIGQUIT".to_owned()));
+        assert!(contents[0].deploy.update_config.is_none());
+    }
+
+    #[test]
+    fn expect_to_parse_placement() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let contents = parser::get_docker_services(yaml).unwrap();
+        let placement = &contents[1].deploy.placement;
+
+        assert_eq!(placement.constraints.len(), 3);
+        assert_eq!(placement.constraints[0].field, "node.labels.disk");
+        assert_eq!(placement.constraints[0].operator, ConstraintOperator::Equal);
+        assert_eq!(placement.constraints[0].value, "ssd");
+        assert_eq!(placement.constraints[1].operator, ConstraintOperator::NotEqual);
+        assert_eq!(placement.preferences, vec!["node.labels.zone"]);
+        assert_eq!(placement.max_replicas_per_node, Some(1));
+        assert!(contents[0].deploy.placement.constraints.is_empty());
+    }
+
+    #[test]
+    fn expect_to_parse_healthcheck() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let contents = parser::get_docker_services(yaml).unwrap();
+        let web = contents[0].healthcheck.as_ref().unwrap();
+        let mysql = contents[1].healthcheck.as_ref().unwrap();
+
+        assert_eq!(web.test, HealthcheckTest::Cmd(vec![
+            "curl".to_owned(),
+            "-f".to_owned(),
+            "http://localhost:5000/health".to_owned()
+        ]));
+        assert_eq!(web.interval, Some(30));
+        assert_eq!(web.timeout, Some(10));
+        assert_eq!(web.retries, Some(3));
+        assert_eq!(web.start_period, Some(60));
+        assert_eq!(mysql.test, HealthcheckTest::Shell("mysqladmin ping -h localhost || exit 1".to_owned()));
+        assert!(contents[2].healthcheck.is_none());
+    }
+
+    #[test]
+    fn expect_disabled_healthcheck_to_be_skipped() {
+        let disabled = "disable: true\ntest: [\"CMD\", \"true\"]";
+        let none = "test: [\"NONE\"]";
+        let shell = "test: [\"CMD-SHELL\", \"pg_isready\"]";
+
+        assert!(parse_healthcheck(&YamlLoader::load_from_str(disabled).unwrap()[0]).is_none());
+        assert!(parse_healthcheck(&YamlLoader::load_from_str(none).unwrap()[0]).is_none());
+
+        let hc = parse_healthcheck(&YamlLoader::load_from_str(shell).unwrap()[0]).unwrap();
+        assert_eq!(hc.test, HealthcheckTest::Shell("pg_isready".to_owned()));
+    }
+
+    #[test]
+    fn expect_to_parse_resources() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let contents = parser::get_docker_services(yaml).unwrap();
+        let mysql = &contents[1].deploy.resources;
+        let bobba = &contents[3].deploy.resources;
+
+        assert_eq!(mysql.limits.cpus, Some("0.5".to_owned()));
+        assert_eq!(mysql.limits.memory, Some("512M".to_owned()));
+        assert_eq!(mysql.reservations.cpus, Some("0.25".to_owned()));
+        assert_eq!(mysql.reservations.memory, Some("256M".to_owned()));
+        // legacy fields
+        assert_eq!(bobba.limits.cpus, Some("2".to_owned()));
+        assert_eq!(bobba.limits.memory, Some("1g".to_owned()));
+        assert!(bobba.reservations.cpus.is_none());
+    }
+
+    #[test]
+    fn expect_to_parse_dependencies() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let contents = parser::get_docker_services(yaml).unwrap();
+        // long syntax
+        let web = &contents[0].depends_on;
+        assert_eq!(web.len(), 1);
+        assert_eq!(web[0].name, "mysql");
+        assert_eq!(web[0].condition, DependencyCondition::Healthy);
+
+        // short syntax
+        let migrate = &contents[5].depends_on;
+        assert_eq!(migrate[0].name, "mysql");
+        assert_eq!(migrate[0].condition, DependencyCondition::Started);
+        assert!(contents[1].depends_on.is_empty());
+    }
+
+    #[test]
+    fn expect_to_parse_dependency_conditions() {
+        let content = "
+        migrate:
+          condition: service_completed_successfully
+        cache:
+          condition: service_started
+        ";
+
+        let yaml = YamlLoader::load_from_str(content).unwrap();
+        let deps = parse_dependencies(&yaml[0]);
+
+        assert_eq!(deps.len(), 2);
+        assert_eq!(deps[0].name, "migrate");
+        assert_eq!(deps[0].condition, DependencyCondition::CompletedSuccessfully);
+        assert_eq!(deps[1].condition, DependencyCondition::Started);
+    }
+
+    #[test]
+    fn expect_to_parse_env_files() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let contents = parser::get_docker_services(yaml).unwrap();
+        let files = &contents[0].env_files;
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "./web.env");
+        assert!(files[0].required);
+        assert_eq!(files[1].path, "./web.local.env");
+        assert!(!files[1].required);
+    }
+
+    #[test]
+    fn expect_to_parse_env_content() {
+        let content = "
+        # comment
+        GIN_MODE=release
+        export API_TOKEN=\"changeme\"
+        QUOTED='a=b'
+        EMPTY=
+        ";
+
+        let env = parse_env_content(content);
+        assert_eq!(env, to_pairs(&[
+            ("GIN_MODE", "release"),
+            ("API_TOKEN", "changeme"),
+            ("QUOTED", "a=b"),
+            ("EMPTY", "")
+        ]));
+    }
+
+    #[test]
+    fn expect_to_load_env_files() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let mut contents = parser::get_docker_services(yaml).unwrap();
+        if let Err(err) = load_env_files(&mut contents, "./example") {
+            panic!("{}", err);
+        }
+
+        // the optional file is missing, the variables keep the order of the env file
+        assert_eq!(contents[0].environment, to_pairs(&[
+            ("GIN_MODE", "release"),
+            ("API_TOKEN", "changeme"),
+            ("GO111MODULE", "on")
+        ]));
+    }
+
+    #[test]
+    fn expect_to_not_load_missing_env_file() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let mut contents = parser::get_docker_services(yaml).unwrap();
+        match load_env_files(&mut contents, "./unknown") {
+            Ok(_) => panic!("Expect the required env file to be missing"),
+            Err(err) => assert_eq!(&err.message.to_string(), "Unable to read the env_file of a service")
+        }
+    }
+
+    #[test]
+    fn expect_to_parse_file_references() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let contents = parser::get_docker_services(yaml).unwrap();
+        let mysql = &contents[1];
+
+        assert_eq!(mysql.secrets[0].source, "db_password");
+        assert_eq!(mysql.secrets[0].target, "/run/secrets/db_password");
+        assert!(mysql.secrets[0].external_name.is_none());
+        assert_eq!(mysql.configs[0].source, "mysql_config");
+        assert_eq!(mysql.configs[0].target, "/etc/mysql/conf.d/custom.cnf");
+        assert_eq!(mysql.configs[0].mode, Some(0o440));
+
+        // external secret with a relative target
+        let web = &contents[0];
+        assert_eq!(web.secrets[0].target, "/run/secrets/server.crt");
+        assert_eq!(web.secrets[0].external_name, Some("web-tls".to_owned()));
+    }
+
+    #[test]
+    fn expect_to_parse_file_reference_syntaxes() {
+        let content = "
+        - app_config
+        - source: other
+          uid: '103'
+          gid: '103'
+          mode: '0o400'
+        - source: legacy
+          mode: 0440
+        - source: octal
+          mode: 0o440
+        - source: readonly
+          mode: 0o400
+        - source: decimal
+          mode: 288
+        - { source: flow, mode: 0400 }
+        ";
+
+        let yaml = loader::parse_yaml_builder(content.to_owned()).unwrap();
+        let refs = parse_file_references(&yaml[0], FileKind::Config);
+
+        assert_eq!(refs[0].target, "/app_config");
+        assert!(refs[0].mode.is_none());
+        assert_eq!(refs[1].target, "/other");
+        assert_eq!(refs[1].uid, Some("103".to_owned()));
+        assert_eq!(refs[1].mode, Some(0o400));
+        assert_eq!(refs[2].mode, Some(0o440));
+        assert_eq!(refs[3].mode, Some(0o440));
+        assert_eq!(refs[4].mode, Some(0o400));
+        assert_eq!(refs[5].mode, Some(0o440));
+        assert_eq!(refs[6].mode, Some(0o400));
+    }
+
+    #[test]
+    fn expect_to_load_file_objects() {
+        let yaml = match loader::load("./example", "docker-compose.yaml", None) {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err)
+        };
+
+        let mut files = parser::get_docker_files(&yaml);
+        if let Err(err) = load_file_objects(&mut files, "./example") {
+            panic!("{}", err);
+        }
+
+        let secret = files.iter().find(|f| f.name == "db_password").unwrap();
+        let external = files.iter().find(|f| f.name == "tls_cert").unwrap();
+        let config = files.iter().find(|f| f.name == "mysql_config").unwrap();
+
+        assert_eq!(secret.kind, FileKind::Secret);
+        assert_eq!(secret.data, b"big_cat\n".to_vec());
+        assert!(external.external);
+        assert!(external.data.is_empty());
+        assert_eq!(config.kind, FileKind::Config);
+        assert!(config.data.starts_with(b"[mysqld]"));
+    }
+
+    #[test]
+    fn expect_to_get_project_name() {
+        let yaml = YamlLoader::load_from_str("name: My_Shop\nservices: {}").unwrap();
+        assert_eq!(parser::get_project_name(&yaml, "./example"), "my_shop");
+
+        // the folder of the compose files is used without a top-level name
+        let yaml = YamlLoader::load_from_str("services: {}").unwrap();
+        assert_eq!(parser::get_project_name(&yaml, "./example"), "example");
+    }
+
+    #[test]
+    fn expect_to_parse_duration() {
+        assert_eq!(parser::parse_duration("1m30s"), Some(90));
+        assert_eq!(parser::parse_duration("1h"), Some(3600));
+        assert_eq!(parser::parse_duration("1.5s"), Some(2));
+        assert_eq!(parser::parse_duration("500ms"), Some(1));
+        assert_eq!(parser::parse_duration("45"), Some(45));
+        assert_eq!(parser::parse_duration("1m30"), None);
+        assert_eq!(parser::parse_duration("10 days"), None);
+        assert_eq!(parser::parse_duration(""), None);
     }
 
     #[test]
     fn expect_load_to_fail() {
-        match loader::load("./example", "docker-unformatted.yaml") {
+        match loader::load("./example", "docker-unformatted.yaml", None) {
             Ok(_) => panic!("Expect to have return an error"),
             Err(err) => assert_eq!(&err.message.to_string(), "Unable to parse the docker-compose.yaml for reason: ")
         }
@@ -2,7 +2,10 @@
 mod directory {
     use std::collections::HashMap;
     use crate::docker::parser::DockerService;
-    use crate::kubernetes::builder::{get_basic_objects};
+    use crate::docker::host::DockerHost;
+    use crate::docker::dependency::{DockerDependency, DependencyCondition};
+    use crate::tests::{to_map, to_ports, to_labels, to_pairs};
+    use crate::kubernetes::builder::{get_basic_objects, check_selectors};
     use crate::kubernetes::controllers::controller::ControllerKind;
     use crate::kubernetes::controllers::service::ServiceType;
     use crate::confiture::config::{
@@ -17,22 +20,26 @@ mod directory {
         let dk = DockerService {
             name: "nginx".to_string(),
             image: "nginx:1.3.0".to_string(),
-            commands: vec!["sudo nginx reload".to_string()],
-            ports: vec!["9000:9000".to_string()],
-            labels: vec!["back".to_string()],
-            environment: vec!["API_ENV=dev".to_string()],
-            volumes: vec![]
+            command: vec!["sudo nginx reload".to_string()],
+            ports: to_ports(&["9000:9000"]),
+            labels: to_map(&[("tier", "back")]),
+            environment: to_pairs(&[("API_ENV", "dev")]),
+            volumes: vec![],
+            ..Default::default()
         };
 
         let conf = ConfigConfiture {
             name: "nginx".to_string(),
+            skip: false,
             deployment: ConfigDeployment {
-                replicas: 3,
-                controller: ControllerKind::Deployment
+                replicas: Some(3),
+                controller: Some(ControllerKind::Deployment),
+                ..Default::default()
             },
             service: ConfigService {
                 kind: ServiceType::NodePort,
-                nodeport: 30320
+                nodeport: 30320,
+                ports: vec![]
             }
         };
 
@@ -47,25 +54,27 @@ mod directory {
         let mut map: HashMap<String, &ConfigConfiture> = HashMap::new();
         map.insert("nginx".to_string(), &conf);
 
-        let kubes = get_basic_objects(&vec![dk], map);
+        let kubes = get_basic_objects(&vec![dk], map, &[], &[]);
         assert!(!kubes.is_empty());
 
         // testing controller value
         assert_eq!(kubes[0].ctrl.ctrl, ControllerKind::Deployment);
         assert_eq!(kubes[0].ctrl.name, "nginx");
         assert_eq!(kubes[0].ctrl.image, "nginx:1.3.0");
-        assert_eq!(kubes[0].ctrl.commands, vec!["sudo nginx reload".to_string()]);
-        assert_eq!(kubes[0].ctrl.labels, vec!["back".to_string()]);
-        assert_eq!(kubes[0].ctrl.env, vec!["API_ENV=dev".to_string()]);
+        assert_eq!(kubes[0].ctrl.args, vec!["sudo nginx reload".to_string()]);
+        assert_eq!(kubes[0].ctrl.labels, to_labels("nginx", "nginx", &[("tier", "back")]));
+        assert_eq!(kubes[0].ctrl.config_map_ref, Some("nginx-env".to_owned()));
+        assert_eq!(kubes[0].config_map.as_ref().unwrap().data, to_map(&[("API_ENV", "dev")]));
+        assert!(kubes[0].secret.is_none());
         assert_eq!(kubes[0].ctrl.replicas, 3);
 
         // testing service value
         assert_eq!(kubes[0].svc.as_ref().unwrap().name, "nginx-svc");
-        assert_eq!(kubes[0].svc.as_ref().unwrap().host_port, 9000);
-        assert_eq!(kubes[0].svc.as_ref().unwrap().target_port, 9000);
+        assert_eq!(kubes[0].svc.as_ref().unwrap().ports[0].port, 9000);
+        assert_eq!(kubes[0].svc.as_ref().unwrap().ports[0].target_port, 9000);
         assert_eq!(kubes[0].svc.as_ref().unwrap().kind, ServiceType::NodePort);
-        assert_eq!(kubes[0].svc.as_ref().unwrap().labels, vec!["back".to_string()]);
-        assert_eq!(kubes[0].svc.as_ref().unwrap().nodeport, 30320);
+        assert_eq!(kubes[0].svc.as_ref().unwrap().labels, to_labels("nginx", "nginx", &[("tier", "back")]));
+        assert_eq!(kubes[0].svc.as_ref().unwrap().ports[0].node_port, Some(30320));
     }
 
     #[test]
@@ -76,19 +85,124 @@ mod directory {
         let mut map: HashMap<String, &ConfigConfiture> = HashMap::new();
         map.insert("nginx".to_string(), &conf);
 
-        let kubes = get_basic_objects(&vec![dk], map);
+        let kubes = get_basic_objects(&vec![dk], map, &[], &[]);
         // create folder
         match folder::create(&kubes) {
             Ok(_) => {},
             Err(_) => panic!("Expect to create folders")
         }
     }
+
+    #[test]
+    fn expect_to_generate_service_without_confiture() {
+        let (dk, _) = setup();
+        let map: HashMap<String, &ConfigConfiture> = HashMap::new();
+
+        let kubes = get_basic_objects(&[dk], map, &[], &[]);
+        assert_eq!(kubes.len(), 1);
+        assert_eq!(kubes[0].ctrl.ctrl, ControllerKind::Deployment);
+        assert_eq!(kubes[0].ctrl.replicas, 1);
+        assert_eq!(kubes[0].svc.as_ref().unwrap().kind, ServiceType::ClusterIP);
+    }
+
+    #[test]
+    fn expect_to_skip_service() {
+        let (dk, mut conf) = setup();
+        conf.skip = true;
+        let mut map: HashMap<String, &ConfigConfiture> = HashMap::new();
+        map.insert("nginx".to_string(), &conf);
+
+        let kubes = get_basic_objects(&[dk], map, &[], &[]);
+        assert!(kubes.is_empty());
+    }
+
+    #[test]
+    fn expect_to_not_wait_for_skipped_service() {
+        let (dk, mut conf) = setup();
+        conf.skip = true;
+        let api = DockerService {
+            name: "api".to_owned(),
+            depends_on: vec![DockerDependency { name: "nginx".to_owned(), condition: DependencyCondition::Started }],
+            ..dk.clone()
+        };
+
+        let kubes = get_basic_objects(&[api.clone(), dk.clone()], HashMap::new(), &[], &[]);
+        assert_eq!(kubes[0].ctrl.init_containers.len(), 1);
+
+        let mut map: HashMap<String, &ConfigConfiture> = HashMap::new();
+        map.insert("nginx".to_string(), &conf);
+
+        let kubes = get_basic_objects(&[api, dk], map, &[], &[]);
+        assert_eq!(kubes.len(), 1);
+        assert!(kubes[0].ctrl.init_containers.is_empty());
+    }
+
+    #[test]
+    fn expect_to_detect_selector_collision() {
+        let (dk, _) = setup();
+        let worker = DockerService {
+            name: "worker".to_owned(),
+            labels: dk.labels.clone(),
+            ..dk.clone()
+        };
+
+        // services sharing their compose labels don't select each other
+        let kubes = get_basic_objects(&[dk.clone(), worker], HashMap::new(), &[], &[]);
+        assert!(check_selectors(&kubes).is_ok());
+
+        // names which are identical once truncated to a label value
+        let long = DockerService {
+            name: format!("{}-a", "n".repeat(63)),
+            ..dk.clone()
+        };
+        let other = DockerService {
+            name: format!("{}-b", "n".repeat(63)),
+            ..dk
+        };
+
+        let kubes = get_basic_objects(&[long, other], HashMap::new(), &[], &[]);
+        assert!(check_selectors(&kubes).is_err());
+    }
+
+    #[test]
+    fn expect_to_create_subdomain_service() {
+        let (dk, _) = setup();
+        let api = DockerService {
+            name: "api".to_owned(),
+            host: DockerHost {
+                hostname: Some("api".to_owned()),
+                domainname: Some("backend.local".to_owned()),
+                ..Default::default()
+            },
+            ..dk.clone()
+        };
+
+        let kubes = get_basic_objects(&[api.clone(), dk.clone()], HashMap::new(), &[], &[]);
+        let headless = kubes[0].headless.as_ref().unwrap();
+        assert_eq!(headless.name, "backend");
+        assert!(headless.headless);
+        assert_eq!(headless.selector, kubes[0].ctrl.selector);
+        assert_eq!(kubes[0].ctrl.subdomain, Some("backend".to_owned()));
+        assert!(kubes[1].headless.is_none());
+
+        // a subdomain shared by several services can't be resolved by a single Service
+        let worker = DockerService {
+            name: "worker".to_owned(),
+            ..api.clone()
+        };
+        let kubes = get_basic_objects(&[api, worker], HashMap::new(), &[], &[]);
+        assert!(kubes[0].headless.is_none());
+        assert!(kubes[0].ctrl.subdomain.is_none());
+    }
 }
 
 #[cfg(test)]
 mod create {
+    use std::env;
+    use std::path::Path;
     use std::collections::HashMap;
     use crate::docker::parser::DockerService;
+    use crate::tests::{to_map, to_ports, to_pairs};
     use crate::kubernetes::builder::{get_basic_objects};
     use crate::kubernetes::controllers::controller::ControllerKind;
     use crate::kubernetes::controllers::service::ServiceType;
@@ -110,22 +224,26 @@ mod create {
         let dk = DockerService {
             name: "nginx".to_string(),
             image: "nginx:1.3.0".to_string(),
-            commands: vec!["sudo nginx reload".to_string()],
-            ports: vec!["9000:9000".to_string()],
-            labels: vec!["back".to_string()],
-            environment: vec!["API_ENV=dev".to_string()],
-            volumes: vec![]
+            command: vec!["sudo nginx reload".to_string()],
+            ports: to_ports(&["9000:9000"]),
+            labels: to_map(&[("tier", "back")]),
+            environment: to_pairs(&[("API_ENV", "dev")]),
+            volumes: vec![],
+            ..Default::default()
         };
 
         let conf = ConfigConfiture {
             name: "nginx".to_string(),
+            skip: false,
             deployment: ConfigDeployment {
-                replicas: 3,
-                controller: ControllerKind::Deployment
+                replicas: Some(3),
+                controller: Some(ControllerKind::Deployment),
+                ..Default::default()
             },
             service: ConfigService {
                 kind: ServiceType::NodePort,
-                nodeport: 30320
+                nodeport: 30320,
+                ports: vec![]
             }
         };
 
@@ -133,7 +251,27 @@ mod create {
         let mut map: HashMap<String, &ConfigConfiture> = HashMap::new();
         map.insert("nginx".to_string(), &conf);
 
-        get_basic_objects(&vec![dk], map)
+        // write the objects in a temporary folder instead of the current directory
+        let folder = env::temp_dir().join("capoomobi-generator");
+        let rebase = |path: &Path| folder.join(path.file_name().unwrap());
+
+        get_basic_objects(&vec![dk], map, &[], &[])
+            .into_iter()
+            .map(|mut k| {
+                k.project_path = folder.clone();
+                k.ctrl.path = rebase(&k.ctrl.path);
+                if let Some(svc) = k.svc.as_mut() {
+                    svc.path = rebase(&svc.path);
+                }
+                if let Some(config_map) = k.config_map.as_mut() {
+                    config_map.path = rebase(&config_map.path);
+                }
+                if let Some(secret) = k.secret.as_mut() {
+                    secret.path = rebase(&secret.path);
+                }
+                k
+            })
+            .collect()
     }
 
     #[test]
@@ -3,9 +3,13 @@ mod controller {
     use std::path::PathBuf;
     use crate::kubernetes::controllers::controller::{
         KubeController,
-        ControllerKind
+        ControllerKind,
+        ContainerPort
     };
+    use crate::docker::port::Protocol;
     use crate::docker::parser::DockerService;
+    use crate::docker::deploy::DockerDeploy;
+    use crate::tests::{to_map, to_ports, to_selector, to_labels, to_pairs};
     use crate::confiture::config::ConfigDeployment;
 
     // setup method use to get a default DockerService
@@ -13,16 +17,18 @@ mod controller {
         let dk = DockerService {
             name: "capoo".to_string(),
             image: "shigedangao/capoomobi:latest".to_string(),
-            commands: vec!["sh echo 'hey'".to_string()],
-            ports: vec!["9000:9000".to_string()],
-            labels: vec!["rust".to_string()],
-            environment: vec!["mode=dev".to_string(), "john=doe".to_string()],
-            volumes: vec![]
+            command: vec!["sh echo 'hey'".to_string()],
+            ports: to_ports(&["9000:9000"]),
+            labels: to_map(&[("lang", "rust")]),
+            environment: to_pairs(&[("mode", "dev"), ("john", "doe")]),
+            volumes: vec![],
+            ..Default::default()
         };
 
         let conf = ConfigDeployment {
-            replicas: 2,
-            controller: ControllerKind::Deployment
+            replicas: Some(2),
+            controller: Some(ControllerKind::Deployment),
+            ..Default::default()
         };
 
         (dk, conf)
@@ -32,26 +38,64 @@ mod controller {
     #[test]
     fn expect_to_create_kube_controller() {
         let (dk, conf) = setup();
-        let controller = KubeController::new(dk, &conf, &PathBuf::new()).unwrap();
+        let controller = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
 
         assert_eq!(controller.ctrl, ControllerKind::Deployment);
         assert_eq!(controller.name, "capoo");
         assert_eq!(controller.image, "shigedangao/capoomobi:latest");
-        assert_eq!(controller.commands, vec!["sh echo 'hey'".to_string()]);
-        assert_eq!(controller.ports, vec![9000]);
+        assert_eq!(controller.args, vec!["sh echo 'hey'".to_string()]);
+        assert_eq!(controller.ports, vec![
+            ContainerPort { name: "tcp-9000".to_string(), port: 9000, protocol: Protocol::Tcp }
+        ]);
+    }
+
+    #[test]
+    fn expect_to_dedupe_container_ports() {
+        let (mut dk, conf) = setup();
+        dk.ports = to_ports(&["8080:80", "8081:80", "53:53/udp"]);
+        let controller = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+
+        assert_eq!(controller.ports.len(), 2);
+        assert_eq!(controller.ports[0].name, "tcp-80");
+        assert_eq!(controller.ports[1].name, "udp-53");
+        assert_eq!(controller.ports[1].protocol, Protocol::Udp);
     }
 
     #[test]
     fn expect_create_kube_ports_empty() {
         let (mut dk, conf) = setup();
         dk.ports = vec![];
-        let controller = KubeController::new(dk, &conf, &PathBuf::new()).unwrap();
+        let controller = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
 
         assert_eq!(controller.ctrl, ControllerKind::Deployment);
         assert_eq!(controller.name, "capoo");
         assert_eq!(controller.image, "shigedangao/capoomobi:latest");
-        assert_eq!(controller.commands, vec!["sh echo 'hey'".to_string()]);
-        assert_eq!(controller.ports, Vec::<u16>::new());
+        assert_eq!(controller.args, vec!["sh echo 'hey'".to_string()]);
+        assert!(controller.ports.is_empty());
+    }
+
+    #[test]
+    fn expect_to_use_compose_deploy_as_defaults() {
+        let (mut dk, mut conf) = setup();
+        dk.deploy = DockerDeploy {
+            replicas: Some(300),
+            labels: to_map(&[("lang", "go"), ("team", "capoo")]),
+            ..Default::default()
+        };
+
+        let controller = KubeController::new(dk.clone(), &conf, &[], &PathBuf::new()).unwrap();
+        assert_eq!(controller.replicas, 2);
+        assert_eq!(controller.labels, to_labels("capoo", "capoo", &[("lang", "go"), ("team", "capoo")]));
+        assert_eq!(controller.pod_labels, to_labels("capoo", "capoo", &[("lang", "rust")]));
+        assert_eq!(controller.selector, to_selector("capoo", "capoo"));
+
+        conf.replicas = None;
+        let controller = KubeController::new(dk.clone(), &conf, &[], &PathBuf::new()).unwrap();
+        assert_eq!(controller.replicas, 300);
+
+        dk.deploy.replicas = None;
+        let controller = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        assert_eq!(controller.replicas, 1);
     }
 }
 
@@ -60,26 +104,31 @@ mod service {
     use std::path::PathBuf;
     use crate::kubernetes::controllers::service::{
         ServiceType,
-        KubeService
+        KubeService,
+        ServicePort
     };
+    use crate::docker::port::Protocol;
     use crate::docker::parser::DockerService;
-    use crate::confiture::config::ConfigService;
+    use crate::tests::{to_map, to_ports, to_selector, to_labels, to_pairs};
+    use crate::confiture::config::{ConfigService, ConfigServicePort};
 
     // method use to get default DockerService
     fn setup() -> (DockerService, ConfigService) {
         let dk = DockerService {
             name: "capoo".to_string(),
             image: "shigedangao/capoomobi:latest".to_string(),
-            commands: vec!["sh echo 'hey'".to_string()],
-            ports: vec!["9000:9000".to_string()],
-            labels: vec!["rust".to_string()],
-            environment: vec!["mode=dev".to_string(), "john=doe".to_string()],
-            volumes: vec![]
+            command: vec!["sh echo 'hey'".to_string()],
+            ports: to_ports(&["9000:9000"]),
+            labels: to_map(&[("lang", "rust")]),
+            environment: to_pairs(&[("mode", "dev"), ("john", "doe")]),
+            volumes: vec![],
+            ..Default::default()
         };
 
         let conf = ConfigService  {
             kind: ServiceType::NodePort,
-            nodeport: 9000
+            nodeport: 9000,
+            ports: vec![]
         };
 
         (dk, conf)
@@ -91,14 +140,74 @@ mod service {
         let service = KubeService::new(dk, &conf, &PathBuf::from("../foo")).unwrap();
 
         assert_eq!(service.name, "capoo-svc");
-        assert_eq!(service.nodeport, 9000);
         assert_eq!(service.kind, ServiceType::NodePort);
-        assert_eq!(service.host_port, 9000);
-        assert_eq!(service.target_port, 9000);
-        assert_eq!(service.labels, vec!["rust"]);
+        assert_eq!(service.ports, vec![
+            ServicePort {
+                name: "tcp-9000".to_string(),
+                protocol: Protocol::Tcp,
+                port: 9000,
+                target_port: 9000,
+                node_port: Some(9000)
+            }
+        ]);
+        assert_eq!(service.labels, to_labels("capoo", "capoo", &[("lang", "rust")]));
+        assert_eq!(service.selector, to_selector("capoo", "capoo"));
         assert_eq!(service.path.as_os_str(), "../foo/service.yaml");
     }
 
+    #[test]
+    fn expect_create_multi_port_service() {
+        let (mut dk, mut conf) = setup();
+        dk.ports = to_ports(&["8080:80", "9100", "53:53/udp", "53:53"]);
+        conf.nodeport = 0;
+        conf.ports = vec![
+            ConfigServicePort { port: 9100, nodeport: 30910, protocol: Protocol::Tcp },
+            ConfigServicePort { port: 53, nodeport: 30053, protocol: Protocol::Udp },
+            ConfigServicePort { port: 53, nodeport: 30054, protocol: Protocol::Tcp }
+        ];
+
+        let service = KubeService::new(dk, &conf, &PathBuf::new()).unwrap();
+        assert_eq!(service.ports.len(), 4);
+        assert_eq!(service.ports[0].name, "tcp-80");
+        assert_eq!(service.ports[0].port, 8080);
+        assert_eq!(service.ports[0].target_port, 80);
+        assert_eq!(service.ports[0].node_port, None);
+        assert_eq!(service.ports[1].name, "tcp-9100");
+        assert_eq!(service.ports[1].node_port, Some(30910));
+        assert_eq!(service.ports[2].protocol, Protocol::Udp);
+        // a port exposed over tcp & udp has a node port per protocol
+        assert_eq!(service.ports[2].node_port, Some(30053));
+        assert_eq!(service.ports[3].node_port, Some(30054));
+    }
+
+    #[test]
+    fn expect_to_expose_every_published_port() {
+        let (mut dk, mut conf) = setup();
+        dk.ports = to_ports(&["80:80", "8080:80", "8080:80"]);
+        conf.nodeport = 0;
+        conf.ports = vec![
+            ConfigServicePort { port: 8080, nodeport: 30080, protocol: Protocol::Tcp }
+        ];
+
+        let service = KubeService::new(dk, &conf, &PathBuf::new()).unwrap();
+        assert_eq!(service.ports.len(), 2);
+        assert_eq!(service.ports[0].name, "tcp-80");
+        assert_eq!(service.ports[0].port, 80);
+        assert_eq!(service.ports[1].name, "tcp-80-8080");
+        assert_eq!(service.ports[1].port, 8080);
+        assert_eq!(service.ports[1].target_port, 80);
+        assert_eq!(service.ports[1].node_port, Some(30080));
+    }
+
+    #[test]
+    fn expect_cluster_ip_to_not_have_node_port() {
+        let (dk, mut conf) = setup();
+        conf.kind = ServiceType::ClusterIP;
+
+        let service = KubeService::new(dk, &conf, &PathBuf::new()).unwrap();
+        assert_eq!(service.ports[0].node_port, None);
+    }
+
     #[test]
     fn exepct_not_create_service() {
         let (mut dk, conf) = setup();
@@ -113,6 +222,7 @@ mod service {
 mod ingress {
     use crate::kubernetes::controllers::ingress::KubeIngress;
     use crate::docker::parser::DockerService;
+    use crate::tests::{to_map, to_ports, to_pairs};
     use crate::confiture::config::{ConfigIngress, ConfigIngressService};
 
     // method use to get the Config
@@ -120,11 +230,12 @@ mod ingress {
         let dk = DockerService {
             name: "capoo".to_string(),
             image: "shigedangao/capoomobi:latest".to_string(),
-            commands: vec!["sh echo 'hey'".to_string()],
-            ports: vec!["9000:9000".to_string()],
-            labels: vec!["rust".to_string()],
-            environment: vec!["mode=dev".to_string(), "john=doe".to_string()],
-            volumes: vec![]
+            command: vec!["sh echo 'hey'".to_string()],
+            ports: to_ports(&["9000:9000"]),
+            labels: to_map(&[("lang", "rust")]),
+            environment: to_pairs(&[("mode", "dev"), ("john", "doe")]),
+            volumes: vec![],
+            ..Default::default()
         };
 
         let conf = ConfigIngress {
@@ -152,3 +263,1370 @@ mod ingress {
         assert_eq!(ing.backend[0].service_port, 9000);
     }
  }
+
+#[cfg(test)]
+mod volume {
+    use std::path::PathBuf;
+    use crate::kubernetes::controllers::controller::{KubeController, ControllerKind};
+    use crate::kubernetes::controllers::volume::{AccessMode, get_volume_claims, get_pod_volumes};
+    use crate::docker::parser::DockerService;
+    use crate::docker::volume::{DockerVolume, DockerNamedVolume, VolumeKind, resolve_external_volumes};
+    use crate::confiture::config::{ConfigDeployment, ConfigVolume};
+
+    // method use to get a list of volumes
+    fn setup() -> (Vec<DockerVolume>, Vec<DockerNamedVolume>) {
+        let volumes = vec![
+            DockerVolume {
+                kind: VolumeKind::Volume,
+                source: "db_data".to_string(),
+                target: "/var/lib/mysql".to_string(),
+                read_only: false,
+                external: false,
+                ..Default::default()
+            },
+            DockerVolume {
+                kind: VolumeKind::Volume,
+                source: "shared".to_string(),
+                target: "/shared".to_string(),
+                read_only: true,
+                external: false,
+                ..Default::default()
+            },
+            DockerVolume {
+                kind: VolumeKind::Bind,
+                source: "./conf".to_string(),
+                target: "/etc/conf".to_string(),
+                read_only: true,
+                external: false,
+                ..Default::default()
+            }
+        ];
+
+        let named = vec![
+            DockerNamedVolume { name: "db_data".to_string(), external: false, external_name: None },
+            DockerNamedVolume { name: "shared".to_string(), external: true, external_name: None },
+            DockerNamedVolume { name: "logs".to_string(), external: false, external_name: None }
+        ];
+
+        (volumes, named)
+    }
+
+    #[test]
+    fn expect_to_create_volume_claims() {
+        let (volumes, named) = setup();
+        let conf = vec![
+            ConfigVolume {
+                name: "db_data".to_string(),
+                size: Some("5Gi".to_string()),
+                storage_class: Some("fast".to_string()),
+                access_mode: None
+            }
+        ];
+
+        let claims = get_volume_claims(&volumes, &named, &conf);
+        assert_eq!(claims.len(), 2);
+        assert_eq!(claims[0].name, "db-data");
+        assert_eq!(claims[0].size, "5Gi");
+        assert_eq!(claims[0].storage_class, Some("fast".to_string()));
+        assert_eq!(claims[0].access_mode, AccessMode::ReadWriteOnce);
+        assert_eq!(claims[0].get_filename(), "db-data-pvc.yaml");
+        assert_eq!(claims[1].name, "logs");
+        assert_eq!(claims[1].size, "1Gi");
+    }
+
+    #[test]
+    fn expect_controller_to_mount_named_volumes() {
+        let (volumes, _) = setup();
+        let dk = DockerService {
+            name: "db".to_string(),
+            image: "mysql:5.7".to_string(),
+            command: vec![],
+            ports: vec![],
+            labels: Default::default(),
+            environment: Default::default(),
+            volumes,
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(1),
+            controller: Some(ControllerKind::Deployment),
+            ..Default::default()
+        };
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        assert_eq!(ctrl.volumes.len(), 2);
+        assert_eq!(ctrl.volumes[0].name, "db-data");
+        assert_eq!(ctrl.volumes[0].claim, Some("db-data".to_string()));
+        assert_eq!(ctrl.volume_mounts.len(), 2);
+        assert_eq!(ctrl.volume_mounts[1].path, "/shared");
+        assert!(ctrl.volume_mounts[1].read_only);
+    }
+
+    #[test]
+    fn expect_external_volume_to_be_bound_to_its_claim() {
+        let (mut volumes, mut named) = setup();
+        named[1].external_name = Some("shared-claim".to_string());
+        resolve_external_volumes(&mut volumes, &named);
+
+        let (pod_volumes, mounts) = get_pod_volumes(&volumes);
+        assert_eq!(pod_volumes.len(), 2);
+        assert_eq!(pod_volumes[0].claim, Some("db-data".to_string()));
+        assert_eq!(pod_volumes[1].name, "shared");
+        assert_eq!(pod_volumes[1].claim, Some("shared-claim".to_string()));
+        assert_eq!(mounts[1].name, "shared");
+    }
+}
+
+#[cfg(test)]
+mod mount {
+    use std::collections::BTreeMap;
+    use crate::kubernetes::controllers::mount::{ConfigMount, MountStrategy, get_mount_volumes};
+    use crate::tests::to_map;
+    use crate::docker::volume::{DockerVolume, DockerBindContent, DockerBindFile, VolumeKind};
+
+    // method use to get the mounts of a service
+    fn setup() -> Vec<DockerVolume> {
+        vec![
+            DockerVolume {
+                kind: VolumeKind::Bind,
+                source: "/app/nginx.conf".to_string(),
+                target: "/etc/nginx/nginx.conf".to_string(),
+                read_only: true,
+                content: Some(DockerBindContent {
+                    is_file: true,
+                    files: vec![DockerBindFile {
+                        name: "nginx.conf".to_string(),
+                        data: b"worker_processes 1;".to_vec()
+                    }]
+                }),
+                ..Default::default()
+            },
+            DockerVolume {
+                kind: VolumeKind::Tmpfs,
+                target: "/tmp".to_string(),
+                size: Some("64m".to_string()),
+                ..Default::default()
+            },
+            DockerVolume {
+                kind: VolumeKind::Bind,
+                source: "/var/run/docker.sock".to_string(),
+                target: "/var/run/docker.sock".to_string(),
+                ..Default::default()
+            }
+        ]
+    }
+
+    #[test]
+    fn expect_to_translate_mounts_with_default_strategies() {
+        let (volumes, mounts, config_maps) = get_mount_volumes("nginx", &setup(), &[], &to_map(&[("tier", "back")]));
+
+        assert_eq!(volumes.len(), 2);
+        assert_eq!(volumes[0].name, "nginx-etc-nginx-nginx-conf");
+        assert_eq!(volumes[0].config_map, Some("nginx-etc-nginx-nginx-conf".to_string()));
+        assert_eq!(mounts[0].sub_path, Some("nginx.conf".to_string()));
+        assert!(mounts[0].read_only);
+
+        let empty_dir = volumes[1].empty_dir.as_ref().unwrap();
+        assert_eq!(empty_dir.medium, Some("Memory".to_string()));
+        assert_eq!(empty_dir.size_limit, Some("64Mi".to_string()));
+
+        assert_eq!(config_maps.len(), 1);
+        assert_eq!(config_maps[0].data.get("nginx.conf").unwrap(), "worker_processes 1;");
+        assert_eq!(config_maps[0].labels, to_map(&[("tier", "back")]));
+    }
+
+    #[test]
+    fn expect_to_apply_mount_strategies() {
+        let options = vec![
+            ConfigMount {
+                target: "/var/run/docker.sock".to_string(),
+                strategy: MountStrategy::HostPath,
+                size_limit: None
+            },
+            ConfigMount {
+                target: "/etc/nginx/nginx.conf".to_string(),
+                strategy: MountStrategy::Skip,
+                size_limit: None
+            },
+            ConfigMount {
+                target: "/tmp".to_string(),
+                strategy: MountStrategy::HostPath,
+                size_limit: None
+            }
+        ];
+
+        let (volumes, mounts, config_maps) = get_mount_volumes("nginx", &setup(), &options, &BTreeMap::new());
+        assert_eq!(volumes.len(), 1);
+        assert_eq!(volumes[0].host_path, Some("/var/run/docker.sock".to_string()));
+        assert_eq!(mounts[0].path, "/var/run/docker.sock");
+        assert!(config_maps.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod statefulset {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use crate::kubernetes::builder::{get_basic_objects, get_volume_claim_objects};
+    use crate::kubernetes::controllers::controller::{KubeController, ControllerKind, PodManagementPolicy};
+    use crate::kubernetes::controllers::service::{KubeService, ServiceType};
+    use crate::kubernetes::controllers::volume::AccessMode;
+    use crate::docker::parser::DockerService;
+    use crate::docker::volume::{DockerVolume, DockerNamedVolume, VolumeKind};
+    use crate::confiture::config::{ConfigConfiture, ConfigDeployment, ConfigService, ConfigVolume};
+    use crate::tests::to_ports;
+
+    // method use to get a stateful docker service
+    fn setup() -> (DockerService, ConfigDeployment) {
+        let dk = DockerService {
+            name: "db".to_string(),
+            image: "mysql:5.7".to_string(),
+            command: vec![],
+            ports: to_ports(&["3306"]),
+            labels: Default::default(),
+            environment: Default::default(),
+            volumes: vec![
+                DockerVolume {
+                    kind: VolumeKind::Volume,
+                    source: "db_data".to_string(),
+                    target: "/var/lib/mysql".to_string(),
+                    read_only: false,
+                    external: false,
+                    ..Default::default()
+                }
+            ],
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(3),
+            controller: Some(ControllerKind::StatefulSet),
+            pod_management_policy: Some(PodManagementPolicy::Parallel),
+            ..Default::default()
+        };
+
+        (dk, conf)
+    }
+
+    #[test]
+    fn expect_to_create_volume_claim_templates() {
+        let (dk, conf) = setup();
+        let volumes = vec![
+            ConfigVolume {
+                name: "db_data".to_string(),
+                size: Some("20Gi".to_string()),
+                storage_class: None,
+                access_mode: Some(AccessMode::ReadWriteOnce)
+            }
+        ];
+
+        let ctrl = KubeController::new(dk, &conf, &volumes, &PathBuf::new()).unwrap();
+        assert_eq!(ctrl.service_name, Some("db-headless".to_string()));
+        assert_eq!(ctrl.pod_management_policy, Some(PodManagementPolicy::Parallel));
+        assert_eq!(ctrl.volume_claims.len(), 1);
+        assert_eq!(ctrl.volume_claims[0].name, "db-data");
+        assert_eq!(ctrl.volume_claims[0].size, "20Gi");
+        assert!(ctrl.volumes.is_empty());
+        assert_eq!(ctrl.volume_mounts[0].name, "db-data");
+    }
+
+    #[test]
+    fn expect_deployment_to_not_have_stateful_fields() {
+        let (dk, mut conf) = setup();
+        conf.controller = Some(ControllerKind::Deployment);
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        assert!(ctrl.service_name.is_none());
+        assert!(ctrl.pod_management_policy.is_none());
+        assert!(ctrl.volume_claims.is_empty());
+        assert_eq!(ctrl.volumes.len(), 1);
+    }
+
+    #[test]
+    fn expect_to_create_headless_service() {
+        let (dk, _) = setup();
+        let svc = KubeService::new_headless(dk, "db-headless", &PathBuf::new());
+
+        assert_eq!(svc.name, "db-headless");
+        assert_eq!(svc.kind, ServiceType::ClusterIP);
+        assert!(svc.headless);
+        assert_eq!(svc.ports[0].port, 3306);
+        assert!(svc.ports[0].node_port.is_none());
+    }
+
+    #[test]
+    fn expect_to_skip_claims_of_stateful_volumes() {
+        let (dk, conf) = setup();
+        let confiture = ConfigConfiture {
+            name: "db".to_string(),
+            skip: false,
+            deployment: conf,
+            service: ConfigService::default()
+        };
+
+        let mut map = HashMap::new();
+        map.insert("db".to_string(), &confiture);
+
+        let named = vec![
+            DockerNamedVolume { name: "db_data".to_string(), external: false, external_name: None },
+            DockerNamedVolume { name: "logs".to_string(), external: false, external_name: None }
+        ];
+
+        let kubes = get_basic_objects(std::slice::from_ref(&dk), map, &[], &[]);
+        assert!(kubes[0].headless.is_some());
+
+        let claims = get_volume_claim_objects(&[dk], &kubes, &named, &[]);
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].name, "logs");
+    }
+}
+
+#[cfg(test)]
+mod daemonset {
+    use std::path::PathBuf;
+    use crate::kubernetes::controllers::controller::{KubeController, ControllerKind, UpdateStrategyKind};
+    use crate::docker::parser::DockerService;
+    use crate::docker::deploy::{DockerDeploy, DeployMode};
+    use crate::confiture::config::ConfigDeployment;
+    use crate::tests::{to_map, to_selector, to_labels};
+
+    // method use to get a docker service deployed on every node
+    fn setup() -> (DockerService, ConfigDeployment) {
+        let dk = DockerService {
+            name: "agent".to_string(),
+            image: "prom/node-exporter".to_string(),
+            deploy: DockerDeploy {
+                mode: DeployMode::Global,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(1),
+            ..Default::default()
+        };
+
+        (dk, conf)
+    }
+
+    #[test]
+    fn expect_global_mode_to_create_daemonset() {
+        let (dk, conf) = setup();
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+
+        assert_eq!(ctrl.ctrl, ControllerKind::DaemonSet);
+        assert_eq!(ctrl.update_strategy.unwrap().kind, UpdateStrategyKind::RollingUpdate);
+        assert_eq!(ctrl.selector, to_selector("agent", "agent"));
+    }
+
+    #[test]
+    fn expect_confiture_to_override_global_mode() {
+        let (dk, mut conf) = setup();
+        conf.controller = Some(ControllerKind::Deployment);
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        assert_eq!(ctrl.ctrl, ControllerKind::Deployment);
+        assert!(ctrl.update_strategy.is_none());
+    }
+
+    #[test]
+    fn expect_replicated_mode_to_create_deployment() {
+        let (mut dk, conf) = setup();
+        dk.deploy.mode = DeployMode::Replicated;
+        dk.labels = to_map(&[("tier", "monitoring")]);
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        assert_eq!(ctrl.ctrl, ControllerKind::Deployment);
+        // the labels of the compose service don't select the pods
+        assert_eq!(ctrl.selector, to_selector("agent", "agent"));
+        assert_eq!(ctrl.pod_labels, to_labels("agent", "agent", &[("tier", "monitoring")]));
+    }
+}
+
+#[cfg(test)]
+mod job {
+    use std::path::PathBuf;
+    use crate::kubernetes::controllers::controller::{KubeController, ControllerKind};
+    use crate::kubernetes::controllers::service::KubeService;
+    use crate::kubernetes::controllers::job::{JobRestartPolicy, ConcurrencyPolicy};
+    use crate::docker::parser::DockerService;
+    use crate::docker::deploy::{DockerDeploy, RestartCondition};
+    use crate::confiture::config::{ConfigDeployment, ConfigJob, ConfigService};
+
+    // method use to get a one-shot docker service
+    fn setup() -> (DockerService, ConfigDeployment) {
+        let dk = DockerService {
+            name: "migrate".to_string(),
+            image: "migrate/migrate".to_string(),
+            deploy: DockerDeploy {
+                restart: RestartCondition::No,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(1),
+            ..Default::default()
+        };
+
+        (dk, conf)
+    }
+
+    #[test]
+    fn expect_restart_no_to_create_job() {
+        let (dk, conf) = setup();
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        let job = ctrl.job.unwrap();
+
+        assert_eq!(ctrl.ctrl, ControllerKind::Job);
+        assert_eq!(job.restart_policy, JobRestartPolicy::Never);
+        assert_eq!(job.backoff_limit, 6);
+        assert_eq!(job.completions, 1);
+    }
+
+    #[test]
+    fn expect_on_failure_to_restart_pods_on_failure() {
+        let (mut dk, mut conf) = setup();
+        dk.deploy.restart = RestartCondition::OnFailure;
+        conf.job = Some(ConfigJob {
+            backoff_limit: Some(2),
+            parallelism: Some(3),
+            ..Default::default()
+        });
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        let job = ctrl.job.unwrap();
+
+        assert_eq!(ctrl.ctrl, ControllerKind::Job);
+        assert_eq!(job.restart_policy, JobRestartPolicy::OnFailure);
+        assert_eq!(job.backoff_limit, 2);
+        assert_eq!(job.parallelism, 3);
+    }
+
+    #[test]
+    fn expect_schedule_to_create_cronjob() {
+        let (mut dk, mut conf) = setup();
+        dk.deploy.restart = RestartCondition::Always;
+        conf.job = Some(ConfigJob {
+            schedule: Some("*/5 * * * *".to_string()),
+            concurrency_policy: Some(ConcurrencyPolicy::Forbid),
+            ..Default::default()
+        });
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        let job = ctrl.job.unwrap();
+
+        assert_eq!(ctrl.ctrl, ControllerKind::CronJob);
+        assert_eq!(job.schedule, Some("*/5 * * * *".to_string()));
+        assert_eq!(job.concurrency_policy, ConcurrencyPolicy::Forbid);
+        assert_eq!(job.restart_policy, JobRestartPolicy::OnFailure);
+    }
+
+    #[test]
+    fn expect_cronjob_without_schedule_to_create_job() {
+        let (mut dk, mut conf) = setup();
+        dk.deploy.restart = RestartCondition::Always;
+        conf.controller = Some(ControllerKind::CronJob);
+
+        let ctrl = KubeController::new(dk.clone(), &conf, &[], &PathBuf::new()).unwrap();
+        assert_eq!(ctrl.ctrl, ControllerKind::Job);
+        assert!(ctrl.job.unwrap().schedule.is_none());
+
+        conf.job = Some(ConfigJob {
+            schedule: Some(" ".to_string()),
+            ..Default::default()
+        });
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        assert_eq!(ctrl.ctrl, ControllerKind::Job);
+    }
+
+    #[test]
+    fn expect_job_without_ports_to_not_have_service() {
+        let (dk, _) = setup();
+        let svc = KubeService::new(dk, &ConfigService::default(), &PathBuf::new());
+
+        assert!(svc.is_none());
+    }
+}
+
+#[cfg(test)]
+mod probe {
+    use crate::kubernetes::controllers::probe::{ProbeKind, ProbeHandler, HttpGetAction, get_probes, get_probe_handler};
+    use crate::docker::healthcheck::{DockerHealthcheck, HealthcheckTest};
+    use crate::confiture::config::ConfigProbes;
+
+    // method use to get a healthcheck
+    fn setup(test: HealthcheckTest) -> DockerHealthcheck {
+        DockerHealthcheck {
+            test,
+            interval: Some(10),
+            timeout: Some(5),
+            retries: Some(3),
+            start_period: None
+        }
+    }
+
+    #[test]
+    fn expect_curl_to_be_http_get() {
+        let test = HealthcheckTest::Cmd(vec!["curl".to_owned(), "-f".to_owned(), "http://localhost:8080/ready".to_owned()]);
+        let handler = get_probe_handler(&test);
+
+        assert_eq!(handler, ProbeHandler::HttpGet(HttpGetAction {
+            path: "/ready".to_owned(),
+            port: 8080,
+            scheme: "HTTP".to_owned()
+        }));
+    }
+
+    #[test]
+    fn expect_shell_wget_to_be_http_get() {
+        let test = HealthcheckTest::Shell("wget -q --spider https://127.0.0.1 || exit 1".to_owned());
+        match get_probe_handler(&test) {
+            ProbeHandler::HttpGet(action) => {
+                assert_eq!(action.port, 443);
+                assert_eq!(action.path, "/");
+                assert_eq!(action.scheme, "HTTPS");
+            },
+            _ => panic!("Expect an httpGet probe")
+        }
+    }
+
+    #[test]
+    fn expect_nc_to_be_tcp_socket() {
+        let test = HealthcheckTest::Shell("nc -z localhost 5432".to_owned());
+        assert_eq!(get_probe_handler(&test), ProbeHandler::TcpSocket(5432));
+    }
+
+    #[test]
+    fn expect_chained_commands_to_be_exec() {
+        let commands = [
+            "curl -s http://localhost/health | grep -q UP",
+            "curl -f http://localhost/health && test -f /ready",
+            "nc -z localhost 5432; test -f /ready",
+            "curl -f http://localhost/health || curl -f http://localhost:8080/health"
+        ];
+
+        for cmd in commands.iter() {
+            let test = HealthcheckTest::Shell(cmd.to_string());
+            assert_eq!(get_probe_handler(&test), ProbeHandler::Exec(vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                cmd.to_string()
+            ]));
+        }
+    }
+
+    #[test]
+    fn expect_other_commands_to_be_exec() {
+        let remote = HealthcheckTest::Cmd(vec!["curl".to_owned(), "http://google.com".to_owned()]);
+        let shell = HealthcheckTest::Shell("pg_isready -U postgres".to_owned());
+
+        assert_eq!(get_probe_handler(&remote), ProbeHandler::Exec(vec!["curl".to_owned(), "http://google.com".to_owned()]));
+        assert_eq!(get_probe_handler(&shell), ProbeHandler::Exec(vec![
+            "sh".to_owned(),
+            "-c".to_owned(),
+            "pg_isready -U postgres".to_owned()
+        ]));
+    }
+
+    #[test]
+    fn expect_to_create_default_probes() {
+        let mut hc = setup(HealthcheckTest::Shell("pg_isready".to_owned()));
+        let probes = get_probes(Some(&hc), None);
+
+        assert_eq!(probes.len(), 2);
+        assert_eq!(probes[0].name, "livenessProbe");
+        assert_eq!(probes[1].name, "readinessProbe");
+        assert_eq!(probes[0].period_seconds, Some(10));
+        assert_eq!(probes[0].timeout_seconds, Some(5));
+        assert_eq!(probes[0].failure_threshold, Some(3));
+        assert!(probes[0].initial_delay_seconds.is_none());
+
+        hc.start_period = Some(60);
+        let probes = get_probes(Some(&hc), None);
+        assert_eq!(probes.len(), 3);
+        assert_eq!(probes[2].name, "startupProbe");
+        assert_eq!(probes[2].failure_threshold, Some(6));
+        assert!(probes[0].initial_delay_seconds.is_none());
+    }
+
+    #[test]
+    fn expect_confiture_to_override_probes() {
+        let mut hc = setup(HealthcheckTest::Shell("pg_isready".to_owned()));
+        hc.start_period = Some(30);
+
+        let conf = ConfigProbes {
+            kinds: Some(vec![ProbeKind::Readiness]),
+            period_seconds: Some(5),
+            success_threshold: Some(2),
+            ..Default::default()
+        };
+
+        let probes = get_probes(Some(&hc), Some(&conf));
+        assert_eq!(probes.len(), 1);
+        assert_eq!(probes[0].name, "readinessProbe");
+        assert_eq!(probes[0].period_seconds, Some(5));
+        assert_eq!(probes[0].success_threshold, Some(2));
+        assert_eq!(probes[0].initial_delay_seconds, Some(30));
+        assert!(get_probes(None, Some(&conf)).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod resources {
+    use crate::kubernetes::controllers::resources::{
+        KubeResources,
+        ResourceQuantities,
+        get_resources,
+        to_cpu_quantity,
+        to_memory_quantity
+    };
+    use crate::docker::deploy::{DockerResource, DockerResources};
+
+    #[test]
+    fn expect_to_convert_cpus() {
+        assert_eq!(to_cpu_quantity("0.5"), Some("500m".to_owned()));
+        assert_eq!(to_cpu_quantity("1.25"), Some("1250m".to_owned()));
+        assert_eq!(to_cpu_quantity("2"), Some("2".to_owned()));
+        assert_eq!(to_cpu_quantity("0.001"), Some("1m".to_owned()));
+        assert_eq!(to_cpu_quantity("0"), None);
+        assert_eq!(to_cpu_quantity("two"), None);
+    }
+
+    #[test]
+    fn expect_to_convert_memory() {
+        assert_eq!(to_memory_quantity("512M"), Some("512Mi".to_owned()));
+        assert_eq!(to_memory_quantity("1gb"), Some("1Gi".to_owned()));
+        assert_eq!(to_memory_quantity("1.5g"), Some("1.5Gi".to_owned()));
+        assert_eq!(to_memory_quantity("100k"), Some("100Ki".to_owned()));
+        assert_eq!(to_memory_quantity("1073741824"), Some("1073741824".to_owned()));
+        assert_eq!(to_memory_quantity("100b"), Some("100".to_owned()));
+        assert_eq!(to_memory_quantity("lots"), None);
+    }
+
+    #[test]
+    fn expect_to_create_resources() {
+        let resources = DockerResources {
+            limits: DockerResource {
+                cpus: Some("0.5".to_owned()),
+                memory: Some("512M".to_owned())
+            },
+            reservations: DockerResource::default()
+        };
+
+        let res = get_resources(&resources, None).unwrap();
+        let limits = res.limits.unwrap();
+
+        assert_eq!(limits.cpu, Some("500m".to_owned()));
+        assert_eq!(limits.memory, Some("512Mi".to_owned()));
+        assert!(res.requests.is_none());
+        assert!(get_resources(&DockerResources::default(), None).is_none());
+    }
+
+    #[test]
+    fn expect_confiture_to_override_resources() {
+        let resources = DockerResources {
+            limits: DockerResource {
+                cpus: Some("0.5".to_owned()),
+                memory: Some("512M".to_owned())
+            },
+            reservations: DockerResource::default()
+        };
+
+        let conf = KubeResources {
+            limits: Some(ResourceQuantities {
+                cpu: None,
+                memory: Some("1Gi".to_owned())
+            }),
+            requests: Some(ResourceQuantities {
+                cpu: Some("100m".to_owned()),
+                memory: None
+            })
+        };
+
+        let res = get_resources(&resources, Some(&conf)).unwrap();
+        let limits = res.limits.unwrap();
+        let requests = res.requests.unwrap();
+
+        assert_eq!(limits.cpu, Some("500m".to_owned()));
+        assert_eq!(limits.memory, Some("1Gi".to_owned()));
+        assert_eq!(requests.cpu, Some("100m".to_owned()));
+        assert!(requests.memory.is_none());
+    }
+}
+
+#[cfg(test)]
+mod dependency {
+    use crate::kubernetes::controllers::dependency::get_init_containers;
+    use crate::docker::parser::DockerService;
+    use crate::docker::dependency::{DockerDependency, DependencyCondition};
+    use crate::confiture::config::ConfigDeployment;
+    use crate::tests::to_ports;
+
+    // method use to get a list of services where the api depends on the other services
+    fn setup(deps: Vec<(&str, DependencyCondition)>) -> Vec<DockerService> {
+        let api = DockerService {
+            name: "api".to_owned(),
+            image: "api".to_owned(),
+            depends_on: deps
+                .into_iter()
+                .map(|(name, condition)| DockerDependency { name: name.to_owned(), condition })
+                .collect(),
+            ..Default::default()
+        };
+
+        let db = DockerService {
+            name: "db".to_owned(),
+            image: "postgres".to_owned(),
+            ports: to_ports(&["53/udp", "5432:5432"]),
+            ..Default::default()
+        };
+
+        let migrate = DockerService {
+            name: "migrate".to_owned(),
+            image: "migrate".to_owned(),
+            ports: to_ports(&["8080"]),
+            ..Default::default()
+        };
+
+        let worker = DockerService {
+            name: "worker".to_owned(),
+            image: "worker".to_owned(),
+            ..Default::default()
+        };
+
+        vec![api, db, migrate, worker]
+    }
+
+    #[test]
+    fn expect_to_wait_for_dependency() {
+        let services = setup(vec![("db", DependencyCondition::Healthy)]);
+        let init = get_init_containers(&services[0], &services, &ConfigDeployment::default());
+
+        assert_eq!(init.len(), 1);
+        assert_eq!(init[0].name, "wait-for-db");
+        assert_eq!(init[0].image, "busybox:1.36");
+        assert_eq!(init[0].command[0], "sh");
+        // the udp port isn't awaited
+        assert_eq!(init[0].command[2], "until nc -z db-svc 5432; do echo waiting for db-svc; sleep 2; done");
+    }
+
+    #[test]
+    fn expect_to_skip_unreachable_dependencies() {
+        let services = setup(vec![
+            ("migrate", DependencyCondition::CompletedSuccessfully),
+            ("worker", DependencyCondition::Started),
+            ("unknown", DependencyCondition::Started)
+        ]);
+
+        let init = get_init_containers(&services[0], &services, &ConfigDeployment::default());
+        assert!(init.is_empty());
+    }
+
+    #[test]
+    fn expect_init_container_name_to_be_valid() {
+        let mut services = setup(vec![("db_primary", DependencyCondition::Started)]);
+        services[1].name = "db_primary".to_owned();
+
+        let init = get_init_containers(&services[0], &services, &ConfigDeployment::default());
+        assert_eq!(init[0].name, "wait-for-db-primary");
+    }
+
+    #[test]
+    fn expect_to_opt_out_of_dependencies() {
+        let services = setup(vec![("db", DependencyCondition::Started)]);
+        let option = ConfigDeployment {
+            wait_for_dependencies: Some(false),
+            ..Default::default()
+        };
+
+        let init = get_init_containers(&services[0], &services, &option);
+        assert!(init.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod environment {
+    use std::path::PathBuf;
+    use crate::kubernetes::controllers::environment::{get_environment, is_sensitive};
+    use crate::docker::parser::DockerService;
+    use crate::tests::{to_map, to_labels, to_pairs};
+
+    #[test]
+    fn expect_to_match_sensitive_patterns() {
+        let patterns = vec!["*_PASSWORD".to_owned(), "api_*".to_owned(), "*SECRET*".to_owned()];
+
+        assert!(is_sensitive("MYSQL_ROOT_PASSWORD", &patterns));
+        assert!(is_sensitive("mysql_password", &patterns));
+        assert!(is_sensitive("API_KEY", &patterns));
+        assert!(is_sensitive("CLIENT_SECRET_ID", &patterns));
+        assert!(!is_sensitive("PASSWORD_FILE", &patterns));
+        assert!(!is_sensitive("MYSQL_USER", &patterns));
+        assert!(!is_sensitive("MYSQL_PASSWORD", &[]));
+    }
+
+    #[test]
+    fn expect_to_split_environment() {
+        let dk = DockerService {
+            name: "mysql".to_owned(),
+            labels: to_map(&[("tier", "db")]),
+            environment: to_pairs(&[("MYSQL_USER", "mouse"), ("MYSQL_PASSWORD", "cat")]),
+            ..Default::default()
+        };
+
+        let (config_map, secret) = get_environment(&dk, &["*_PASSWORD".to_owned()], &PathBuf::from("mysql"));
+        let config_map = config_map.unwrap();
+        let secret = secret.unwrap();
+
+        assert_eq!(config_map.name, "mysql-env");
+        assert_eq!(config_map.path, PathBuf::from("mysql/configmap.yaml"));
+        assert_eq!(config_map.data, to_map(&[("MYSQL_USER", "mouse")]));
+        assert_eq!(config_map.labels, to_labels("mysql", "mysql", &[("tier", "db")]));
+        assert_eq!(secret.labels, config_map.labels);
+        assert_eq!(secret.path, PathBuf::from("mysql/secret.yaml"));
+        assert_eq!(secret.data, to_map(&[("MYSQL_PASSWORD", "cat")]));
+
+        let vars = secret.get_secret_env_vars(&dk.environment);
+        assert_eq!(vars[0].name, "MYSQL_PASSWORD");
+        assert_eq!(vars[0].secret, "mysql-env");
+    }
+
+    #[test]
+    fn expect_secret_env_vars_to_keep_order() {
+        let dk = DockerService {
+            name: "mysql".to_owned(),
+            environment: to_pairs(&[("Z_PASSWORD", "cat"), ("A_PASSWORD", "$(Z_PASSWORD)")]),
+            ..Default::default()
+        };
+
+        let (_, secret) = get_environment(&dk, &["*_PASSWORD".to_owned()], &PathBuf::new());
+        let vars = secret.unwrap().get_secret_env_vars(&dk.environment);
+        assert_eq!(vars[0].name, "Z_PASSWORD");
+        assert_eq!(vars[1].name, "A_PASSWORD");
+    }
+
+    #[test]
+    fn expect_to_not_create_empty_objects() {
+        let dk = DockerService {
+            name: "front".to_owned(),
+            ..Default::default()
+        };
+
+        let (config_map, secret) = get_environment(&dk, &["*_PASSWORD".to_owned()], &PathBuf::new());
+        assert!(config_map.is_none());
+        assert!(secret.is_none());
+    }
+}
+
+#[cfg(test)]
+mod secret {
+    use std::collections::{BTreeMap, HashMap};
+    use crate::kubernetes::controllers::secret::{KubeFileObject, get_file_objects, get_file_volumes, get_fs_group};
+    use crate::tests::to_map;
+    use crate::docker::secret::{DockerFileObject, DockerFileReference, FileKind};
+
+    // method use to get a top-level secret or config
+    fn setup(name: &str, kind: FileKind, data: &[u8]) -> DockerFileObject {
+        DockerFileObject {
+            name: name.to_owned(),
+            kind,
+            file: None,
+            data: data.to_vec(),
+            external: false,
+            external_name: None
+        }
+    }
+
+    // method use to get a reference to a file
+    fn reference(source: &str, target: &str) -> DockerFileReference {
+        DockerFileReference {
+            source: source.to_owned(),
+            target: target.to_owned(),
+            uid: None,
+            gid: None,
+            mode: None,
+            external_name: None
+        }
+    }
+
+    #[test]
+    fn expect_secret_to_be_encoded() {
+        let obj = KubeFileObject::new(&setup("db_password", FileKind::Secret, b"big_cat"), &BTreeMap::new());
+
+        assert_eq!(obj.name, "db-password");
+        assert_eq!(obj.get_filename(), "db-password-secret.yaml");
+        assert_eq!(obj.binary_data.get("db_password").unwrap(), "YmlnX2NhdA==");
+        assert!(obj.data.is_empty());
+    }
+
+    #[test]
+    fn expect_config_to_be_embedded() {
+        let text = KubeFileObject::new(&setup("nginx.conf", FileKind::Config, b"worker_processes 1;"), &BTreeMap::new());
+        let binary = KubeFileObject::new(&setup("logo", FileKind::Config, &[0xff, 0xfe]), &BTreeMap::new());
+
+        assert_eq!(text.name, "nginx-conf");
+        assert_eq!(text.get_filename(), "nginx-conf-configmap.yaml");
+        assert_eq!(text.data.get("nginx.conf").unwrap(), "worker_processes 1;");
+        assert_eq!(binary.binary_data.get("logo").unwrap(), "//4=");
+    }
+
+    #[test]
+    fn expect_to_skip_external_objects() {
+        let mut external = setup("tls", FileKind::Secret, b"");
+        external.external = true;
+
+        let objects = get_file_objects(&[external, setup("conf", FileKind::Config, b"a")], "shop");
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].name, "conf");
+        assert_eq!(objects[0].labels, to_map(&[("app.kubernetes.io/instance", "shop"), ("app.kubernetes.io/managed-by", "capoomobi")]));
+    }
+
+    #[test]
+    fn expect_to_mount_files() {
+        let mut secret = reference("db_password", "/run/secrets/db_password");
+        secret.mode = Some(0o400);
+        let mut external = reference("tls", "/run/secrets/tls");
+        external.external_name = Some("web-tls".to_owned());
+        external.mode = Some(0o440);
+        let config = reference("app_config", "/etc/app.conf");
+        let again = reference("app_config", "/etc/other.conf");
+
+        let mut keyed = reference("ca", "/run/secrets/ca.crt");
+        keyed.external_name = Some("web-ca".to_owned());
+        let keys: HashMap<String, String> = vec![("ca".to_owned(), "ca.crt".to_owned())].into_iter().collect();
+
+        let (volumes, mounts) = get_file_volumes(&[secret, external, keyed], &[config, again], &keys);
+
+        assert_eq!(volumes.len(), 5);
+        assert_eq!(volumes[0].name, "secret-db-password");
+        assert_eq!(volumes[0].secret, Some("db-password".to_owned()));
+        assert_eq!(volumes[0].items[0].key, "db_password");
+        assert_eq!(volumes[0].items[0].mode, Some(0o400));
+        assert!(volumes[0].default_mode.is_none());
+        // an external object is read from the key named after the compose file
+        assert_eq!(volumes[1].secret, Some("web-tls".to_owned()));
+        assert_eq!(volumes[1].items[0].key, "tls");
+        assert_eq!(volumes[1].items[0].path, "tls");
+        assert_eq!(volumes[1].items[0].mode, Some(0o440));
+        assert_eq!(mounts[1].sub_path, Some("tls".to_owned()));
+        // or from the key set in the confiture
+        assert_eq!(volumes[2].secret, Some("web-ca".to_owned()));
+        assert_eq!(volumes[2].items[0].key, "ca.crt");
+        assert_eq!(volumes[2].items[0].path, "ca");
+        assert_eq!(mounts[2].path, "/run/secrets/ca.crt");
+        assert_eq!(mounts[2].sub_path, Some("ca".to_owned()));
+        assert_eq!(volumes[3].config_map, Some("app-config".to_owned()));
+        assert_eq!(volumes[4].name, "config-app-config-1");
+
+        assert_eq!(mounts[0].path, "/run/secrets/db_password");
+        assert_eq!(mounts[0].sub_path, Some("db_password".to_owned()));
+        assert!(mounts[0].read_only);
+        assert_eq!(mounts[4].name, "config-app-config-1");
+        assert_eq!(mounts[4].path, "/etc/other.conf");
+    }
+
+    #[test]
+    fn expect_to_get_fs_group() {
+        let mut secret = reference("db_password", "/run/secrets/db_password");
+        secret.uid = Some("103".to_owned());
+        secret.gid = Some("103".to_owned());
+        let mut config = reference("app_config", "/etc/app.conf");
+        config.gid = Some("103".to_owned());
+
+        assert_eq!(get_fs_group("web", &[secret.clone()], &[config.clone()]), Some(103));
+        assert!(get_fs_group("web", &[], &[reference("app_config", "/etc/app.conf")]).is_none());
+
+        // a single group is applied to every volume of the pod
+        config.gid = Some("104".to_owned());
+        assert_eq!(get_fs_group("web", &[secret], &[config.clone()]), Some(103));
+
+        config.gid = Some("www-data".to_owned());
+        assert!(get_fs_group("web", &[], &[config]).is_none());
+    }
+}
+
+#[cfg(test)]
+mod security {
+    use std::path::PathBuf;
+    use crate::kubernetes::controllers::controller::KubeController;
+    use crate::kubernetes::controllers::security::{KubeSecurityContext, ProfileKind, get_security_context};
+    use crate::docker::parser::DockerService;
+    use crate::docker::security::DockerSecurity;
+    use crate::confiture::config::ConfigDeployment;
+
+    #[test]
+    fn expect_to_parse_user_and_group() {
+        let ctx = get_security_context("web", Some("1000:2000"), &DockerSecurity::default()).unwrap();
+        assert_eq!(ctx, KubeSecurityContext { run_as_user: Some(1000), run_as_group: Some(2000), ..Default::default() });
+
+        let ctx = get_security_context("web", Some("0"), &DockerSecurity::default()).unwrap();
+        assert_eq!(ctx.run_as_user, Some(0));
+        assert!(ctx.run_as_group.is_none());
+    }
+
+    #[test]
+    fn expect_user_name_to_be_skipped() {
+        assert!(get_security_context("web", Some("postgres"), &DockerSecurity::default()).is_none());
+        assert!(get_security_context("web", None, &DockerSecurity::default()).is_none());
+
+        let ctx = get_security_context("web", Some("postgres:999"), &DockerSecurity::default()).unwrap();
+        assert!(ctx.run_as_user.is_none());
+        assert_eq!(ctx.run_as_group, Some(999));
+    }
+
+    #[test]
+    fn expect_to_map_security_options() {
+        let security = DockerSecurity {
+            privileged: true,
+            read_only: true,
+            cap_add: vec!["NET_ADMIN".to_owned()],
+            security_opt: vec![
+                "no-new-privileges".to_owned(),
+                "seccomp=profiles/audit.json".to_owned(),
+                "apparmor:unconfined".to_owned(),
+                "label:type:svirt_apache_t".to_owned(),
+                "label:level:s0:c100,c200".to_owned(),
+                "systempaths=unconfined".to_owned()
+            ],
+            ..Default::default()
+        };
+
+        let ctx = get_security_context("web", None, &security).unwrap();
+        assert!(ctx.privileged);
+        assert!(ctx.read_only_root_filesystem);
+        assert_eq!(ctx.allow_privilege_escalation, Some(false));
+        assert_eq!(ctx.capabilities.unwrap().add, vec!["NET_ADMIN"]);
+
+        let seccomp = ctx.seccomp_profile.unwrap();
+        assert_eq!(seccomp.kind, ProfileKind::Localhost);
+        assert_eq!(seccomp.localhost_profile, Some("profiles/audit.json".to_owned()));
+        assert_eq!(ctx.app_armor_profile.unwrap().kind, ProfileKind::Unconfined);
+
+        let selinux = ctx.se_linux_options.unwrap();
+        assert_eq!(selinux.kind, Some("svirt_apache_t".to_owned()));
+        assert_eq!(selinux.level, Some("s0:c100,c200".to_owned()));
+        assert!(selinux.user.is_none());
+    }
+
+    #[test]
+    fn expect_host_seccomp_profile_to_be_skipped() {
+        for opt in &["seccomp:/path/profile.json", "seccomp=./profile.json", "seccomp:../profile.json"] {
+            let security = DockerSecurity {
+                security_opt: vec![opt.to_string()],
+                ..Default::default()
+            };
+
+            assert!(get_security_context("web", None, &security).is_none());
+        }
+
+        let security = DockerSecurity {
+            security_opt: vec!["seccomp:/path/profile.json".to_owned(), "apparmor:docker-default".to_owned()],
+            ..Default::default()
+        };
+
+        let ctx = get_security_context("web", None, &security).unwrap();
+        assert!(ctx.seccomp_profile.is_none());
+        assert_eq!(ctx.app_armor_profile.unwrap().localhost_profile, Some("docker-default".to_owned()));
+    }
+
+    #[test]
+    fn expect_entrypoint_to_be_the_command() {
+        let dk = DockerService {
+            name: "api".to_owned(),
+            image: "node".to_owned(),
+            entrypoint: vec!["node".to_owned()],
+            command: vec!["server.js".to_owned()],
+            working_dir: Some("/app".to_owned()),
+            user: Some("1000".to_owned()),
+            tty: true,
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(1),
+            ..Default::default()
+        };
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        assert_eq!(ctrl.command, vec!["node"]);
+        assert_eq!(ctrl.args, vec!["server.js"]);
+        assert_eq!(ctrl.working_dir, Some("/app".to_owned()));
+        assert_eq!(ctrl.security_context.unwrap().run_as_user, Some(1000));
+        assert!(ctrl.tty);
+        assert!(!ctrl.stdin);
+    }
+}
+
+#[cfg(test)]
+mod host {
+    use crate::kubernetes::controllers::host::{get_host_aliases, get_dns_config, get_dns_policy, get_hostname};
+    use crate::docker::host::{DockerHost, DockerExtraHost};
+
+    #[test]
+    fn expect_to_group_host_aliases_by_ip() {
+        let hosts = vec![
+            DockerExtraHost { hostname: "db".to_owned(), ip: "10.0.0.1".to_owned() },
+            DockerExtraHost { hostname: "cache".to_owned(), ip: "10.0.0.1".to_owned() },
+            DockerExtraHost { hostname: "gateway".to_owned(), ip: "host-gateway".to_owned() },
+            DockerExtraHost { hostname: "local".to_owned(), ip: "::1".to_owned() }
+        ];
+
+        let aliases = get_host_aliases("web", &hosts);
+        assert_eq!(aliases.len(), 2);
+        assert_eq!(aliases[0].hostnames, vec!["db", "cache"]);
+        assert_eq!(aliases[1].ip, "::1");
+    }
+
+    #[test]
+    fn expect_to_retrieve_dns_config_and_policy() {
+        let host = DockerHost {
+            dns: vec!["1.1.1.1".to_owned()],
+            dns_opt: vec!["ndots:2".to_owned(), "rotate".to_owned()],
+            ..Default::default()
+        };
+
+        let config = get_dns_config(&host).unwrap();
+        assert_eq!(config.nameservers, vec!["1.1.1.1"]);
+        assert_eq!(config.options[0].value, Some("2".to_owned()));
+        assert!(config.options[1].value.is_none());
+        // the cluster DNS is kept unless the confiture replaces it
+        assert!(get_dns_policy(&host, false, false).is_none());
+        assert_eq!(get_dns_policy(&host, true, false), Some("ClusterFirstWithHostNet".to_owned()));
+        assert_eq!(get_dns_policy(&host, true, true), Some("None".to_owned()));
+
+        let host = DockerHost::default();
+        assert!(get_dns_config(&host).is_none());
+        assert!(get_dns_policy(&host, false, true).is_none());
+        assert_eq!(get_dns_policy(&host, true, false), Some("ClusterFirstWithHostNet".to_owned()));
+    }
+
+    #[test]
+    fn expect_to_split_hostname() {
+        let host = DockerHost {
+            hostname: Some("db.internal.example.com".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(get_hostname(&host), (Some("db".to_owned()), Some("internal".to_owned())));
+
+        let host = DockerHost {
+            hostname: Some("API".to_owned()),
+            domainname: Some("backend.local".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(get_hostname(&host), (Some("api".to_owned()), Some("backend".to_owned())));
+    }
+}
+
+#[cfg(test)]
+mod rollout {
+    use crate::kubernetes::controllers::controller::IntOrString;
+    use crate::kubernetes::controllers::rollout::{
+        DeploymentStrategy,
+        DeploymentStrategyKind,
+        get_deployment_strategy,
+        get_min_ready_seconds,
+        get_pre_stop
+    };
+    use crate::docker::deploy::{DockerDeploy, DockerUpdateConfig, UpdateOrder};
+
+    #[test]
+    fn expect_to_translate_update_config() {
+        let mut deploy = DockerDeploy {
+            update_config: Some(DockerUpdateConfig {
+                parallelism: Some(2),
+                delay: Some(5),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let strategy = get_deployment_strategy("api", &deploy, None).unwrap();
+        assert_eq!(strategy.kind, DeploymentStrategyKind::RollingUpdate);
+        assert_eq!(strategy.max_surge, Some(IntOrString::Int(0)));
+        assert_eq!(strategy.max_unavailable, Some(IntOrString::Int(2)));
+        assert_eq!(get_min_ready_seconds(&deploy), Some(5));
+
+        // the rollback_config is used when there isn't any update_config
+        deploy.rollback_config = deploy.update_config.take().map(|c| DockerUpdateConfig {
+            parallelism: Some(0),
+            order: UpdateOrder::StartFirst,
+            ..c
+        });
+
+        let strategy = get_deployment_strategy("api", &deploy, None).unwrap();
+        assert_eq!(strategy.max_surge, Some(IntOrString::Str("100%".to_owned())));
+        assert_eq!(strategy.max_unavailable, Some(IntOrString::Int(0)));
+    }
+
+    #[test]
+    fn expect_confiture_strategy_to_take_precedence() {
+        let deploy = DockerDeploy {
+            update_config: Some(DockerUpdateConfig::default()),
+            ..Default::default()
+        };
+
+        let option = DeploymentStrategy {
+            kind: DeploymentStrategyKind::Recreate,
+            max_surge: None,
+            max_unavailable: None
+        };
+
+        assert_eq!(get_deployment_strategy("api", &deploy, Some(&option)), Some(option));
+        assert!(get_deployment_strategy("api", &DockerDeploy::default(), None).is_none());
+    }
+
+    #[test]
+    fn expect_to_send_stop_signal_in_pre_stop() {
+        assert_eq!(get_pre_stop(Some("SIGQUIT")), vec!["/bin/sh", "-c", "kill -QUIT 1"]);
+        assert_eq!(get_pre_stop(Some("9")), vec!["/bin/sh", "-c", "kill -9 1"]);
+        assert!(get_pre_stop(Some("SIGTERM")).is_empty());
+        assert!(get_pre_stop(None).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod placement {
+    use std::collections::BTreeMap;
+    use crate::kubernetes::controllers::placement::{
+        AffinityKind,
+        NodeOperator,
+        PodAntiAffinity,
+        TopologySpreadConstraint,
+        UnsatisfiableAction,
+        get_node_placement,
+        get_affinity,
+        get_topology_spread_constraints
+    };
+    use crate::docker::deploy::{DockerPlacement, DockerConstraint, ConstraintOperator};
+    use crate::tests::to_map;
+
+    // method use to create a constraint
+    fn constraint(field: &str, operator: ConstraintOperator, value: &str) -> DockerConstraint {
+        DockerConstraint {
+            field: field.to_owned(),
+            operator,
+            value: value.to_owned()
+        }
+    }
+
+    #[test]
+    fn expect_to_translate_constraints() {
+        let constraints = vec![
+            constraint("node.labels.zone", ConstraintOperator::Equal, "eu-1"),
+            constraint("node.labels.zone", ConstraintOperator::Equal, "eu-2"),
+            constraint("node.hostname", ConstraintOperator::NotEqual, "node-1"),
+            constraint("node.hostname", ConstraintOperator::NotEqual, "node-2"),
+            constraint("node.role", ConstraintOperator::Equal, "manager"),
+            constraint("node.platform.arch", ConstraintOperator::Equal, "aarch64"),
+            constraint("node.id", ConstraintOperator::Equal, "2ivku8v2gvtg4")
+        ];
+
+        let (selector, required) = get_node_placement("api", &constraints);
+        assert_eq!(selector, to_map(&[("zone", "eu-1"), ("kubernetes.io/arch", "arm64")]));
+        assert_eq!(required.len(), 3);
+        assert_eq!(required[0].operator, NodeOperator::In);
+        assert_eq!(required[0].values, vec!["eu-2"]);
+        assert_eq!(required[1].key, "kubernetes.io/hostname");
+        assert_eq!(required[1].operator, NodeOperator::NotIn);
+        assert_eq!(required[1].values, vec!["node-1", "node-2"]);
+        assert_eq!(required[2].key, "node-role.kubernetes.io/control-plane");
+        assert_eq!(required[2].operator, NodeOperator::Exists);
+    }
+
+    #[test]
+    fn expect_to_retrieve_anti_affinity() {
+        let selector = to_map(&[("app", "api")]);
+        let placement = DockerPlacement {
+            max_replicas_per_node: Some(1),
+            ..Default::default()
+        };
+
+        let affinity = get_affinity("api", &placement, Vec::new(), None, &selector).unwrap();
+        let anti_affinity = affinity.pod_anti_affinity.unwrap();
+        assert_eq!(anti_affinity.kind, AffinityKind::Required);
+        assert_eq!(anti_affinity.topology_key, "kubernetes.io/hostname");
+        assert_eq!(anti_affinity.selector, selector);
+
+        let option = PodAntiAffinity {
+            kind: AffinityKind::Preferred,
+            topology_key: "zone".to_owned(),
+            selector: BTreeMap::new()
+        };
+        let affinity = get_affinity("api", &placement, Vec::new(), Some(&option), &selector).unwrap();
+        assert_eq!(affinity.pod_anti_affinity.unwrap().kind, AffinityKind::Preferred);
+        assert!(get_affinity("api", &DockerPlacement::default(), Vec::new(), None, &selector).is_none());
+    }
+
+    #[test]
+    fn expect_to_spread_pods_over_preferences() {
+        let selector = to_map(&[("app", "api")]);
+        let placement = DockerPlacement {
+            preferences: vec!["node.labels.zone".to_owned(), "node.labels.rack".to_owned(), "engine.labels.os".to_owned()],
+            ..Default::default()
+        };
+
+        let options = vec![TopologySpreadConstraint {
+            max_skew: 2,
+            topology_key: "rack".to_owned(),
+            when_unsatisfiable: UnsatisfiableAction::DoNotSchedule,
+            selector: BTreeMap::new()
+        }];
+
+        let constraints = get_topology_spread_constraints("api", &placement, &options, &selector);
+        assert_eq!(constraints.len(), 2);
+        assert_eq!(constraints[0].topology_key, "zone");
+        assert_eq!(constraints[0].when_unsatisfiable, UnsatisfiableAction::ScheduleAnyway);
+        assert_eq!(constraints[1].max_skew, 2);
+        assert_eq!(constraints[1].selector, selector);
+    }
+}
+
+#[cfg(test)]
+mod labels {
+    use crate::kubernetes::controllers::labels::{get_selector, get_labels};
+    use crate::tests::{to_map, to_selector, to_labels};
+
+    #[test]
+    fn expect_to_select_by_name_and_project() {
+        assert_eq!(get_selector("api", "shop"), to_selector("api", "shop"));
+        // the service is its own instance without a compose project
+        assert_eq!(get_selector("api", ""), to_selector("api", "api"));
+    }
+
+    #[test]
+    fn expect_to_convert_name_to_label_value() {
+        let name = format!("{}_worker", "a".repeat(60));
+        let selector = get_selector(&name, "shop");
+
+        assert_eq!(selector["app.kubernetes.io/name"], format!("{}_wo", "a".repeat(60)));
+        assert_eq!(get_selector("api.", "shop")["app.kubernetes.io/name"], "api");
+    }
+
+    #[test]
+    fn expect_reserved_labels_to_take_precedence() {
+        let selector = to_selector("api", "shop");
+        let labels = to_map(&[("tier", "back"), ("app.kubernetes.io/name", "front")]);
+
+        assert_eq!(get_labels("api", &labels, &selector), to_labels("api", "shop", &[("tier", "back")]));
+    }
+
+    #[test]
+    fn expect_invalid_labels_to_be_skipped() {
+        let selector = to_selector("api", "shop");
+        let labels = to_map(&[
+            ("com.example.description", "Accounting webapp"),
+            ("com.example/tier", "back"),
+            ("com.example/", "empty"),
+            ("Com.Example/tier", "back"),
+            ("version", ""),
+            ("long", &"a".repeat(64))
+        ]);
+
+        let expected = to_labels("api", "shop", &[("com.example/tier", "back"), ("version", "")]);
+        assert_eq!(get_labels("api", &labels, &selector), expected);
+    }
+}
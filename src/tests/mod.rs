@@ -5,3 +5,50 @@ mod kube;
 mod fs;
 mod generator;
 mod templating;
+
+#[cfg(test)]
+use std::collections::BTreeMap;
+#[cfg(test)]
+use yaml_rust::Yaml;
+#[cfg(test)]
+use crate::docker::port::{DockerPort, parse_ports};
+
+// Build a BTreeMap from a list of key/value slices
+#[cfg(test)]
+fn to_map(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+// Build a list of DockerPort from a list of compose short syntax ports
+#[cfg(test)]
+fn to_ports(ports: &[&str]) -> Vec<DockerPort> {
+    let yaml = Yaml::Array(ports.iter().map(|p| Yaml::String(p.to_string())).collect());
+    parse_ports(&yaml)
+}
+
+// Build the labels selecting the pods of a service
+#[cfg(test)]
+fn to_selector(name: &str, instance: &str) -> BTreeMap<String, String> {
+    to_map(&[("app.kubernetes.io/name", name), ("app.kubernetes.io/instance", instance)])
+}
+
+// Build the labels of an object of a service from the labels of the compose service
+#[cfg(test)]
+fn to_labels(name: &str, instance: &str, labels: &[(&str, &str)]) -> BTreeMap<String, String> {
+    let mut map = to_map(labels);
+    map.extend(to_selector(name, instance));
+    map.insert("app.kubernetes.io/managed-by".to_owned(), "capoomobi".to_owned());
+    map
+}
+
+// Build an ordered list of key/value pairs (e.g: environment)
+#[cfg(test)]
+fn to_pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
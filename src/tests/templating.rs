@@ -3,6 +3,7 @@ mod controller {
     use std::collections::HashMap;
     use yaml_rust::{YamlLoader, Yaml};
     use crate::docker::parser::DockerService;
+    use crate::tests::{to_map, to_ports, to_pairs};
     use crate::kubernetes::builder::{get_basic_objects};
     use crate::kubernetes::controllers::controller::ControllerKind;
     use crate::kubernetes::controllers::service::ServiceType;
@@ -20,22 +21,26 @@ mod controller {
         let dk = DockerService {
             name       : "nginx".to_owned(),
             image      : "nginx:1.3.0".to_owned(),
-            commands   : vec!["sudo nginx reload".to_owned()],
-            ports      : vec!["9000:9000".to_owned()],
-            labels     : vec!["app=front".to_owned(), "tier=front".to_owned()],
-            environment: vec!["API_ENV=dev".to_owned()],
-            volumes    : vec![]
+            command    : vec!["sudo nginx reload".to_owned()],
+            ports      : to_ports(&["9000:9000"]),
+            labels     : to_map(&[("app", "front"), ("tier", "front")]),
+            environment: to_pairs(&[("API_ENV", "dev")]),
+            volumes    : vec![],
+            ..Default::default()
         };
 
         let conf = ConfigConfiture {
             name: "nginx".to_owned(),
+            skip: false,
             deployment: ConfigDeployment {
-                replicas: 3,
-                controller: ControllerKind::Deployment
+                replicas: Some(3),
+                controller: Some(ControllerKind::Deployment),
+                ..Default::default()
             },
             service: ConfigService {
                 kind: ServiceType::NodePort,
-                nodeport: 30320
+                nodeport: 30320,
+                ports: vec![]
             }
         };
 
@@ -43,7 +48,7 @@ mod controller {
         map.insert("nginx".to_owned(), &conf);
 
         // build kubes objects
-        let kubes = get_basic_objects(&[dk], map);
+        let kubes = get_basic_objects(&[dk], map, &[], &[]);
         let builder = ControllerTmplBuilder{};
 
         // render the template
@@ -119,21 +124,20 @@ mod controller {
             .as_hash()
             .unwrap();
 
-        let app_label = match_labels
-            .get(&Yaml::from_str("app"))
+        let name_label = match_labels
+            .get(&Yaml::from_str("app.kubernetes.io/name"))
             .unwrap()
             .as_str()
             .unwrap();
 
-        let tier_label = match_labels
-            .get(&Yaml::from_str("tier"))
-            .unwrap()
-            .as_str()
-            .unwrap();
+        // the labels of the compose service are only set on the pods
+        let pod_labels = &raw["spec"]["template"]["metadata"]["labels"];
 
         assert_eq!(replicas, 3);
-        assert_eq!(app_label, "front");
-        assert_eq!(tier_label, "front");
+        assert_eq!(name_label, "nginx");
+        assert!(match_labels.get(&Yaml::from_str("tier")).is_none());
+        assert_eq!(pod_labels["app.kubernetes.io/name"].as_str().unwrap(), "nginx");
+        assert_eq!(pod_labels["tier"].as_str().unwrap(), "front");
     }
 
     #[test]
@@ -197,6 +201,19 @@ mod controller {
         assert_eq!(image, "nginx:1.3.0");
         assert_eq!(container_port, 9000);
     }
+
+    #[test]
+    fn expect_to_render_env() {
+        let template = setup();
+        let resources = YamlLoader::load_from_str(&template).unwrap();
+        let raw = &resources[0];
+
+        let container = &raw["spec"]["template"]["spec"]["containers"][0];
+        let env_from = container["envFrom"].as_vec().unwrap();
+
+        assert_eq!(env_from[0]["configMapRef"]["name"].as_str().unwrap(), "nginx-env");
+        assert!(container["env"].is_badvalue());
+    }
 }
 
 #[cfg(test)]
@@ -204,6 +221,7 @@ mod services {
     use std::collections::HashMap;
     use yaml_rust::{YamlLoader, Yaml};
     use crate::docker::parser::DockerService;
+    use crate::tests::{to_map, to_ports, to_pairs};
     use crate::kubernetes::builder::{get_basic_objects};
     use crate::kubernetes::controllers::controller::ControllerKind;
     use crate::kubernetes::controllers::service::ServiceType;
@@ -221,28 +239,32 @@ mod services {
         let dk = DockerService {
             name       : "nginx".to_owned(),
             image      : "nginx:1.3.0".to_owned(),
-            commands   : vec!["sudo nginx reload".to_owned()],
-            ports      : vec!["9000:9000".to_owned()],
-            labels     : vec!["app=front".to_owned(), "tier=front".to_owned()],
-            environment: vec!["API_ENV=dev".to_owned()],
-            volumes    : vec![]
+            command    : vec!["sudo nginx reload".to_owned()],
+            ports      : to_ports(&["9000:9000", "9100"]),
+            labels     : to_map(&[("app", "front"), ("tier", "front")]),
+            environment: to_pairs(&[("API_ENV", "dev")]),
+            volumes    : vec![],
+            ..Default::default()
         };
 
         let conf = ConfigConfiture {
             name: "nginx".to_owned(),
+            skip: false,
             deployment: ConfigDeployment {
-                replicas: 3,
-                controller: ControllerKind::Deployment
+                replicas: Some(3),
+                controller: Some(ControllerKind::Deployment),
+                ..Default::default()
             },
             service: ConfigService {
                 kind: ServiceType::NodePort,
-                nodeport: 30320
+                nodeport: 30320,
+                ports: vec![]
             }
         };
 
         let mut map_config: HashMap<String, &ConfigConfiture> = HashMap::new();
         map_config.insert("nginx".to_owned(), &conf);
-        let kubes = get_basic_objects(&[dk], map_config);
+        let kubes = get_basic_objects(&[dk], map_config, &[], &[]);
 
         // init the service template builder
         let builder = ServiceTmplBuilder{};
@@ -329,21 +351,16 @@ mod services {
             .as_hash()
             .unwrap();
 
-        let app = selector
-            .get(&Yaml::from_str("app"))
-            .unwrap()
-            .as_str()
-            .unwrap();
-
-        let tier = selector
-            .get(&Yaml::from_str("tier"))
+        let name = selector
+            .get(&Yaml::from_str("app.kubernetes.io/name"))
             .unwrap()
             .as_str()
             .unwrap();
 
         assert_eq!(kind, "NodePort");
-        assert_eq!(app, "front");
-        assert_eq!(tier, "front");
+        assert_eq!(name, "nginx");
+        assert!(selector.get(&Yaml::from_str("tier")).is_none());
+        assert_eq!(service["metadata"]["labels"]["tier"].as_str().unwrap(), "front");
     }
 
     #[test]
@@ -390,12 +407,27 @@ mod services {
         assert_eq!(target_port, 9000);
         assert_eq!(nodeport, 30320);
     }
+
+    #[test]
+    fn expect_to_render_every_ports() {
+        let service_str = setup();
+        let yaml = YamlLoader::load_from_str(&service_str).unwrap();
+        let ports = yaml[0]["spec"]["ports"].as_vec().unwrap();
+
+        assert_eq!(ports.len(), 2);
+        assert_eq!(ports[0]["name"].as_str().unwrap(), "tcp-9000");
+        assert_eq!(ports[1]["name"].as_str().unwrap(), "tcp-9100");
+        assert_eq!(ports[1]["port"].as_i64().unwrap(), 9100);
+        assert_eq!(ports[1]["targetPort"].as_i64().unwrap(), 9100);
+        assert!(ports[1]["nodePort"].is_badvalue());
+    }
 }
 
 #[cfg(test)]
 mod ingress {
     use yaml_rust::{Yaml, YamlLoader};
     use crate::docker::parser::DockerService;
+    use crate::tests::{to_map, to_ports, to_pairs};
     use crate::kubernetes::controllers::ingress::KubeIngress;
     use crate::confiture::config::{ConfigIngress, ConfigIngressService};
     use crate::kubernetes::template::ingress::IngressTmplBuilder;
@@ -406,11 +438,12 @@ mod ingress {
         let dk = DockerService {
             name       : "nginx".to_owned(),
             image      : "nginx:1.3.0".to_owned(),
-            commands   : vec!["sudo nginx reload".to_owned()],
-            ports      : vec!["9000:9000".to_owned()],
-            labels     : vec!["app=front".to_owned(), "tier=front".to_owned()],
-            environment: vec!["API_ENV=dev".to_owned()],
-            volumes    : vec![]
+            command    : vec!["sudo nginx reload".to_owned()],
+            ports      : to_ports(&["9000:9000"]),
+            labels     : to_map(&[("app", "front"), ("tier", "front")]),
+            environment: to_pairs(&[("API_ENV", "dev")]),
+            volumes    : vec![],
+            ..Default::default()
         };
 
         let config = ConfigIngress {
@@ -521,3 +554,955 @@ mod ingress {
         assert_eq!(service_port, 9000);
     }
 }
+
+#[cfg(test)]
+mod volume {
+    use yaml_rust::YamlLoader;
+    use crate::kubernetes::controllers::volume::{KubeVolumeClaim, AccessMode};
+    use crate::kubernetes::template::volume::VolumeClaimTmplBuilder;
+    use crate::kubernetes::io::output;
+    use crate::assets::loader::K8SAssetType;
+
+    fn setup() -> String {
+        let claim = KubeVolumeClaim {
+            name: "db-data".to_owned(),
+            size: "10Gi".to_owned(),
+            storage_class: Some("standard".to_owned()),
+            access_mode: AccessMode::ReadWriteMany
+        };
+
+        let builder = VolumeClaimTmplBuilder {};
+        output::render_component(&builder, &claim, K8SAssetType::VolumeClaim).unwrap()
+    }
+
+    #[test]
+    fn expect_to_render_volume_claim() {
+        let template = setup();
+        let yaml = YamlLoader::load_from_str(&template).unwrap();
+        let claim = &yaml[0];
+
+        assert_eq!(claim["kind"].as_str().unwrap(), "PersistentVolumeClaim");
+        assert_eq!(claim["metadata"]["name"].as_str().unwrap(), "db-data");
+        assert_eq!(claim["spec"]["accessModes"][0].as_str().unwrap(), "ReadWriteMany");
+        assert_eq!(claim["spec"]["storageClassName"].as_str().unwrap(), "standard");
+        assert_eq!(claim["spec"]["resources"]["requests"]["storage"].as_str().unwrap(), "10Gi");
+    }
+}
+
+#[cfg(test)]
+mod statefulset {
+    use std::collections::HashMap;
+    use yaml_rust::YamlLoader;
+    use crate::kubernetes::builder::{get_basic_objects};
+    use crate::kubernetes::controllers::controller::{ControllerKind};
+    use crate::kubernetes::template::controller::ControllerTmplBuilder;
+    use crate::kubernetes::template::service::ServiceTmplBuilder;
+    use crate::kubernetes::io::output;
+    use crate::assets::loader::K8SAssetType;
+    use crate::docker::parser::DockerService;
+    use crate::docker::volume::{DockerVolume, VolumeKind};
+    use crate::confiture::config::{ConfigConfiture, ConfigDeployment, ConfigService};
+    use crate::tests::to_ports;
+
+    fn setup() -> (String, String) {
+        let dk = DockerService {
+            name: "db".to_owned(),
+            image: "mysql:5.7".to_owned(),
+            command: vec![],
+            ports: to_ports(&["3306"]),
+            labels: Default::default(),
+            environment: Default::default(),
+            volumes: vec![
+                DockerVolume {
+                    kind: VolumeKind::Volume,
+                    source: "db-data".to_owned(),
+                    target: "/var/lib/mysql".to_owned(),
+                    read_only: false,
+                    external: false,
+                    ..Default::default()
+                }
+            ],
+            ..Default::default()
+        };
+
+        let conf = ConfigConfiture {
+            name: "db".to_owned(),
+            skip: false,
+            deployment: ConfigDeployment {
+                replicas: Some(3),
+                controller: Some(ControllerKind::StatefulSet),
+                ..Default::default()
+            },
+            service: ConfigService::default()
+        };
+
+        let mut map = HashMap::new();
+        map.insert("db".to_owned(), &conf);
+
+        let kubes = get_basic_objects(&[dk], map, &[], &[]);
+        let kube = &kubes[0];
+        let ctrl = output::render_component(&ControllerTmplBuilder {}, &kube.ctrl, K8SAssetType::Controller).unwrap();
+        let headless = output::render_component(&ServiceTmplBuilder {}, kube.headless.as_ref().unwrap(), K8SAssetType::Service).unwrap();
+
+        (ctrl, headless)
+    }
+
+    #[test]
+    fn expect_to_render_stateful_spec() {
+        let (ctrl, _) = setup();
+        let yaml = YamlLoader::load_from_str(&ctrl).unwrap();
+        let spec = &yaml[0]["spec"];
+
+        assert_eq!(yaml[0]["kind"].as_str().unwrap(), "StatefulSet");
+        assert_eq!(spec["serviceName"].as_str().unwrap(), "db-headless");
+        assert_eq!(spec["podManagementPolicy"].as_str().unwrap(), "OrderedReady");
+        assert!(spec["template"]["spec"]["volumes"].is_badvalue());
+
+        let claim = &spec["volumeClaimTemplates"][0];
+        assert_eq!(claim["metadata"]["name"].as_str().unwrap(), "db-data");
+        assert_eq!(claim["spec"]["accessModes"][0].as_str().unwrap(), "ReadWriteOnce");
+        assert_eq!(claim["spec"]["resources"]["requests"]["storage"].as_str().unwrap(), "1Gi");
+    }
+
+    #[test]
+    fn expect_to_render_headless_service() {
+        let (_, headless) = setup();
+        let yaml = YamlLoader::load_from_str(&headless).unwrap();
+        let svc = &yaml[0];
+
+        assert_eq!(svc["metadata"]["name"].as_str().unwrap(), "db-headless");
+        assert_eq!(svc["spec"]["clusterIP"].as_str().unwrap(), "None");
+        assert_eq!(svc["spec"]["ports"][0]["port"].as_i64().unwrap(), 3306);
+    }
+}
+
+#[cfg(test)]
+mod daemonset {
+    use std::path::PathBuf;
+    use yaml_rust::{YamlLoader, Yaml};
+    use crate::kubernetes::controllers::controller::{
+        KubeController,
+        ControllerKind,
+        Toleration,
+        TolerationOperator,
+        TaintEffect
+    };
+    use crate::kubernetes::template::controller::ControllerTmplBuilder;
+    use crate::kubernetes::io::output;
+    use crate::assets::loader::K8SAssetType;
+    use crate::docker::parser::DockerService;
+    use crate::confiture::config::ConfigDeployment;
+
+    fn render(kind: ControllerKind) -> Yaml {
+        let dk = DockerService {
+            name: "agent".to_owned(),
+            image: "prom/node-exporter".to_owned(),
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(2),
+            controller: Some(kind),
+            tolerations: vec![
+                Toleration {
+                    key: Some("dedicated".to_owned()),
+                    operator: TolerationOperator::Equal,
+                    value: Some("monitoring".to_owned()),
+                    effect: Some(TaintEffect::NoExecute)
+                }
+            ],
+            ..Default::default()
+        };
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        let tmpl = output::render_component(&ControllerTmplBuilder {}, &ctrl, K8SAssetType::Controller).unwrap();
+        let mut yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+
+        yaml.remove(0)
+    }
+
+    #[test]
+    fn expect_daemonset_to_not_have_replicas() {
+        let yaml = render(ControllerKind::DaemonSet);
+        let spec = &yaml["spec"];
+
+        assert_eq!(yaml["kind"].as_str().unwrap(), "DaemonSet");
+        assert!(spec["replicas"].is_badvalue());
+        assert_eq!(spec["updateStrategy"]["type"].as_str().unwrap(), "RollingUpdate");
+        assert_eq!(spec["selector"]["matchLabels"]["app.kubernetes.io/name"].as_str().unwrap(), "agent");
+        assert_eq!(spec["template"]["metadata"]["labels"]["app.kubernetes.io/name"].as_str().unwrap(), "agent");
+    }
+
+    #[test]
+    fn expect_to_render_tolerations() {
+        let yaml = render(ControllerKind::DaemonSet);
+        let toleration = &yaml["spec"]["template"]["spec"]["tolerations"][0];
+
+        assert_eq!(toleration["key"].as_str().unwrap(), "dedicated");
+        assert_eq!(toleration["operator"].as_str().unwrap(), "Equal");
+        assert_eq!(toleration["value"].as_str().unwrap(), "monitoring");
+        assert_eq!(toleration["effect"].as_str().unwrap(), "NoExecute");
+    }
+
+    #[test]
+    fn expect_replicaset_to_have_replicas_and_selector() {
+        let yaml = render(ControllerKind::ReplicaSet);
+        let spec = &yaml["spec"];
+
+        assert_eq!(yaml["kind"].as_str().unwrap(), "ReplicaSet");
+        assert_eq!(spec["replicas"].as_i64().unwrap(), 2);
+        assert!(spec["updateStrategy"].is_badvalue());
+        assert_eq!(spec["selector"]["matchLabels"]["app.kubernetes.io/name"].as_str().unwrap(), "agent");
+    }
+}
+
+#[cfg(test)]
+mod job {
+    use std::path::PathBuf;
+    use yaml_rust::{YamlLoader, Yaml};
+    use crate::kubernetes::controllers::controller::{KubeController};
+    use crate::kubernetes::template::controller::ControllerTmplBuilder;
+    use crate::kubernetes::io::output;
+    use crate::docker::parser::DockerService;
+    use crate::docker::deploy::{DockerDeploy, RestartCondition};
+    use crate::confiture::config::{ConfigDeployment, ConfigJob};
+
+    fn render(schedule: Option<&str>) -> Yaml {
+        let dk = DockerService {
+            name: "report".to_owned(),
+            image: "busybox".to_owned(),
+            command: vec!["date".to_owned()],
+            deploy: DockerDeploy {
+                restart: RestartCondition::OnFailure,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(1),
+            job: Some(ConfigJob {
+                schedule: schedule.map(String::from),
+                backoff_limit: Some(0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        let asset = output::get_controller_asset(ctrl.ctrl);
+        let tmpl = output::render_component(&ControllerTmplBuilder {}, &ctrl, asset).unwrap();
+        let mut yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+
+        yaml.remove(0)
+    }
+
+    #[test]
+    fn expect_to_render_job() {
+        let yaml = render(None);
+        let spec = &yaml["spec"];
+        let pod = &spec["template"]["spec"];
+
+        assert_eq!(yaml["apiVersion"].as_str().unwrap(), "batch/v1");
+        assert_eq!(yaml["kind"].as_str().unwrap(), "Job");
+        assert_eq!(spec["backoffLimit"].as_i64().unwrap(), 0);
+        assert_eq!(spec["completions"].as_i64().unwrap(), 1);
+        assert!(spec["replicas"].is_badvalue());
+        assert_eq!(pod["restartPolicy"].as_str().unwrap(), "OnFailure");
+        assert_eq!(pod["containers"][0]["image"].as_str().unwrap(), "busybox");
+    }
+
+    #[test]
+    fn expect_to_render_cronjob() {
+        let yaml = render(Some("0 3 * * *"));
+        let spec = &yaml["spec"];
+        let job = &spec["jobTemplate"]["spec"];
+        let pod = &job["template"]["spec"];
+
+        assert_eq!(yaml["kind"].as_str().unwrap(), "CronJob");
+        assert_eq!(spec["schedule"].as_str().unwrap(), "0 3 * * *");
+        assert_eq!(spec["concurrencyPolicy"].as_str().unwrap(), "Allow");
+        assert_eq!(job["backoffLimit"].as_i64().unwrap(), 0);
+        assert_eq!(job["template"]["metadata"]["labels"]["app.kubernetes.io/name"].as_str().unwrap(), "report");
+        assert_eq!(pod["restartPolicy"].as_str().unwrap(), "OnFailure");
+        assert_eq!(pod["containers"][0]["name"].as_str().unwrap(), "report");
+        assert_eq!(pod["containers"][0]["args"][0].as_str().unwrap(), "date");
+    }
+}
+
+#[cfg(test)]
+mod probe {
+    use std::path::PathBuf;
+    use yaml_rust::YamlLoader;
+    use crate::kubernetes::controllers::controller::KubeController;
+    use crate::kubernetes::template::controller::ControllerTmplBuilder;
+    use crate::kubernetes::io::output;
+    use crate::assets::loader::K8SAssetType;
+    use crate::docker::parser::DockerService;
+    use crate::docker::healthcheck::{DockerHealthcheck, HealthcheckTest};
+    use crate::confiture::config::ConfigDeployment;
+
+    #[test]
+    fn expect_to_render_probes() {
+        let dk = DockerService {
+            name: "db".to_owned(),
+            image: "postgres".to_owned(),
+            healthcheck: Some(DockerHealthcheck {
+                test: HealthcheckTest::Shell("pg_isready -U postgres".to_owned()),
+                interval: Some(15),
+                timeout: None,
+                retries: Some(4),
+                start_period: Some(20)
+            }),
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(1),
+            ..Default::default()
+        };
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        let tmpl = output::render_component(&ControllerTmplBuilder {}, &ctrl, K8SAssetType::Controller).unwrap();
+        let yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+        let container = &yaml[0]["spec"]["template"]["spec"]["containers"][0];
+
+        let liveness = &container["livenessProbe"];
+        assert_eq!(liveness["exec"]["command"][0].as_str().unwrap(), "sh");
+        assert_eq!(liveness["exec"]["command"][2].as_str().unwrap(), "pg_isready -U postgres");
+        assert_eq!(liveness["periodSeconds"].as_i64().unwrap(), 15);
+        assert_eq!(liveness["failureThreshold"].as_i64().unwrap(), 4);
+        assert!(liveness["timeoutSeconds"].is_badvalue());
+        assert_eq!(container["readinessProbe"]["periodSeconds"].as_i64().unwrap(), 15);
+        assert_eq!(container["startupProbe"]["failureThreshold"].as_i64().unwrap(), 4);
+        assert_eq!(container["image"].as_str().unwrap(), "postgres");
+    }
+}
+
+#[cfg(test)]
+mod resources {
+    use std::path::PathBuf;
+    use yaml_rust::YamlLoader;
+    use crate::kubernetes::controllers::controller::KubeController;
+    use crate::kubernetes::template::controller::ControllerTmplBuilder;
+    use crate::kubernetes::io::output;
+    use crate::assets::loader::K8SAssetType;
+    use crate::docker::parser::DockerService;
+    use crate::docker::deploy::{DockerDeploy, DockerResources, DockerResource};
+    use crate::confiture::config::ConfigDeployment;
+
+    #[test]
+    fn expect_to_render_resources() {
+        let dk = DockerService {
+            name: "db".to_owned(),
+            image: "postgres".to_owned(),
+            deploy: DockerDeploy {
+                resources: DockerResources {
+                    limits: DockerResource {
+                        cpus: Some("1.5".to_owned()),
+                        memory: Some("2g".to_owned())
+                    },
+                    reservations: DockerResource {
+                        cpus: None,
+                        memory: Some("512m".to_owned())
+                    }
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(1),
+            ..Default::default()
+        };
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        let tmpl = output::render_component(&ControllerTmplBuilder {}, &ctrl, K8SAssetType::Controller).unwrap();
+        let yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+        let resources = &yaml[0]["spec"]["template"]["spec"]["containers"][0]["resources"];
+
+        assert_eq!(resources["limits"]["cpu"].as_str().unwrap(), "1500m");
+        assert_eq!(resources["limits"]["memory"].as_str().unwrap(), "2Gi");
+        assert!(resources["requests"]["cpu"].is_badvalue());
+        assert_eq!(resources["requests"]["memory"].as_str().unwrap(), "512Mi");
+    }
+}
+
+#[cfg(test)]
+mod dependency {
+    use std::path::PathBuf;
+    use yaml_rust::YamlLoader;
+    use crate::kubernetes::controllers::controller::KubeController;
+    use crate::kubernetes::controllers::dependency::KubeInitContainer;
+    use crate::kubernetes::template::controller::ControllerTmplBuilder;
+    use crate::kubernetes::io::output;
+    use crate::assets::loader::K8SAssetType;
+    use crate::docker::parser::DockerService;
+    use crate::confiture::config::ConfigDeployment;
+
+    #[test]
+    fn expect_to_render_init_containers() {
+        let dk = DockerService {
+            name: "api".to_owned(),
+            image: "api".to_owned(),
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(1),
+            ..Default::default()
+        };
+
+        let mut ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        ctrl.init_containers = vec![KubeInitContainer {
+            name: "wait-for-db".to_owned(),
+            image: "busybox:1.36".to_owned(),
+            command: vec!["sh".to_owned(), "-c".to_owned(), "until nc -z db-svc 5432; do sleep 2; done".to_owned()]
+        }];
+
+        let tmpl = output::render_component(&ControllerTmplBuilder {}, &ctrl, K8SAssetType::Controller).unwrap();
+        let yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+        let spec = &yaml[0]["spec"]["template"]["spec"];
+        let init = &spec["initContainers"][0];
+
+        assert_eq!(init["name"].as_str().unwrap(), "wait-for-db");
+        assert_eq!(init["image"].as_str().unwrap(), "busybox:1.36");
+        assert_eq!(init["command"][2].as_str().unwrap(), "until nc -z db-svc 5432; do sleep 2; done");
+        assert_eq!(spec["containers"][0]["name"].as_str().unwrap(), "api");
+    }
+
+    #[test]
+    fn expect_to_not_render_init_containers() {
+        let dk = DockerService {
+            name: "api".to_owned(),
+            image: "api".to_owned(),
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(1),
+            ..Default::default()
+        };
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        let tmpl = output::render_component(&ControllerTmplBuilder {}, &ctrl, K8SAssetType::Controller).unwrap();
+        let yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+
+        assert!(yaml[0]["spec"]["template"]["spec"]["initContainers"].is_badvalue());
+    }
+}
+
+#[cfg(test)]
+mod environment {
+    use std::collections::HashMap;
+    use yaml_rust::YamlLoader;
+    use crate::docker::parser::DockerService;
+    use crate::tests::{to_map, to_pairs};
+    use crate::kubernetes::builder::{get_basic_objects, Kube};
+    use crate::kubernetes::io::output;
+    use crate::kubernetes::template::controller::ControllerTmplBuilder;
+    use crate::kubernetes::template::environment::EnvTmplBuilder;
+    use crate::assets::loader::K8SAssetType;
+    use crate::confiture::config::{ConfigConfiture, ConfigDeployment, ConfigService};
+
+    // method use to build the kube objects of a service with sensitive variables
+    fn setup() -> Kube {
+        let dk = DockerService {
+            name: "db".to_owned(),
+            image: "postgres".to_owned(),
+            labels: to_map(&[("tier", "db")]),
+            environment: to_pairs(&[("POSTGRES_DB", "app"), ("POSTGRES_PASSWORD", "true")]),
+            ..Default::default()
+        };
+
+        let conf = ConfigConfiture {
+            name: "db".to_owned(),
+            skip: false,
+            deployment: ConfigDeployment {
+                replicas: Some(1),
+                ..Default::default()
+            },
+            service: ConfigService::default()
+        };
+
+        let mut map: HashMap<String, &ConfigConfiture> = HashMap::new();
+        map.insert("db".to_owned(), &conf);
+
+        get_basic_objects(&[dk], map, &[], &["*_PASSWORD".to_owned()]).remove(0)
+    }
+
+    #[test]
+    fn expect_to_render_env_references() {
+        let kube = setup();
+        let tmpl = output::render_component(&ControllerTmplBuilder {}, &kube.ctrl, K8SAssetType::Controller).unwrap();
+        let yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+        let container = &yaml[0]["spec"]["template"]["spec"]["containers"][0];
+
+        assert_eq!(container["envFrom"][0]["configMapRef"]["name"].as_str().unwrap(), "db-env");
+        assert_eq!(container["env"][0]["name"].as_str().unwrap(), "POSTGRES_PASSWORD");
+        assert_eq!(container["env"][0]["valueFrom"]["secretKeyRef"]["name"].as_str().unwrap(), "db-env");
+        assert_eq!(container["env"][0]["valueFrom"]["secretKeyRef"]["key"].as_str().unwrap(), "POSTGRES_PASSWORD");
+    }
+
+    #[test]
+    fn expect_to_render_config_map() {
+        let kube = setup();
+        let tmpl = output::render_component(&EnvTmplBuilder {}, &kube.config_map.unwrap(), K8SAssetType::ConfigMap).unwrap();
+        let yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+
+        assert_eq!(yaml[0]["kind"].as_str().unwrap(), "ConfigMap");
+        assert_eq!(yaml[0]["metadata"]["name"].as_str().unwrap(), "db-env");
+        assert_eq!(yaml[0]["data"]["POSTGRES_DB"].as_str().unwrap(), "app");
+        assert!(yaml[0]["data"]["POSTGRES_PASSWORD"].is_badvalue());
+    }
+
+    #[test]
+    fn expect_to_render_secret() {
+        let kube = setup();
+        let tmpl = output::render_component(&EnvTmplBuilder {}, &kube.secret.unwrap(), K8SAssetType::Secret).unwrap();
+        let yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+
+        assert_eq!(yaml[0]["kind"].as_str().unwrap(), "Secret");
+        assert_eq!(yaml[0]["type"].as_str().unwrap(), "Opaque");
+        // the value stays a string
+        assert_eq!(yaml[0]["stringData"]["POSTGRES_PASSWORD"].as_str().unwrap(), "true");
+    }
+}
+
+#[cfg(test)]
+mod secret {
+    use std::path::PathBuf;
+    use yaml_rust::YamlLoader;
+    use crate::kubernetes::controllers::controller::KubeController;
+    use crate::kubernetes::controllers::secret::KubeFileObject;
+    use crate::kubernetes::template::controller::ControllerTmplBuilder;
+    use crate::kubernetes::template::environment::EnvTmplBuilder;
+    use crate::kubernetes::io::output;
+    use crate::assets::loader::K8SAssetType;
+    use crate::docker::parser::DockerService;
+    use crate::docker::secret::{DockerFileObject, DockerFileReference, FileKind};
+    use crate::confiture::config::ConfigDeployment;
+    use crate::tests::to_map;
+
+    #[test]
+    fn expect_to_render_file_volumes() {
+        let dk = DockerService {
+            name: "api".to_owned(),
+            image: "api".to_owned(),
+            secrets: vec![DockerFileReference {
+                source: "api_key".to_owned(),
+                target: "/run/secrets/api_key".to_owned(),
+                uid: None,
+                gid: Some("1000".to_owned()),
+                mode: Some(0o440),
+                external_name: None
+            }],
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(1),
+            ..Default::default()
+        };
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        let tmpl = output::render_component(&ControllerTmplBuilder {}, &ctrl, K8SAssetType::Controller).unwrap();
+        let yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+        let spec = &yaml[0]["spec"]["template"]["spec"];
+        let mount = &spec["containers"][0]["volumeMounts"][0];
+        let volume = &spec["volumes"][0];
+
+        assert_eq!(mount["mountPath"].as_str().unwrap(), "/run/secrets/api_key");
+        assert_eq!(mount["subPath"].as_str().unwrap(), "api_key");
+        assert_eq!(volume["secret"]["secretName"].as_str().unwrap(), "api-key");
+        assert_eq!(volume["secret"]["items"][0]["key"].as_str().unwrap(), "api_key");
+        assert_eq!(volume["secret"]["items"][0]["mode"].as_i64().unwrap(), 0o440);
+        assert_eq!(spec["securityContext"]["fsGroup"].as_i64().unwrap(), 1000);
+    }
+
+    #[test]
+    fn expect_to_render_file_objects() {
+        let secret = KubeFileObject::new(&DockerFileObject {
+            name: "api_key".to_owned(),
+            kind: FileKind::Secret,
+            file: None,
+            data: b"s3cr3t".to_vec(),
+            external: false,
+            external_name: None
+        }, &to_map(&[("app.kubernetes.io/managed-by", "capoomobi")]));
+
+        let tmpl = output::render_component(&EnvTmplBuilder {}, &secret, K8SAssetType::Secret).unwrap();
+        let yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+
+        assert_eq!(yaml[0]["metadata"]["name"].as_str().unwrap(), "api-key");
+        assert_eq!(yaml[0]["data"]["api_key"].as_str().unwrap(), "czNjcjN0");
+        assert!(yaml[0]["stringData"].is_badvalue());
+        assert_eq!(yaml[0]["metadata"]["labels"]["app.kubernetes.io/managed-by"].as_str().unwrap(), "capoomobi");
+    }
+}
+
+#[cfg(test)]
+mod mount {
+    use std::path::PathBuf;
+    use std::collections::BTreeMap;
+    use yaml_rust::YamlLoader;
+    use crate::kubernetes::controllers::controller::KubeController;
+    use crate::kubernetes::controllers::mount::{ConfigMount, MountStrategy, get_mount_volumes};
+    use crate::kubernetes::template::controller::ControllerTmplBuilder;
+    use crate::kubernetes::io::output;
+    use crate::assets::loader::K8SAssetType;
+    use crate::docker::parser::DockerService;
+    use crate::docker::volume::{DockerVolume, VolumeKind};
+    use crate::confiture::config::ConfigDeployment;
+
+    #[test]
+    fn expect_to_render_mount_volumes() {
+        let dk = DockerService {
+            name: "cache".to_owned(),
+            image: "redis".to_owned(),
+            volumes: vec![
+                DockerVolume {
+                    kind: VolumeKind::Tmpfs,
+                    target: "/data".to_owned(),
+                    size: Some("1g".to_owned()),
+                    ..Default::default()
+                },
+                DockerVolume {
+                    kind: VolumeKind::Volume,
+                    target: "/scratch".to_owned(),
+                    ..Default::default()
+                },
+                DockerVolume {
+                    kind: VolumeKind::Bind,
+                    source: "/var/log".to_owned(),
+                    target: "/logs".to_owned(),
+                    ..Default::default()
+                }
+            ],
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(1),
+            mounts: vec![ConfigMount {
+                target: "/logs".to_owned(),
+                strategy: MountStrategy::HostPath,
+                size_limit: None
+            }],
+            ..Default::default()
+        };
+
+        let (mut volumes, mut mounts, _) = get_mount_volumes(&dk.name, &dk.volumes, &conf.mounts, &BTreeMap::new());
+        let mut ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        ctrl.volumes.append(&mut volumes);
+        ctrl.volume_mounts.append(&mut mounts);
+
+        let tmpl = output::render_component(&ControllerTmplBuilder {}, &ctrl, K8SAssetType::Controller).unwrap();
+        let yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+        let spec = &yaml[0]["spec"]["template"]["spec"];
+        let volumes = &spec["volumes"];
+
+        assert_eq!(spec["containers"][0]["volumeMounts"][0]["mountPath"].as_str().unwrap(), "/data");
+        assert_eq!(volumes[0]["emptyDir"]["medium"].as_str().unwrap(), "Memory");
+        assert_eq!(volumes[0]["emptyDir"]["sizeLimit"].as_str().unwrap(), "1Gi");
+        assert!(volumes[1]["emptyDir"].as_hash().unwrap().is_empty());
+        assert_eq!(volumes[2]["hostPath"]["path"].as_str().unwrap(), "/var/log");
+    }
+}
+
+#[cfg(test)]
+mod command {
+    use std::path::PathBuf;
+    use yaml_rust::YamlLoader;
+    use crate::kubernetes::controllers::controller::KubeController;
+    use crate::kubernetes::template::controller::ControllerTmplBuilder;
+    use crate::kubernetes::io::output;
+    use crate::assets::loader::K8SAssetType;
+    use crate::docker::parser::DockerService;
+    use crate::confiture::config::ConfigDeployment;
+
+    fn render(dk: DockerService) -> yaml_rust::Yaml {
+        let conf = ConfigDeployment {
+            replicas: Some(1),
+            ..Default::default()
+        };
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        let tmpl = output::render_component(&ControllerTmplBuilder {}, &ctrl, K8SAssetType::Controller).unwrap();
+        YamlLoader::load_from_str(&tmpl).unwrap().remove(0)
+    }
+
+    #[test]
+    fn expect_to_render_command_and_args() {
+        let yaml = render(DockerService {
+            name: "api".to_owned(),
+            image: "node".to_owned(),
+            entrypoint: vec!["sh".to_owned(), "-c".to_owned()],
+            command: vec!["node server.js".to_owned(), "--port=8080".to_owned()],
+            working_dir: Some("/usr/src/app".to_owned()),
+            user: Some("0:0".to_owned()),
+            tty: true,
+            stdin_open: true,
+            ..Default::default()
+        });
+
+        let container = &yaml["spec"]["template"]["spec"]["containers"][0];
+        assert_eq!(container["command"][1].as_str().unwrap(), "-c");
+        assert_eq!(container["args"][0].as_str().unwrap(), "node server.js");
+        assert_eq!(container["args"][1].as_str().unwrap(), "--port=8080");
+        assert_eq!(container["workingDir"].as_str().unwrap(), "/usr/src/app");
+        assert_eq!(container["securityContext"]["runAsUser"].as_i64().unwrap(), 0);
+        assert_eq!(container["securityContext"]["runAsGroup"].as_i64().unwrap(), 0);
+        assert!(container["tty"].as_bool().unwrap());
+        assert!(container["stdin"].as_bool().unwrap());
+    }
+
+    #[test]
+    fn expect_to_omit_empty_fields() {
+        let yaml = render(DockerService {
+            name: "api".to_owned(),
+            image: "node".to_owned(),
+            ..Default::default()
+        });
+
+        let container = &yaml["spec"]["template"]["spec"]["containers"][0];
+        assert!(container["command"].is_badvalue());
+        assert!(container["args"].is_badvalue());
+        assert!(container["workingDir"].is_badvalue());
+        assert!(container["securityContext"].is_badvalue());
+        assert!(container["tty"].is_badvalue());
+    }
+}
+
+#[cfg(test)]
+mod security {
+    use std::path::PathBuf;
+    use yaml_rust::YamlLoader;
+    use crate::kubernetes::controllers::controller::KubeController;
+    use crate::kubernetes::template::controller::ControllerTmplBuilder;
+    use crate::kubernetes::io::output;
+    use crate::assets::loader::K8SAssetType;
+    use crate::docker::parser::DockerService;
+    use crate::docker::security::DockerSecurity;
+    use crate::confiture::config::ConfigDeployment;
+
+    #[test]
+    fn expect_to_render_security_context() {
+        let dk = DockerService {
+            name: "agent".to_owned(),
+            image: "prom/node-exporter".to_owned(),
+            security: DockerSecurity {
+                privileged: true,
+                read_only: true,
+                cap_add: vec!["SYS_TIME".to_owned()],
+                cap_drop: vec!["ALL".to_owned()],
+                security_opt: vec![
+                    "no-new-privileges:true".to_owned(),
+                    "seccomp:unconfined".to_owned(),
+                    "apparmor:node-exporter".to_owned(),
+                    "label:user:system_u".to_owned()
+                ],
+                host_network: true,
+                host_pid: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(1),
+            ..Default::default()
+        };
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        let tmpl = output::render_component(&ControllerTmplBuilder {}, &ctrl, K8SAssetType::Controller).unwrap();
+        let yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+        let pod = &yaml[0]["spec"]["template"]["spec"];
+        let ctx = &pod["containers"][0]["securityContext"];
+
+        assert!(ctx["privileged"].as_bool().unwrap());
+        assert!(ctx["readOnlyRootFilesystem"].as_bool().unwrap());
+        assert!(!ctx["allowPrivilegeEscalation"].as_bool().unwrap());
+        assert_eq!(ctx["capabilities"]["add"][0].as_str().unwrap(), "SYS_TIME");
+        assert_eq!(ctx["capabilities"]["drop"][0].as_str().unwrap(), "ALL");
+        assert_eq!(ctx["seccompProfile"]["type"].as_str().unwrap(), "Unconfined");
+        assert!(ctx["seccompProfile"]["localhostProfile"].is_badvalue());
+        assert_eq!(ctx["appArmorProfile"]["type"].as_str().unwrap(), "Localhost");
+        assert_eq!(ctx["appArmorProfile"]["localhostProfile"].as_str().unwrap(), "node-exporter");
+        assert_eq!(ctx["seLinuxOptions"]["user"].as_str().unwrap(), "system_u");
+        assert!(pod["hostNetwork"].as_bool().unwrap());
+        assert_eq!(pod["dnsPolicy"].as_str().unwrap(), "ClusterFirstWithHostNet");
+        assert!(pod["hostPID"].as_bool().unwrap());
+        assert!(pod["hostIPC"].is_badvalue());
+    }
+}
+
+#[cfg(test)]
+mod host {
+    use std::path::PathBuf;
+    use yaml_rust::YamlLoader;
+    use crate::kubernetes::controllers::controller::KubeController;
+    use crate::kubernetes::template::controller::ControllerTmplBuilder;
+    use crate::kubernetes::io::output;
+    use crate::assets::loader::K8SAssetType;
+    use crate::docker::parser::DockerService;
+    use crate::docker::host::{DockerHost, DockerExtraHost};
+    use crate::confiture::config::ConfigDeployment;
+
+    #[test]
+    fn expect_to_render_host_fields() {
+        let dk = DockerService {
+            name: "legacy".to_owned(),
+            image: "legacy".to_owned(),
+            host: DockerHost {
+                hostname: Some("legacy".to_owned()),
+                domainname: Some("apps".to_owned()),
+                extra_hosts: vec![DockerExtraHost { hostname: "db".to_owned(), ip: "10.0.0.1".to_owned() }],
+                dns: vec!["10.0.0.53".to_owned()],
+                dns_search: vec!["corp.local".to_owned()],
+                dns_opt: vec!["ndots:1".to_owned()]
+            },
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(1),
+            replace_cluster_dns: Some(true),
+            ..Default::default()
+        };
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        let tmpl = output::render_component(&ControllerTmplBuilder {}, &ctrl, K8SAssetType::Controller).unwrap();
+        let yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+        let pod = &yaml[0]["spec"]["template"]["spec"];
+
+        assert_eq!(pod["hostname"].as_str().unwrap(), "legacy");
+        assert_eq!(pod["subdomain"].as_str().unwrap(), "apps");
+        assert_eq!(pod["hostAliases"][0]["ip"].as_str().unwrap(), "10.0.0.1");
+        assert_eq!(pod["hostAliases"][0]["hostnames"][0].as_str().unwrap(), "db");
+        assert_eq!(pod["dnsPolicy"].as_str().unwrap(), "None");
+        assert_eq!(pod["dnsConfig"]["nameservers"][0].as_str().unwrap(), "10.0.0.53");
+        assert_eq!(pod["dnsConfig"]["searches"][0].as_str().unwrap(), "corp.local");
+        assert_eq!(pod["dnsConfig"]["options"][0]["name"].as_str().unwrap(), "ndots");
+        assert_eq!(pod["dnsConfig"]["options"][0]["value"].as_str().unwrap(), "1");
+    }
+}
+
+#[cfg(test)]
+mod rollout {
+    use std::path::PathBuf;
+    use yaml_rust::YamlLoader;
+    use crate::kubernetes::controllers::controller::KubeController;
+    use crate::kubernetes::template::controller::ControllerTmplBuilder;
+    use crate::kubernetes::io::output;
+    use crate::assets::loader::K8SAssetType;
+    use crate::docker::parser::DockerService;
+    use crate::docker::deploy::{DockerDeploy, DockerUpdateConfig, UpdateOrder};
+    use crate::confiture::config::ConfigDeployment;
+
+    #[test]
+    fn expect_to_render_rollout_settings() {
+        let dk = DockerService {
+            name: "api".to_owned(),
+            image: "api".to_owned(),
+            deploy: DockerDeploy {
+                update_config: Some(DockerUpdateConfig {
+                    delay: Some(30),
+                    order: UpdateOrder::StartFirst,
+                    ..Default::default()
+                }),
+                stop_grace_period: Some(0),
+                stop_signal: Some("SIGINT".to_owned()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(2),
+            ..Default::default()
+        };
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        let tmpl = output::render_component(&ControllerTmplBuilder {}, &ctrl, K8SAssetType::Controller).unwrap();
+        let yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+        let spec = &yaml[0]["spec"];
+        let pod = &spec["template"]["spec"];
+
+        assert_eq!(spec["strategy"]["type"].as_str().unwrap(), "RollingUpdate");
+        assert_eq!(spec["strategy"]["rollingUpdate"]["maxSurge"].as_i64().unwrap(), 1);
+        assert_eq!(spec["strategy"]["rollingUpdate"]["maxUnavailable"].as_i64().unwrap(), 0);
+        assert_eq!(spec["minReadySeconds"].as_i64().unwrap(), 30);
+        assert_eq!(pod["terminationGracePeriodSeconds"].as_i64().unwrap(), 0);
+        assert_eq!(pod["containers"][0]["lifecycle"]["preStop"]["exec"]["command"][2].as_str().unwrap(), "kill -INT 1");
+    }
+}
+
+#[cfg(test)]
+mod placement {
+    use std::path::PathBuf;
+    use yaml_rust::YamlLoader;
+    use crate::kubernetes::controllers::controller::KubeController;
+    use crate::kubernetes::controllers::placement::{AffinityKind, PodAntiAffinity};
+    use crate::kubernetes::template::controller::ControllerTmplBuilder;
+    use crate::kubernetes::io::output;
+    use crate::assets::loader::K8SAssetType;
+    use crate::docker::parser::DockerService;
+    use crate::docker::deploy::{DockerDeploy, DockerPlacement, DockerConstraint, ConstraintOperator};
+    use crate::confiture::config::ConfigDeployment;
+
+    #[test]
+    fn expect_to_render_placement() {
+        let dk = DockerService {
+            name: "api".to_owned(),
+            image: "api".to_owned(),
+            deploy: DockerDeploy {
+                placement: DockerPlacement {
+                    constraints: vec![
+                        DockerConstraint {
+                            field: "node.labels.zone".to_owned(),
+                            operator: ConstraintOperator::Equal,
+                            value: "eu-1".to_owned()
+                        },
+                        DockerConstraint {
+                            field: "node.role".to_owned(),
+                            operator: ConstraintOperator::Equal,
+                            value: "worker".to_owned()
+                        }
+                    ],
+                    preferences: vec!["node.labels.rack".to_owned()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let conf = ConfigDeployment {
+            replicas: Some(3),
+            anti_affinity: Some(PodAntiAffinity {
+                kind: AffinityKind::Preferred,
+                topology_key: "kubernetes.io/hostname".to_owned(),
+                selector: Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let ctrl = KubeController::new(dk, &conf, &[], &PathBuf::new()).unwrap();
+        let tmpl = output::render_component(&ControllerTmplBuilder {}, &ctrl, K8SAssetType::Controller).unwrap();
+        let yaml = YamlLoader::load_from_str(&tmpl).unwrap();
+        let pod = &yaml[0]["spec"]["template"]["spec"];
+        let node = &pod["affinity"]["nodeAffinity"]["requiredDuringSchedulingIgnoredDuringExecution"];
+        let anti = &pod["affinity"]["podAntiAffinity"]["preferredDuringSchedulingIgnoredDuringExecution"][0];
+        let spread = &pod["topologySpreadConstraints"][0];
+
+        assert_eq!(pod["nodeSelector"]["zone"].as_str().unwrap(), "eu-1");
+        assert_eq!(node["nodeSelectorTerms"][0]["matchExpressions"][0]["operator"].as_str().unwrap(), "DoesNotExist");
+        assert!(node["nodeSelectorTerms"][0]["matchExpressions"][0]["values"].is_badvalue());
+        assert_eq!(anti["weight"].as_i64().unwrap(), 100);
+        assert_eq!(anti["podAffinityTerm"]["labelSelector"]["matchLabels"]["app.kubernetes.io/name"].as_str().unwrap(), "api");
+        assert_eq!(spread["topologyKey"].as_str().unwrap(), "rack");
+        assert_eq!(spread["labelSelector"]["matchLabels"]["app.kubernetes.io/name"].as_str().unwrap(), "api");
+    }
+}